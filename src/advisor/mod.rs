@@ -3,6 +3,7 @@
 //! Provides intelligent model upgrade recommendations based on task complexity,
 //! failure rates, and utility optimization.
 
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
 /// Available Ollama models with their characteristics
@@ -32,6 +33,7 @@ pub struct TaskMetrics {
     pub tool_failures: u32,
     pub plan_steps: u32,
     pub total_iterations: u32,
+    pub stagnant_iterations: u32,
 }
 
 /// Upgrade recommendation
@@ -44,6 +46,15 @@ pub struct UpgradeRecommendation {
     pub utility_gain: f64,
 }
 
+/// A mid-task switch to a stronger model
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ModelEscalation {
+    pub from_model: String,
+    pub to_model: String,
+    pub iteration: usize,
+    pub reason: String,
+}
+
 /// Model Advisor system
 pub struct ModelAdvisor {
     current_model: String,
@@ -251,6 +262,10 @@ impl ModelAdvisor {
             reasons.push("Task requires many iterations".to_string());
         }
 
+        if metrics.stagnant_iterations > 1 {
+            reasons.push(format!("No progress for {} iterations", metrics.stagnant_iterations));
+        }
+
         if reasons.is_empty() {
             "Task complexity exceeds current model capabilities".to_string()
         } else {
@@ -263,9 +278,50 @@ impl ModelAdvisor {
         metrics.json_failures > 2
             || metrics.tool_failures > 3
             || metrics.plan_steps > 5
+            || metrics.stagnant_iterations > 1
             || self.calculate_complexity(metrics) > 0.6
     }
 
+    /// Tags of the tiers above the current model, nearest first
+    pub fn upgrade_path(&self) -> Vec<String> {
+        let mut path = Vec::new();
+        let mut tier = ModelTier::from_tag(&self.current_model).and_then(|t| t.next());
+        while let Some(t) = tier {
+            if let Some(spec) = self.models.get(&t) {
+                path.push(spec.tag.clone());
+            }
+            tier = t.next();
+        }
+        path
+    }
+
+    /// Choose a model to escalate to mid-task
+    ///
+    /// Escalates when an upgrade is recommended or a trigger condition is
+    /// met, to the nearest higher tier among the `installed` models.
+    pub fn select_escalation(&self, metrics: &TaskMetrics, installed: &[String]) -> Option<UpgradeRecommendation> {
+        if let Some(recommendation) = self.recommend_upgrade(metrics) {
+            if installed.contains(&recommendation.to_model) {
+                return Some(recommendation);
+            }
+        } else if !self.check_triggers(metrics) {
+            return None;
+        }
+
+        let current_spec = self.models.get(&ModelTier::from_tag(&self.current_model)?)?;
+        let to_model = self.upgrade_path().into_iter().find(|tag| installed.contains(tag))?;
+        let next_spec = self.models.get(&ModelTier::from_tag(&to_model)?)?;
+        let utility_gain = self.calculate_utility(next_spec, metrics) - self.calculate_utility(current_spec, metrics);
+
+        Some(UpgradeRecommendation {
+            from_model: self.current_model.clone(),
+            to_model,
+            confidence: (utility_gain / 0.5).clamp(0.0, 1.0),
+            reason: self.generate_reason(metrics),
+            utility_gain,
+        })
+    }
+
     /// Get model specifications
     pub fn get_model_spec(&self, tier: &ModelTier) -> Option<&ModelSpec> {
         self.models.get(tier)
@@ -308,6 +364,7 @@ mod tests {
             tool_failures: 0,
             plan_steps: 2,
             total_iterations: 2,
+            stagnant_iterations: 0,
         };
         let complexity = advisor.calculate_complexity(&metrics);
         assert!(complexity < 0.2);
@@ -322,6 +379,7 @@ mod tests {
             tool_failures: 4,
             plan_steps: 8,
             total_iterations: 10,
+            stagnant_iterations: 0,
         };
         let complexity = advisor.calculate_complexity(&metrics);
         assert!(complexity > 0.5);
@@ -336,6 +394,7 @@ mod tests {
             tool_failures: 0,
             plan_steps: 2,
             total_iterations: 2,
+            stagnant_iterations: 0,
         };
         assert!(advisor.recommend_upgrade(&metrics).is_none());
     }
@@ -354,6 +413,7 @@ mod tests {
             tool_failures: 4,
             plan_steps: 8,
             total_iterations: 10,
+            stagnant_iterations: 0,
         };
         let recommendation = advisor.recommend_upgrade(&metrics);
         assert!(recommendation.is_some());
@@ -375,6 +435,7 @@ mod tests {
             tool_failures: 0,
             plan_steps: 2,
             total_iterations: 2,
+            stagnant_iterations: 0,
         };
         assert!(advisor.check_triggers(&metrics_trigger));
 
@@ -384,10 +445,33 @@ mod tests {
             tool_failures: 0,
             plan_steps: 2,
             total_iterations: 2,
+            stagnant_iterations: 0,
         };
         assert!(!advisor.check_triggers(&metrics_no_trigger));
     }
 
+    #[test]
+    fn test_select_escalation_skips_missing_tiers() {
+        let advisor = ModelAdvisor::new("qwen2.5:7b-instruct".to_string());
+        assert_eq!(advisor.upgrade_path(), vec!["qwen2.5:14b-instruct", "qwen2.5:32b-instruct"]);
+
+        let stalled = TaskMetrics {
+            stagnant_iterations: 3,
+            ..TaskMetrics::default()
+        };
+        let installed = vec!["qwen2.5:7b-instruct".to_string(), "qwen2.5:32b-instruct".to_string()];
+        let escalation = advisor.select_escalation(&stalled, &installed).unwrap();
+        assert_eq!(escalation.to_model, "qwen2.5:32b-instruct");
+        assert!(escalation.reason.contains("No progress for 3 iterations"));
+
+        // Nothing to escalate to, or no reason to
+        assert!(advisor.select_escalation(&stalled, &installed[..1]).is_none());
+        assert!(advisor.select_escalation(&TaskMetrics::default(), &installed).is_none());
+        assert!(ModelAdvisor::new("qwen2.5:32b-instruct".to_string())
+            .select_escalation(&stalled, &installed)
+            .is_none());
+    }
+
     #[test]
     fn test_custom_cost_sensitivity() {
         let advisor = ModelAdvisor::new("qwen2.5:7b-instruct".to_string())
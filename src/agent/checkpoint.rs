@@ -0,0 +1,240 @@
+//! Run checkpoints for crash-safe resume
+//!
+//! After every iteration the orchestrator writes a `Checkpoint` with its
+//! state, memory, goal tree and budget to `<state_dir>/runs/<run-id>.json`
+//! (`paths.state_dir` in the config, `~/.ollamabuddy` by default).
+//! `ollamabuddy resume [run-id]` and the REPL `/resume` load it back and
+//! continue the same goal from the last completed iteration.
+
+use crate::agent::AgentState;
+use crate::budget::BudgetSnapshot;
+use crate::errors::{AgentError, Result};
+use crate::planning::GoalTree;
+use crate::types::MemoryEntry;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Serialized snapshot of an agent run
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Checkpoint {
+    /// Run identifier (also the file stem)
+    pub run_id: String,
+
+    /// Original task
+    pub task: String,
+
+    /// Model used for the run
+    pub model: String,
+
+    /// Working directory of the run
+    pub working_dir: PathBuf,
+
+    /// Last completed iteration
+    pub iteration: usize,
+
+    /// State machine state after the last iteration
+    pub state: AgentState,
+
+    /// Conversation memory
+    pub memory: Vec<MemoryEntry>,
+
+    /// Planner goal tree, if planning was initialized
+    pub goal_tree: Option<GoalTree>,
+
+    /// Iteration budget
+    pub budget: BudgetSnapshot,
+
+    /// Unix timestamp of the first checkpoint
+    pub created_at: u64,
+
+    /// Unix timestamp of the latest checkpoint
+    pub updated_at: u64,
+}
+
+impl Checkpoint {
+    /// Create an empty checkpoint for a new run
+    pub fn new(task: impl Into<String>, model: impl Into<String>, working_dir: PathBuf) -> Self {
+        let now = now_secs();
+        Self {
+            run_id: new_run_id(),
+            task: task.into(),
+            model: model.into(),
+            working_dir,
+            iteration: 0,
+            state: AgentState::Init,
+            memory: Vec::new(),
+            goal_tree: None,
+            budget: BudgetSnapshot::default(),
+            created_at: now,
+            updated_at: now,
+        }
+    }
+
+    /// Whether the run ended (successfully or not) and cannot be resumed
+    pub fn is_finished(&self) -> bool {
+        matches!(self.state, AgentState::Final | AgentState::Error)
+    }
+}
+
+/// On-disk store of run checkpoints
+#[derive(Debug, Clone)]
+pub struct CheckpointStore {
+    dir: PathBuf,
+}
+
+impl CheckpointStore {
+    /// Create store rooted at `dir` (created on first save)
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    /// Store directory
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Write checkpoint, replacing any previous one for the same run
+    ///
+    /// Writes to a temporary file first so a crash mid-write never leaves a
+    /// truncated checkpoint behind.
+    pub fn save(&self, checkpoint: &Checkpoint) -> Result<PathBuf> {
+        fs::create_dir_all(&self.dir)?;
+        let path = self.path_for(&checkpoint.run_id)?;
+        let tmp = path.with_extension("json.tmp");
+        fs::write(&tmp, serde_json::to_vec_pretty(checkpoint)?)?;
+        fs::rename(&tmp, &path)?;
+        Ok(path)
+    }
+
+    /// Load checkpoint by run id
+    pub fn load(&self, run_id: &str) -> Result<Checkpoint> {
+        let path = self.path_for(run_id)?;
+        if !path.exists() {
+            return Err(AgentError::Generic(format!("No checkpoint for run '{}'", run_id)));
+        }
+        let data = fs::read(&path)?;
+        Ok(serde_json::from_slice(&data)?)
+    }
+
+    /// All checkpoints, most recently updated first
+    ///
+    /// Unreadable files are skipped.
+    pub fn list(&self) -> Result<Vec<Checkpoint>> {
+        if !self.dir.exists() {
+            return Ok(Vec::new());
+        }
+
+        let mut checkpoints: Vec<Checkpoint> = fs::read_dir(&self.dir)?
+            .filter_map(|entry| entry.ok())
+            .map(|entry| entry.path())
+            .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+            .filter_map(|path| fs::read(path).ok())
+            .filter_map(|data| serde_json::from_slice(&data).ok())
+            .collect();
+
+        checkpoints.sort_by_key(|c| std::cmp::Reverse(c.updated_at));
+        Ok(checkpoints)
+    }
+
+    /// Most recently updated run that has not finished
+    pub fn latest_resumable(&self) -> Result<Option<Checkpoint>> {
+        Ok(self.list()?.into_iter().find(|c| !c.is_finished()))
+    }
+
+    /// Delete a run's checkpoint
+    pub fn remove(&self, run_id: &str) -> Result<()> {
+        let path = self.path_for(run_id)?;
+        if path.exists() {
+            fs::remove_file(path)?;
+        }
+        Ok(())
+    }
+
+    fn path_for(&self, run_id: &str) -> Result<PathBuf> {
+        // Run ids come from the command line; keep them inside the store
+        if run_id.is_empty()
+            || !run_id.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+        {
+            return Err(AgentError::Generic(format!("Invalid run id '{}'", run_id)));
+        }
+        Ok(self.dir.join(format!("{}.json", run_id)))
+    }
+}
+
+/// Sortable, human-readable run id: `YYYYMMDD-HHMMSS-xxxx`
+fn new_run_id() -> String {
+    let suffix = uuid::Uuid::new_v4().simple().to_string();
+    format!("{}-{}", chrono::Local::now().format("%Y%m%d-%H%M%S"), &suffix[..4])
+}
+
+fn now_secs() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap()
+        .as_secs()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn sample(task: &str) -> Checkpoint {
+        let mut checkpoint = Checkpoint::new(task, "qwen2.5:7b-instruct", PathBuf::from("/tmp"));
+        checkpoint.state = AgentState::Planning;
+        checkpoint.iteration = 3;
+        checkpoint.memory.push(MemoryEntry::UserGoal {
+            goal: task.to_string(),
+            timestamp: 0,
+        });
+        checkpoint.goal_tree = Some(GoalTree::new(task.to_string(), 0.4));
+        checkpoint
+    }
+
+    #[test]
+    fn test_save_and_load_roundtrip() {
+        let dir = TempDir::new().unwrap();
+        let store = CheckpointStore::new(dir.path());
+        let checkpoint = sample("count lines");
+
+        store.save(&checkpoint).unwrap();
+        let loaded = store.load(&checkpoint.run_id).unwrap();
+
+        assert_eq!(loaded.task, "count lines");
+        assert_eq!(loaded.iteration, 3);
+        assert_eq!(loaded.state, AgentState::Planning);
+        assert_eq!(loaded.memory.len(), 1);
+        assert_eq!(loaded.goal_tree.unwrap().nodes.len(), 1);
+    }
+
+    #[test]
+    fn test_latest_resumable_skips_finished_runs() {
+        let dir = TempDir::new().unwrap();
+        let store = CheckpointStore::new(dir.path());
+
+        let mut older = sample("older");
+        older.run_id = "run-a".to_string();
+        older.updated_at = 100;
+        let mut finished = sample("finished");
+        finished.run_id = "run-b".to_string();
+        finished.updated_at = 200;
+        finished.state = AgentState::Final;
+
+        store.save(&older).unwrap();
+        store.save(&finished).unwrap();
+
+        assert_eq!(store.list().unwrap().len(), 2);
+        let latest = store.latest_resumable().unwrap().unwrap();
+        assert_eq!(latest.run_id, "run-a");
+    }
+
+    #[test]
+    fn test_rejects_path_like_run_ids() {
+        let dir = TempDir::new().unwrap();
+        let store = CheckpointStore::new(dir.path());
+        assert!(store.load("../config").is_err());
+        assert!(store.load("").is_err());
+        assert!(store.load("missing-run").is_err());
+    }
+}
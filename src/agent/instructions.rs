@@ -0,0 +1,143 @@
+//! Project instruction files merged into the system prompt
+//!
+//! Team conventions live in `OLLAMABUDDY.md` (or `.ollamabuddy/instructions.md`)
+//! in the working directory or any of its ancestors, plus a user-level
+//! `~/.ollamabuddy/instructions.md`. All files found are appended to the
+//! system prompt, most general first, so instructions closer to the working
+//! directory come last and take precedence.
+
+use crate::errors::Result;
+use std::path::{Path, PathBuf};
+
+/// Instruction file names looked up in each directory, in order
+pub const PROJECT_INSTRUCTION_FILES: &[&str] = &["OLLAMABUDDY.md", ".ollamabuddy/instructions.md"];
+
+/// User-level instruction file, relative to the home directory
+pub const USER_INSTRUCTION_FILE: &str = ".ollamabuddy/instructions.md";
+
+/// Maximum bytes taken from a single file, to protect the context window
+pub const MAX_INSTRUCTION_BYTES: usize = 16 * 1024;
+
+/// One loaded instruction file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InstructionFile {
+    /// Where the instructions came from
+    pub path: PathBuf,
+
+    /// File contents (trimmed, possibly truncated)
+    pub content: String,
+}
+
+/// Find instruction files for `working_dir`
+///
+/// Returns the user-level file (from `user_file`, if given) followed by
+/// project files from the filesystem root down to `working_dir`. Empty files
+/// are skipped and a file reachable by several routes is loaded once.
+pub fn discover(working_dir: &Path, user_file: Option<&Path>) -> Result<Vec<InstructionFile>> {
+    let mut candidates: Vec<PathBuf> = user_file.map(Path::to_path_buf).into_iter().collect();
+
+    let mut dirs: Vec<&Path> = working_dir.ancestors().collect();
+    dirs.reverse();
+    for dir in dirs {
+        candidates.extend(PROJECT_INSTRUCTION_FILES.iter().map(|name| dir.join(name)));
+    }
+
+    let mut seen = Vec::new();
+    let mut files = Vec::new();
+    for path in candidates {
+        if !path.is_file() {
+            continue;
+        }
+        let canonical = path.canonicalize()?;
+        if seen.contains(&canonical) {
+            continue;
+        }
+        seen.push(canonical);
+
+        let content = truncate(std::fs::read_to_string(&path)?.trim());
+        if !content.is_empty() {
+            files.push(InstructionFile { path, content });
+        }
+    }
+    Ok(files)
+}
+
+/// Discover instruction files using the default user-level location
+pub fn load(working_dir: &Path) -> Result<Vec<InstructionFile>> {
+    let user_file = dirs::home_dir().map(|home| home.join(USER_INSTRUCTION_FILE));
+    discover(working_dir, user_file.as_deref())
+}
+
+/// Append instruction files to a system prompt
+pub fn merge_into_prompt(system_prompt: &str, files: &[InstructionFile]) -> String {
+    if files.is_empty() {
+        return system_prompt.to_string();
+    }
+
+    let mut prompt = format!(
+        "{}\n\nPROJECT INSTRUCTIONS (follow these; later sections override earlier ones):",
+        system_prompt
+    );
+    for file in files {
+        prompt.push_str(&format!("\n\n--- {} ---\n{}", file.path.display(), file.content));
+    }
+    prompt
+}
+
+fn truncate(content: &str) -> String {
+    if content.len() <= MAX_INSTRUCTION_BYTES {
+        return content.to_string();
+    }
+    let mut end = MAX_INSTRUCTION_BYTES;
+    while !content.is_char_boundary(end) {
+        end -= 1;
+    }
+    format!("{}\n[truncated]", &content[..end])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_discover_orders_general_to_specific() {
+        let temp = TempDir::new().unwrap();
+        let project = temp.path().join("project");
+        let nested = project.join("crates").join("core");
+        std::fs::create_dir_all(nested.join(".ollamabuddy")).unwrap();
+        std::fs::write(temp.path().join("user.md"), "Be concise.").unwrap();
+        std::fs::write(project.join("OLLAMABUDDY.md"), "Use cargo nextest.\n").unwrap();
+        std::fs::write(nested.join(".ollamabuddy/instructions.md"), "No unsafe code.").unwrap();
+        std::fs::write(nested.join("OLLAMABUDDY.md"), "   \n").unwrap();
+
+        let files = discover(&nested, Some(&temp.path().join("user.md"))).unwrap();
+        let contents: Vec<&str> = files.iter().map(|f| f.content.as_str()).collect();
+        assert_eq!(contents, vec!["Be concise.", "Use cargo nextest.", "No unsafe code."]);
+
+        // The user file is not loaded twice when it is also a project file
+        let files = discover(&project, Some(&project.join("OLLAMABUDDY.md"))).unwrap();
+        assert_eq!(files.len(), 1);
+    }
+
+    #[test]
+    fn test_merge_into_prompt() {
+        assert_eq!(merge_into_prompt("SYSTEM", &[]), "SYSTEM");
+
+        let files = vec![InstructionFile {
+            path: PathBuf::from("/repo/OLLAMABUDDY.md"),
+            content: "Run tests with make test.".to_string(),
+        }];
+        let prompt = merge_into_prompt("SYSTEM", &files);
+        assert!(prompt.starts_with("SYSTEM\n\nPROJECT INSTRUCTIONS"));
+        assert!(prompt.ends_with("--- /repo/OLLAMABUDDY.md ---\nRun tests with make test."));
+    }
+
+    #[test]
+    fn test_truncates_large_files() {
+        let large = "é".repeat(MAX_INSTRUCTION_BYTES);
+        let truncated = truncate(&large);
+        assert!(truncated.len() <= MAX_INSTRUCTION_BYTES + "\n[truncated]".len());
+        assert!(truncated.ends_with("[truncated]"));
+    }
+}
@@ -2,7 +2,8 @@
 //! 
 //! Maintains a fixed-size circular buffer of memory entries with:
 //! - Maximum 100 entries (bounded storage)
-//! - FIFO eviction when full
+//! - FIFO eviction in batches when full (a leading system prompt and goal
+//!   are kept), so history is append-only between evictions
 //! - Fast access to recent entries
 //! - Token tracking for context management
 
@@ -13,6 +14,9 @@ use std::collections::VecDeque;
 /// Maximum number of memory entries (bounded storage guarantee)
 pub const MAX_MEMORY_ENTRIES: usize = 100;
 
+/// Fraction of capacity evicted at once when memory is full
+const EVICTION_BATCH_DIVISOR: usize = 4;
+
 /// Memory manager with bounded storage
 #[derive(Debug, Clone)]
 pub struct MemoryManager {
@@ -21,6 +25,9 @@ pub struct MemoryManager {
     
     /// Maximum allowed entries
     max_entries: usize,
+
+    /// Times entries were removed or replaced rather than appended
+    compactions: u64,
 }
 
 impl MemoryManager {
@@ -34,30 +41,60 @@ impl MemoryManager {
         Self {
             entries: VecDeque::with_capacity(max_entries),
             max_entries,
+            compactions: 0,
         }
     }
 
-    /// Add entry to memory, evicting oldest if at capacity
+    /// Add entry to memory, evicting the oldest entries if at capacity
+    ///
+    /// A leading system prompt and the goal after it are never evicted, so
+    /// the prompt keeps its instructions and a stable prefix. A quarter of
+    /// the capacity is evicted at once, so the rendered history only changes
+    /// at these points and the model's prompt cache stays valid in between.
     /// 
     /// # Complexity
     /// - O(1) amortized - VecDeque push_back
-    /// - O(1) eviction - VecDeque pop_front
+    /// - O(n) eviction, once per batch - VecDeque drain near the front
     pub fn add(&mut self, entry: MemoryEntry) {
         // Evict oldest if at capacity
         if self.entries.len() >= self.max_entries {
-            self.entries.pop_front();
+            let pinned = self.pinned_prefix().min(self.entries.len() - 1);
+            let batch = (self.max_entries / EVICTION_BATCH_DIVISOR)
+                .max(1)
+                .min(self.entries.len() - pinned);
+            self.entries.drain(pinned..pinned + batch);
+            self.compactions += 1;
         }
 
         self.entries.push_back(entry);
     }
 
+    /// Number of times entries were evicted, replaced or cleared
+    ///
+    /// While this is unchanged, entries have only been appended.
+    pub fn compactions(&self) -> u64 {
+        self.compactions
+    }
+
+    /// Number of leading entries exempt from eviction
+    fn pinned_prefix(&self) -> usize {
+        match (self.entries.front(), self.entries.get(1)) {
+            (Some(MemoryEntry::SystemPrompt { .. }), Some(MemoryEntry::UserGoal { .. })) => 2,
+            (Some(MemoryEntry::SystemPrompt { .. }), _) => 1,
+            _ => 0,
+        }
+    }
+
     /// Get reference to all entries
     pub fn entries(&self) -> &VecDeque<MemoryEntry> {
         &self.entries
     }
 
     /// Get mutable reference to all entries
+    ///
+    /// Counts as a compaction, since callers may rewrite history.
     pub fn entries_mut(&mut self) -> &mut VecDeque<MemoryEntry> {
+        self.compactions += 1;
         &mut self.entries
     }
 
@@ -96,6 +133,7 @@ impl MemoryManager {
     /// Clear all entries
     pub fn clear(&mut self) {
         self.entries.clear();
+        self.compactions += 1;
     }
 
     /// Calculate total token count for all entries
@@ -122,6 +160,7 @@ impl MemoryManager {
 
         self.entries.clear();
         self.entries.extend(new_entries);
+        self.compactions += 1;
         Ok(())
     }
 
@@ -204,6 +243,42 @@ mod tests {
         assert_eq!(memory.entries()[2].timestamp(), 4);
     }
 
+    #[test]
+    fn test_eviction_keeps_system_prompt_and_goal() {
+        let mut memory = MemoryManager::with_capacity(4);
+
+        memory.add(MemoryEntry::SystemPrompt { content: "System".to_string() });
+        for i in 1..=5 {
+            memory.add(create_test_entry(i));
+        }
+
+        // Entry 1 is the goal; entries 2 and 3 were evicted
+        assert_eq!(memory.len(), 4);
+        assert_eq!(memory.system_prompt(), Some("System"));
+        let timestamps: Vec<u64> = memory.entries().iter().skip(1).map(|e| e.timestamp()).collect();
+        assert_eq!(timestamps, vec![1, 4, 5]);
+    }
+
+    #[test]
+    fn test_eviction_in_batches() {
+        let mut memory = MemoryManager::with_capacity(8);
+        memory.add(MemoryEntry::SystemPrompt { content: "System".to_string() });
+        for i in 1..=7 {
+            memory.add(create_test_entry(i));
+        }
+        assert_eq!(memory.compactions(), 0);
+
+        // Full: entries 2 and 3 go at once, leaving room to append again
+        memory.add(create_test_entry(8));
+        assert_eq!(memory.compactions(), 1);
+        assert_eq!(memory.len(), 7);
+        memory.add(create_test_entry(9));
+        assert_eq!(memory.compactions(), 1);
+
+        let timestamps: Vec<u64> = memory.entries().iter().skip(1).map(|e| e.timestamp()).collect();
+        assert_eq!(timestamps, vec![1, 4, 5, 6, 7, 8, 9]);
+    }
+
     #[test]
     fn test_last_n() {
         let mut memory = MemoryManager::new();
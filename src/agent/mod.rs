@@ -5,8 +5,13 @@
 pub mod state;
 pub mod memory;
 pub mod orchestrator;
+pub mod checkpoint;
+pub mod instructions;
+pub mod templates;
 
 // Re-export commonly used types
 pub use state::{AgentState, StateEvent};
 pub use memory::{MemoryManager, MAX_MEMORY_ENTRIES};
-pub use orchestrator::AgentOrchestrator;
+pub use orchestrator::{AgentOrchestrator, AskPolicy, ModelRole, ModelRoutes, TurnPrompt};
+pub use checkpoint::{Checkpoint, CheckpointStore};
+pub use templates::PromptTemplate;
@@ -7,12 +7,15 @@
 //! - Streaming communication
 //! - Tool execution (interface for PRD 2)
 
-use crate::agent::{AgentState, StateEvent, MemoryManager};
-use crate::context::ContextCompressor;
+use crate::agent::{AgentState, StateEvent, MemoryManager, Checkpoint, CheckpointStore, PromptTemplate};
+use crate::budget::{BudgetSnapshot, RunLimits};
+use crate::context::{ContextCompressor, TokenCounter};
 use crate::errors::Result;
-use crate::streaming::{OllamaClient, JsonParser};
+use crate::streaming::{GenerateOptions, OllamaClient, JsonParser};
+use crate::telemetry::{TelemetryCollector, TelemetryEvent};
 use crate::types::MemoryEntry;
 use crate::planning::AdvancedPlanner;
+use std::fmt;
 
 /// Agent orchestrator configuration
 #[derive(Debug, Clone)]
@@ -28,6 +31,76 @@ pub struct AgentConfig {
     
     /// Enable verbose logging
     pub verbose: bool,
+
+    /// How to handle `ask` messages from the model
+    pub ask_policy: AskPolicy,
+
+    /// Time, token and tool-runtime limits for each run
+    pub limits: RunLimits,
+
+    /// Switch to a stronger installed model when the run gets stuck
+    pub auto_upgrade: bool,
+
+    /// Models for planning and summarisation (`model` runs tool-calling turns)
+    pub routes: ModelRoutes,
+
+    /// How long Ollama keeps models loaded between turns
+    pub keep_alive: String,
+}
+
+/// Part of a run a model is used for
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ModelRole {
+    /// Goal decomposition and re-planning
+    Planner,
+
+    /// Tool-calling turns of the agent loop
+    Executor,
+
+    /// Summaries of older history during context compression
+    Summary,
+}
+
+impl ModelRole {
+    /// All roles in display order
+    pub const ALL: [ModelRole; 3] = [ModelRole::Planner, ModelRole::Executor, ModelRole::Summary];
+}
+
+impl fmt::Display for ModelRole {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ModelRole::Planner => write!(f, "Planner"),
+            ModelRole::Executor => write!(f, "Executor"),
+            ModelRole::Summary => write!(f, "Summary"),
+        }
+    }
+}
+
+/// Per-role model overrides
+///
+/// Without a planner model, planning uses the executor model. Without a
+/// summary model, context compression stays rule-based.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ModelRoutes {
+    /// Model for goal decomposition
+    pub planner: Option<String>,
+
+    /// Model for compression summaries
+    pub summary: Option<String>,
+}
+
+/// Policy for answering questions the model asks mid-task
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub enum AskPolicy {
+    /// Prompt the user and wait for an answer
+    #[default]
+    Interactive,
+
+    /// Abort the task (non-interactive runs)
+    Fail,
+
+    /// Reply with a fixed answer without prompting
+    AutoAnswer(String),
 }
 
 impl Default for AgentConfig {
@@ -37,10 +110,53 @@ impl Default for AgentConfig {
             model: "qwen2.5:7b-instruct".to_string(),
             max_iterations: 50,
             verbose: false,
+            ask_policy: AskPolicy::default(),
+            limits: RunLimits::default(),
+            auto_upgrade: false,
+            routes: ModelRoutes::default(),
+            keep_alive: crate::streaming::DEFAULT_KEEP_ALIVE.to_string(),
         }
     }
 }
 
+/// Prompt for the next agent turn
+#[derive(Debug, Clone, PartialEq)]
+pub struct TurnPrompt {
+    /// Text to send: the full prompt, or only what was added since the
+    /// last reply when continuing its context
+    pub text: String,
+
+    /// Context returned by the last reply, when continuing it
+    pub context: Option<Vec<i64>>,
+
+    /// Estimated size of the whole prompt the model sees, continued
+    /// context included
+    pub prompt_tokens: usize,
+}
+
+impl TurnPrompt {
+    /// Request options carrying the continued context
+    pub fn options(&self) -> GenerateOptions {
+        GenerateOptions {
+            context: self.context.clone(),
+            ..GenerateOptions::default()
+        }
+    }
+}
+
+/// Conversation held in Ollama's KV cache after the last reply
+#[derive(Debug, Clone)]
+struct ModelSession {
+    /// `context` returned with the reply
+    context: Vec<i64>,
+
+    /// Memory entries already part of that context
+    entries: usize,
+
+    /// `MemoryManager::compactions` when the reply was received
+    compactions: u64,
+}
+
 /// Main agent orchestrator
 pub struct AgentOrchestrator {
     /// Current state
@@ -52,8 +168,17 @@ pub struct AgentOrchestrator {
     /// Context compressor
     compressor: ContextCompressor,
     
-    /// Ollama client
+    /// Ollama client for tool-calling turns
     client: OllamaClient,
+
+    /// Ollama client for planning
+    planner_client: OllamaClient,
+
+    /// Ollama client for compression summaries, if routed
+    summary_client: Option<OllamaClient>,
+
+    /// Collector for per-role model telemetry
+    telemetry: TelemetryCollector,
     
     /// JSON parser
     parser: JsonParser,
@@ -82,12 +207,28 @@ pub struct AgentOrchestrator {
     
     /// Working memory for active context
     working_memory: crate::memory::WorkingMemory,
+
+    /// Checkpoint store and the run being checkpointed, if enabled
+    checkpointing: Option<(CheckpointStore, Checkpoint)>,
+
+    /// Layout of memory entries in the model prompt
+    prompt_template: PromptTemplate,
+
+    /// Conversation the next turn can continue instead of resending
+    session: Option<ModelSession>,
 }
 
 impl AgentOrchestrator {
     /// Create new agent orchestrator
     pub fn new(config: AgentConfig) -> Result<Self> {
-        let client = OllamaClient::with_config(&config.ollama_url, &config.model)?;
+        let client = Self::model_client(&config, &config.model)?;
+        let planner_client = match &config.routes.planner {
+            Some(model) => Self::model_client(&config, model)?,
+            None => client.clone(),
+        };
+        let summary_client = config.routes.summary.as_deref()
+            .map(|model| Self::model_client(&config, model))
+            .transpose()?;
         
         
         // Initialize memory system (PRD 6)
@@ -102,11 +243,15 @@ impl AgentOrchestrator {
             crate::memory::ExperienceTracker::new()
         ));
         let working_memory = crate::memory::WorkingMemory::new();
+        let prompt_template = PromptTemplate::for_model(&config.model);
         Ok(Self {
             state: AgentState::Init,
             memory: MemoryManager::new(),
             compressor: ContextCompressor::new(),
             client,
+            planner_client,
+            summary_client,
+            telemetry: TelemetryCollector::new(),
             parser: JsonParser::new(),
             config,
             iterations: 0,
@@ -116,9 +261,17 @@ impl AgentOrchestrator {
             experience_tracker,
             working_memory,
             planner: None,
+            checkpointing: None,
+            prompt_template,
+            session: None,
         })
     }
 
+    /// Ollama client for `model` with the configured URL and keep-alive
+    fn model_client(config: &AgentConfig, model: &str) -> Result<OllamaClient> {
+        Ok(OllamaClient::with_config(&config.ollama_url, model)?.with_keep_alive(&config.keep_alive))
+    }
+
     /// Create orchestrator with default configuration
     pub fn with_defaults() -> Result<Self> {
         Self::new(AgentConfig::default())
@@ -129,6 +282,11 @@ impl AgentOrchestrator {
         self.state
     }
 
+    /// Get configuration
+    pub fn config(&self) -> &AgentConfig {
+        &self.config
+    }
+
     /// Get memory manager reference
     pub fn memory(&self) -> &MemoryManager {
         &self.memory
@@ -166,8 +324,32 @@ impl AgentOrchestrator {
         self.memory.add(MemoryEntry::UserGoal { goal, timestamp });
     }
 
+    /// Add an approved plan to memory
+    pub fn add_plan(&mut self, steps: Vec<String>, reasoning: Option<String>) {
+        let timestamp = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_secs();
+
+        self.memory.add(MemoryEntry::Plan { steps, reasoning, timestamp });
+    }
+
+    /// Add an error the model should see (and react to) on its next turn
+    pub fn add_error(&mut self, message: String, recoverable: bool) {
+        let timestamp = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_secs();
+
+        self.memory.add(MemoryEntry::ErrorEntry { message, recoverable, timestamp });
+    }
+
     /// Check if compression is needed and compress if necessary
-    pub fn maybe_compress(&mut self) -> Result<()> {
+    ///
+    /// With a summary model routed, older history is replaced by a model
+    /// summary; otherwise, or if summarising fails, the rule-based
+    /// compressor is used.
+    pub async fn maybe_compress(&mut self) -> Result<()> {
         let entries = self.memory.to_vec();
         
         if self.compressor.needs_compression(&entries) {
@@ -176,7 +358,10 @@ impl AgentOrchestrator {
                 eprintln!("[COMPRESS] Starting compression: {} tokens", before_tokens);
             }
             
-            let compressed = self.compressor.compress(&entries)?;
+            let compressed = match self.summarize_history(&entries).await {
+                Some(summarized) => summarized,
+                None => self.compressor.compress(&entries)?,
+            };
             
             if self.config.verbose {
                 let stats = self.compressor.compression_stats(&entries, &compressed);
@@ -194,53 +379,124 @@ impl AgentOrchestrator {
         Ok(())
     }
 
+    /// Replace older history with a summary from the summary model
+    ///
+    /// Keeps the system prompt, goal and last 3 entries verbatim. Returns
+    /// `None` without a summary model, when the call fails, or when the
+    /// result would exceed the compression target.
+    async fn summarize_history(&self, entries: &[MemoryEntry]) -> Option<Vec<MemoryEntry>> {
+        let client = self.summary_client.as_ref()?;
+
+        let (older, recent) = entries.split_at(entries.len().saturating_sub(3));
+        let system_prompt = older.iter().position(|e| matches!(e, MemoryEntry::SystemPrompt { .. }));
+        let user_goal = older.iter().position(|e| matches!(e, MemoryEntry::UserGoal { .. }));
+        let history: Vec<&MemoryEntry> = older.iter()
+            .enumerate()
+            .filter(|(i, _)| Some(*i) != system_prompt && Some(*i) != user_goal)
+            .map(|(_, e)| e)
+            .collect();
+        if history.is_empty() {
+            return None;
+        }
+
+        let prompt = format!(
+            "Summarise the following steps of an agent working on a task in at most 150 words. \
+            Keep file paths, commands, results and errors that later steps may need.\n\n{}\n\nSUMMARY:",
+            self.prompt_template.render(history)
+        );
+        let generation = client.generate(prompt).await.ok()?;
+        if let Some(stats) = &generation.stats {
+            self.telemetry.record_generation(stats);
+        }
+        self.telemetry.record(TelemetryEvent::ModelCall {
+            role: ModelRole::Summary,
+            model: client.model().to_string(),
+            tokens: generation.tokens,
+            duration_ms: generation.duration.as_millis() as u64,
+            timestamp: std::time::Instant::now(),
+        });
+
+        let summary = crate::streaming::split_thinking(&generation.text).answer;
+        if summary.trim().is_empty() {
+            return None;
+        }
+
+        let mut summarized: Vec<MemoryEntry> = [system_prompt, user_goal]
+            .into_iter()
+            .flatten()
+            .map(|i| older[i].clone())
+            .collect();
+        summarized.push(MemoryEntry::SystemPrompt {
+            content: format!("Summary of earlier steps:\n{}", summary.trim()),
+        });
+        summarized.extend_from_slice(recent);
+
+        let tokens: usize = summarized.iter().map(|e| e.estimate_tokens()).sum();
+        (tokens <= crate::context::TARGET_AFTER_COMPRESSION).then_some(summarized)
+    }
+
     /// Build prompt from current memory
     pub fn build_prompt(&self) -> String {
-        let mut parts = Vec::new();
-        
-        for entry in self.memory.entries() {
-            match entry {
-                MemoryEntry::SystemPrompt { content } => {
-                    parts.push(format!("SYSTEM: {}", content));
-                }
-                MemoryEntry::UserGoal { goal, .. } => {
-                    parts.push(format!("GOAL: {}", goal));
-                }
-                MemoryEntry::Plan { steps, reasoning, .. } => {
-                    parts.push("PLAN:".to_string());
-                    for (i, step) in steps.iter().enumerate() {
-                        parts.push(format!("  {}. {}", i + 1, step));
-                    }
-                    if let Some(r) = reasoning {
-                        parts.push(format!("  Reasoning: {}", r));
-                    }
-                }
-                MemoryEntry::ToolCall { tool, args, .. } => {
-                    parts.push(format!("TOOL_CALL: {} with {:?}", tool, args));
-                }
-                MemoryEntry::ToolResult { tool, output, success, .. } => {
-                    let status = if *success { "SUCCESS" } else { "FAILED" };
-                    parts.push(format!("TOOL_RESULT [{}] {}: {}", status, tool, output));
-                }
-                MemoryEntry::Question { question, .. } => {
-                    parts.push(format!("QUESTION: {}", question));
-                }
-                MemoryEntry::UserResponse { response, .. } => {
-                    parts.push(format!("USER_RESPONSE: {}", response));
-                }
-                MemoryEntry::FinalResult { result, summary, .. } => {
-                    parts.push(format!("FINAL_RESULT: {}", result));
-                    if let Some(s) = summary {
-                        parts.push(format!("  Summary: {}", s));
-                    }
-                }
-                MemoryEntry::ErrorEntry { message, .. } => {
-                    parts.push(format!("ERROR: {}", message));
-                }
+        self.prompt_template.render(self.memory.entries())
+    }
+
+    /// Build the prompt for the next agent turn
+    ///
+    /// Memory is append-only between compactions, so after a reply the next
+    /// turn continues that reply's context and sends only the entries added
+    /// since, and Ollama evaluates just those. Tool calls and questions are
+    /// left out: the model's own reply already holds them. The full prompt
+    /// is sent on the first turn, after memory was compacted, or when the
+    /// continued context would outgrow the context window.
+    pub fn turn_prompt(&self) -> TurnPrompt {
+        let session = self.session.as_ref().filter(|session| {
+            session.compactions == self.memory.compactions() && session.entries <= self.memory.len()
+        });
+
+        if let Some(session) = session {
+            let added = self.memory.entries().range(session.entries..).filter(|entry| {
+                !matches!(entry, MemoryEntry::ToolCall { .. } | MemoryEntry::Question { .. })
+            });
+            let text = self.prompt_template.render_continuation(added);
+            let prompt_tokens = session.context.len() + TokenCounter::new().estimate(&text);
+            if !text.is_empty() && prompt_tokens <= crate::context::MAX_CONTEXT_TOKENS {
+                return TurnPrompt {
+                    text,
+                    context: Some(session.context.clone()),
+                    prompt_tokens,
+                };
             }
         }
-        
-        parts.join("\n\n")
+
+        let text = self.build_prompt();
+        TurnPrompt {
+            prompt_tokens: TokenCounter::new().estimate(&text),
+            text,
+            context: None,
+        }
+    }
+
+    /// Remember the context returned with a reply so the next turn continues it
+    ///
+    /// Call once the reply is complete, before adding the tool results it
+    /// leads to. `None` (e.g. the reply was cut off) makes the next turn
+    /// send the full prompt.
+    pub fn continue_from_reply(&mut self, context: Option<Vec<i64>>) {
+        self.session = context.map(|context| ModelSession {
+            context,
+            entries: self.memory.len(),
+            compactions: self.memory.compactions(),
+        });
+    }
+
+    /// Prompt template used by `build_prompt`
+    pub fn prompt_template(&self) -> &PromptTemplate {
+        &self.prompt_template
+    }
+
+    /// Replace the prompt template (e.g. with a user-provided one)
+    pub fn set_prompt_template(&mut self, template: PromptTemplate) {
+        self.prompt_template = template;
     }
 
     /// Get total token count in current memory
@@ -263,11 +519,145 @@ impl AgentOrchestrator {
         self.iterations = 0;
     }
 
+    /// Get iteration counter (last checkpointed iteration after a resume)
+    pub fn iterations(&self) -> usize {
+        self.iterations
+    }
+
+    /// Start writing a checkpoint for `run` after every iteration
+    pub fn enable_checkpoints(&mut self, store: CheckpointStore, run: Checkpoint) {
+        self.checkpointing = Some((store, run));
+    }
+
+    /// Id of the run being checkpointed
+    pub fn run_id(&self) -> Option<&str> {
+        self.checkpointing.as_ref().map(|(_, run)| run.run_id.as_str())
+    }
+
+    /// Id of the checkpointed run, unless its last checkpoint is finished
+    ///
+    /// Uses the same predicate as resuming, so it is only `Some` when
+    /// `resume <id>` would accept the run.
+    pub fn resumable_run_id(&self) -> Option<&str> {
+        self.checkpointing
+            .as_ref()
+            .filter(|(_, run)| !run.is_finished())
+            .map(|(_, run)| run.run_id.as_str())
+    }
+
+    /// Iteration budget saved with the run being checkpointed
+    pub fn checkpoint_budget(&self) -> Option<BudgetSnapshot> {
+        self.checkpointing.as_ref().map(|(_, run)| run.budget)
+    }
+
+    /// Record the current iteration budget in the next checkpoint
+    ///
+    /// No-op unless checkpoints were enabled.
+    pub fn set_checkpoint_budget(&mut self, budget: BudgetSnapshot) {
+        if let Some((_, run)) = self.checkpointing.as_mut() {
+            run.budget = budget;
+        }
+    }
+
+    /// Write a checkpoint of the current state, memory and goal tree
+    ///
+    /// No-op unless checkpoints were enabled.
+    pub fn save_checkpoint(&mut self, iteration: usize) -> Result<()> {
+        self.iterations = iteration;
+
+        let state = self.state;
+        let memory = self.memory.to_vec();
+        let goal_tree = self.planner.as_ref().and_then(|p| p.goal_tree()).cloned();
+
+        let Some((store, run)) = self.checkpointing.as_mut() else {
+            return Ok(());
+        };
+
+        run.iteration = iteration;
+        run.state = state;
+        run.memory = memory;
+        run.goal_tree = goal_tree;
+        run.budget.used_iterations = iteration;
+        run.updated_at = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_secs();
+
+        store.save(run)?;
+        Ok(())
+    }
+
+    /// Restore state, memory, iteration count and goal tree from a checkpoint
+    ///
+    /// Checkpoints are written between iterations, so any non-terminal state
+    /// resumes in `Planning`. The planner is rebuilt from the saved goal tree
+    /// without another LLM decomposition.
+    pub fn restore_checkpoint(&mut self, checkpoint: &Checkpoint) -> Result<()> {
+        self.memory.replace_all(checkpoint.memory.clone())?;
+        self.session = None;
+        self.iterations = checkpoint.iteration;
+        self.state = if checkpoint.is_finished() {
+            checkpoint.state
+        } else {
+            AgentState::Planning
+        };
+
+        self.planner = checkpoint.goal_tree.clone().map(|tree| {
+            let mut planner = AdvancedPlanner::new();
+            planner.set_client(self.planner_client.clone());
+            planner.set_telemetry(self.telemetry.clone());
+            planner.restore(tree);
+            planner
+        });
+
+        self.set_goal(checkpoint.task.clone());
+        Ok(())
+    }
+
+    /// Switch the model used for the rest of the run
+    ///
+    /// Memory, state and the prompt template are kept, so the new model
+    /// continues where the previous one left off. Planning follows the
+    /// switch unless a planner model is routed.
+    pub fn set_model(&mut self, model: &str) -> Result<()> {
+        self.client = Self::model_client(&self.config, model)?;
+        self.config.model = model.to_string();
+        // Another model cannot continue this one's context
+        self.session = None;
+        if self.config.routes.planner.is_none() {
+            self.planner_client = self.client.clone();
+            if let Some(planner) = self.planner.as_mut() {
+                planner.set_client(self.planner_client.clone());
+            }
+        }
+        if let Some((_, run)) = self.checkpointing.as_mut() {
+            run.model = model.to_string();
+        }
+        Ok(())
+    }
+
     /// Get Ollama client reference
     pub fn client(&self) -> &OllamaClient {
         &self.client
     }
 
+    /// Model serving `role`, if any
+    pub fn model_for(&self, role: ModelRole) -> Option<&str> {
+        match role {
+            ModelRole::Planner => Some(self.planner_client.model()),
+            ModelRole::Executor => Some(self.client.model()),
+            ModelRole::Summary => self.summary_client.as_ref().map(|c| c.model()),
+        }
+    }
+
+    /// Share a telemetry collector for planner and summary model calls
+    pub fn set_telemetry(&mut self, telemetry: TelemetryCollector) {
+        if let Some(planner) = self.planner.as_mut() {
+            planner.set_telemetry(telemetry.clone());
+        }
+        self.telemetry = telemetry;
+    }
+
     /// Get JSON parser reference
     pub fn parser(&self) -> &JsonParser {
         &self.parser
@@ -284,8 +674,9 @@ impl AgentOrchestrator {
     pub async fn initialize_planning(&mut self, goal: &str) -> Result<()> {
         let mut planner = AdvancedPlanner::new();
 
-        // Pass Ollama client to planner for LLM-based reasoning
-        planner.set_client(self.client.clone());
+        // Pass the planner's Ollama client for LLM-based reasoning
+        planner.set_client(self.planner_client.clone());
+        planner.set_telemetry(self.telemetry.clone());
 
         // Initialize planning with LLM (this will take 2-5 seconds for actual thinking)
         planner.initialize(goal, &[]).await?;
@@ -801,6 +1192,7 @@ impl AgentOrchestrator {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::collections::HashMap;
 
     #[test]
     fn test_orchestrator_creation() {
@@ -840,6 +1232,156 @@ mod tests {
         assert!(orch.memory().user_goal().is_some());
     }
 
+    #[test]
+    fn test_turn_prompt_continues_reply_context() {
+        let mut orch = AgentOrchestrator::with_defaults().unwrap();
+        assert_eq!(orch.client().keep_alive(), crate::streaming::DEFAULT_KEEP_ALIVE);
+        orch.add_system_prompt("You are a helpful assistant".to_string());
+        orch.add_user_goal("List files".to_string());
+
+        let first = orch.turn_prompt();
+        assert_eq!(first.text, orch.build_prompt());
+        assert_eq!(first.context, None);
+
+        // The reply asked for a tool; only its result is new to the model
+        orch.continue_from_reply(Some(vec![1, 2, 3]));
+        orch.memory_mut().add(MemoryEntry::ToolCall {
+            tool: "list_dir".to_string(),
+            args: HashMap::from([("path".to_string(), serde_json::json!("src-dir"))]),
+            timestamp: 1,
+        });
+        orch.memory_mut().add(MemoryEntry::ToolResult {
+            tool: "list_dir".to_string(),
+            output: "main.rs".to_string(),
+            success: true,
+            duration_ms: 1,
+            timestamp: 1,
+        });
+        let next = orch.turn_prompt();
+        assert_eq!(next.context, Some(vec![1, 2, 3]));
+        assert!(next.text.contains("main.rs"));
+        assert!(!next.text.contains("List files") && !next.text.contains("src-dir"));
+        assert_eq!(next.prompt_tokens, 3 + TokenCounter::new().estimate(&next.text));
+        assert!(orch.build_prompt().starts_with(&first.text));
+
+        // Compaction rewrites history, so the full prompt is sent again
+        let entries = orch.memory().to_vec();
+        orch.memory_mut().replace_all(entries).unwrap();
+        assert_eq!(orch.turn_prompt().context, None);
+
+        orch.continue_from_reply(Some(vec![4]));
+        orch.set_model("qwen2.5:14b-instruct").unwrap();
+        assert_eq!(orch.turn_prompt().text, orch.build_prompt());
+    }
+
+    #[test]
+    fn test_set_model_keeps_memory() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let store = CheckpointStore::new(dir.path());
+
+        let mut orch = AgentOrchestrator::with_defaults().unwrap();
+        let run = Checkpoint::new("Goal", "qwen2.5:7b-instruct", std::path::PathBuf::from("."));
+        let run_id = run.run_id.clone();
+        orch.enable_checkpoints(store.clone(), run);
+        orch.add_user_goal("Goal".to_string());
+
+        orch.set_model("qwen2.5:14b-instruct").unwrap();
+        assert_eq!(orch.config().model, "qwen2.5:14b-instruct");
+        assert_eq!(orch.client().model(), "qwen2.5:14b-instruct");
+        assert_eq!(orch.memory().len(), 1);
+
+        // A resumed run continues with the escalated model
+        orch.save_checkpoint(1).unwrap();
+        assert_eq!(store.load(&run_id).unwrap().model, "qwen2.5:14b-instruct");
+    }
+
+    #[test]
+    fn test_model_routes() {
+        let mut orch = AgentOrchestrator::with_defaults().unwrap();
+        assert_eq!(orch.model_for(ModelRole::Planner), Some("qwen2.5:7b-instruct"));
+        assert_eq!(orch.model_for(ModelRole::Summary), None);
+
+        // Without a planner route, planning follows the executor
+        orch.set_model("qwen2.5:14b-instruct").unwrap();
+        assert_eq!(orch.model_for(ModelRole::Planner), Some("qwen2.5:14b-instruct"));
+
+        let mut routed = AgentOrchestrator::new(AgentConfig {
+            model: "qwen2.5:3b-instruct".to_string(),
+            routes: ModelRoutes {
+                planner: Some("qwen2.5:32b-instruct".to_string()),
+                summary: Some("qwen2.5:0.5b-instruct".to_string()),
+            },
+            ..AgentConfig::default()
+        }).unwrap();
+        routed.set_model("qwen2.5:7b-instruct").unwrap();
+        assert_eq!(routed.model_for(ModelRole::Planner), Some("qwen2.5:32b-instruct"));
+        assert_eq!(routed.model_for(ModelRole::Executor), Some("qwen2.5:7b-instruct"));
+        assert_eq!(routed.model_for(ModelRole::Summary), Some("qwen2.5:0.5b-instruct"));
+    }
+
+    #[tokio::test]
+    async fn test_compression_falls_back_without_summary() {
+        // Nothing listens on the discard port, so the summary call fails
+        let mut orch = AgentOrchestrator::new(AgentConfig {
+            ollama_url: "http://127.0.0.1:9".to_string(),
+            routes: ModelRoutes {
+                summary: Some("qwen2.5:0.5b-instruct".to_string()),
+                ..ModelRoutes::default()
+            },
+            ..AgentConfig::default()
+        }).unwrap();
+        orch.add_system_prompt("System".to_string());
+        orch.add_user_goal("Goal".to_string());
+        for i in 0..10 {
+            orch.memory_mut().add(MemoryEntry::ToolResult {
+                tool: "read_file".to_string(),
+                output: "line\n".repeat(600),
+                success: true,
+                duration_ms: 1,
+                timestamp: i,
+            });
+        }
+        assert!(orch.token_count() >= crate::context::COMPRESS_THRESHOLD);
+
+        orch.maybe_compress().await.unwrap();
+        assert!(orch.token_count() <= crate::context::TARGET_AFTER_COMPRESSION);
+        assert!(orch.memory().system_prompt().is_some());
+        assert!(orch.telemetry.get_stats().model_usage.is_empty());
+    }
+
+    #[test]
+    fn test_checkpoint_save_and_restore() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let store = CheckpointStore::new(dir.path());
+
+        let mut orch = AgentOrchestrator::with_defaults().unwrap();
+        let run = Checkpoint::new("Goal", "test-model", std::path::PathBuf::from("."));
+        let run_id = run.run_id.clone();
+        orch.enable_checkpoints(store.clone(), run);
+        orch.add_system_prompt("System".to_string());
+        orch.add_user_goal("Goal".to_string());
+        orch.transition(StateEvent::StartSession).unwrap();
+        orch.transition(StateEvent::ToolCall).unwrap();
+        orch.save_checkpoint(2).unwrap();
+        assert_eq!(orch.resumable_run_id(), Some(run_id.as_str()));
+
+        let checkpoint = store.load(&run_id).unwrap();
+        assert_eq!(checkpoint.iteration, 2);
+        assert_eq!(checkpoint.state, AgentState::Executing);
+
+        let mut resumed = AgentOrchestrator::with_defaults().unwrap();
+        resumed.restore_checkpoint(&checkpoint).unwrap();
+        assert_eq!(resumed.iterations(), 2);
+        assert_eq!(resumed.state(), AgentState::Planning);
+        assert_eq!(resumed.memory().len(), 2);
+        assert!(resumed.build_prompt().contains("GOAL: Goal"));
+
+        // A run that ended in Error cannot be resumed, so no hint is offered
+        orch.transition(StateEvent::ToolFailure).unwrap();
+        orch.save_checkpoint(3).unwrap();
+        assert_eq!(orch.resumable_run_id(), None);
+    }
+
     #[test]
     fn test_build_prompt() {
         let mut orch = AgentOrchestrator::with_defaults().unwrap();
@@ -0,0 +1,464 @@
+//! Per-model-family prompt templates
+//!
+//! A `PromptTemplate` controls how each `MemoryEntry` variant is rendered by
+//! `AgentOrchestrator::build_prompt`, plus optional few-shot tool-call
+//! examples placed after the system prompt. Templates are chosen by matching
+//! the model tag against each template's `models` patterns (`*` wildcards,
+//! case-insensitive).
+//!
+//! Built-in templates cover qwen, llama3, mistral and deepseek-r1. Users can
+//! override or add families with TOML files in `~/.ollamabuddy/templates/`;
+//! a user file with the same `name` as a built-in replaces it, and user
+//! templates are matched before built-ins. Any field left out of a file
+//! keeps the default layout.
+//!
+//! Placeholders such as `{goal}` are substituted in a single pass, so text
+//! inside memory entries is never re-expanded.
+
+use crate::errors::{AgentError, Result};
+use crate::types::MemoryEntry;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+/// How memory entries are laid out in the model prompt
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PromptTemplate {
+    /// Template name (defaults to the file stem for user templates)
+    pub name: String,
+
+    /// Model tag patterns this template applies to, e.g. `qwen*`
+    pub models: Vec<String>,
+
+    /// Text placed between rendered parts
+    pub separator: String,
+
+    /// `{content}`
+    pub system: String,
+
+    /// `{goal}`
+    pub goal: String,
+
+    /// Plan header (no placeholders)
+    pub plan: String,
+
+    /// `{n}`, `{step}`
+    pub plan_step: String,
+
+    /// `{reasoning}`
+    pub plan_reasoning: String,
+
+    /// `{tool}`, `{args}` (JSON)
+    pub tool_call: String,
+
+    /// `{status}` (SUCCESS/FAILED), `{tool}`, `{output}`
+    pub tool_result: String,
+
+    /// `{question}`
+    pub question: String,
+
+    /// `{response}`
+    pub user_response: String,
+
+    /// `{result}`
+    pub final_result: String,
+
+    /// `{summary}`
+    pub final_summary: String,
+
+    /// `{message}`
+    pub error: String,
+
+    /// Header placed before the few-shot examples
+    pub examples_header: String,
+
+    /// Few-shot examples, rendered after the first system prompt
+    pub examples: Vec<String>,
+}
+
+impl Default for PromptTemplate {
+    /// The original `SYSTEM:/GOAL:/TOOL_RESULT` layout, without examples
+    fn default() -> Self {
+        Self {
+            name: "default".to_string(),
+            models: Vec::new(),
+            separator: "\n\n".to_string(),
+            system: "SYSTEM: {content}".to_string(),
+            goal: "GOAL: {goal}".to_string(),
+            plan: "PLAN:".to_string(),
+            plan_step: "  {n}. {step}".to_string(),
+            plan_reasoning: "  Reasoning: {reasoning}".to_string(),
+            tool_call: "TOOL_CALL: {tool} with {args}".to_string(),
+            tool_result: "TOOL_RESULT [{status}] {tool}: {output}".to_string(),
+            question: "QUESTION: {question}".to_string(),
+            user_response: "USER_RESPONSE: {response}".to_string(),
+            final_result: "FINAL_RESULT: {result}".to_string(),
+            final_summary: "  Summary: {summary}".to_string(),
+            error: "ERROR: {message}".to_string(),
+            examples_header: "EXAMPLES:".to_string(),
+            examples: Vec::new(),
+        }
+    }
+}
+
+impl PromptTemplate {
+    /// Built-in templates, most specific first
+    pub fn builtins() -> Vec<Self> {
+        let tool_call_examples = vec![
+            "GOAL: How many lines are in main.rs?\n\
+             Reasoning: I need the file's line count.\n\
+             {\"type\": \"tool_call\", \"tool\": \"run_command\", \"args\": {\"command\": \"wc\", \"args\": [\"-l\", \"main.rs\"]}}"
+                .to_string(),
+            "TOOL_RESULT [SUCCESS] run_command: 42 main.rs\n\
+             Reasoning: The count is in the output.\n\
+             {\"type\": \"final\", \"result\": \"main.rs has 42 lines\"}"
+                .to_string(),
+        ];
+
+        vec![
+            Self {
+                name: "deepseek-r1".to_string(),
+                models: vec!["deepseek-r1*".to_string()],
+                // Reasoning models do best zero-shot with clearly marked sections
+                system: "## Instructions\n{content}".to_string(),
+                goal: "## Task\n{goal}".to_string(),
+                plan: "## Plan".to_string(),
+                tool_call: "## Tool call\n{tool} {args}".to_string(),
+                tool_result: "## Tool result ({tool}, {status})\n{output}".to_string(),
+                question: "## Your question\n{question}".to_string(),
+                user_response: "## User answer\n{response}".to_string(),
+                final_result: "## Final result\n{result}".to_string(),
+                error: "## Error\n{message}".to_string(),
+                ..Self::default()
+            },
+            Self {
+                name: "llama3".to_string(),
+                models: vec!["llama3*".to_string()],
+                system: "### System\n{content}".to_string(),
+                goal: "### Goal\n{goal}".to_string(),
+                plan: "### Plan".to_string(),
+                tool_call: "### Tool call\n{tool} {args}".to_string(),
+                tool_result: "### Tool result ({tool}, {status})\n{output}".to_string(),
+                question: "### Question\n{question}".to_string(),
+                user_response: "### User response\n{response}".to_string(),
+                final_result: "### Final result\n{result}".to_string(),
+                error: "### Error\n{message}".to_string(),
+                examples_header: "### Examples of correct responses".to_string(),
+                examples: tool_call_examples.clone(),
+                ..Self::default()
+            },
+            Self {
+                name: "mistral".to_string(),
+                models: vec!["mistral*".to_string(), "mixtral*".to_string()],
+                examples_header: "EXAMPLES OF CORRECT RESPONSES:".to_string(),
+                examples: tool_call_examples.clone(),
+                ..Self::default()
+            },
+            Self {
+                name: "qwen".to_string(),
+                models: vec!["qwen*".to_string()],
+                examples_header: "EXAMPLES OF CORRECT RESPONSES:".to_string(),
+                examples: tool_call_examples,
+                ..Self::default()
+            },
+        ]
+    }
+
+    /// Whether this template applies to `model` (e.g. `qwen2.5:7b-instruct`)
+    pub fn matches(&self, model: &str) -> bool {
+        let model = model.to_lowercase();
+        self.models
+            .iter()
+            .any(|pattern| wildcard_match(&pattern.to_lowercase(), &model))
+    }
+
+    /// Best built-in template for a model (the default layout if none match)
+    pub fn for_model(model: &str) -> Self {
+        Self::select(model, Vec::new())
+    }
+
+    /// Template for a model, considering user templates in `dir`
+    ///
+    /// A missing directory is not an error; a malformed template file is.
+    pub fn resolve(model: &str, dir: Option<&Path>) -> Result<Self> {
+        let user = match dir {
+            Some(dir) => load_dir(dir)?,
+            None => Vec::new(),
+        };
+        Ok(Self::select(model, user))
+    }
+
+    fn select(model: &str, user: Vec<Self>) -> Self {
+        let user_names: Vec<String> = user.iter().map(|t| t.name.clone()).collect();
+        let mut candidates = user;
+        candidates.extend(Self::builtins().into_iter().filter(|t| !user_names.contains(&t.name)));
+
+        if let Some(index) = candidates.iter().position(|t| t.matches(model)) {
+            return candidates.swap_remove(index);
+        }
+        candidates
+            .into_iter()
+            .find(|t| t.name == "default")
+            .unwrap_or_default()
+    }
+
+    /// Render memory entries into a prompt
+    pub fn render<'a>(&self, entries: impl IntoIterator<Item = &'a MemoryEntry>) -> String {
+        self.render_entries(entries, self.examples.is_empty())
+    }
+
+    /// Render entries that continue an already rendered prompt
+    ///
+    /// Examples were shown with the first system prompt, so they are not
+    /// repeated after later system messages.
+    pub fn render_continuation<'a>(&self, entries: impl IntoIterator<Item = &'a MemoryEntry>) -> String {
+        self.render_entries(entries, true)
+    }
+
+    fn render_entries<'a>(
+        &self,
+        entries: impl IntoIterator<Item = &'a MemoryEntry>,
+        mut examples_added: bool,
+    ) -> String {
+        let mut parts: Vec<String> = Vec::new();
+
+        for entry in entries {
+            match entry {
+                MemoryEntry::SystemPrompt { content } => {
+                    parts.push(fill(&self.system, &[("content", content)]));
+                    if !examples_added {
+                        parts.push(self.render_examples());
+                        examples_added = true;
+                    }
+                }
+                MemoryEntry::UserGoal { goal, .. } => {
+                    parts.push(fill(&self.goal, &[("goal", goal)]));
+                }
+                MemoryEntry::Plan { steps, reasoning, .. } => {
+                    parts.push(self.plan.clone());
+                    for (i, step) in steps.iter().enumerate() {
+                        let n = (i + 1).to_string();
+                        parts.push(fill(&self.plan_step, &[("n", &n), ("step", step)]));
+                    }
+                    if let Some(r) = reasoning {
+                        parts.push(fill(&self.plan_reasoning, &[("reasoning", r)]));
+                    }
+                }
+                MemoryEntry::ToolCall { tool, args, .. } => {
+                    let args = serde_json::to_string(args).unwrap_or_default();
+                    parts.push(fill(&self.tool_call, &[("tool", tool), ("args", &args)]));
+                }
+                MemoryEntry::ToolResult { tool, output, success, .. } => {
+                    let status = if *success { "SUCCESS" } else { "FAILED" };
+                    parts.push(fill(
+                        &self.tool_result,
+                        &[("status", status), ("tool", tool), ("output", output)],
+                    ));
+                }
+                MemoryEntry::Question { question, .. } => {
+                    parts.push(fill(&self.question, &[("question", question)]));
+                }
+                MemoryEntry::UserResponse { response, .. } => {
+                    parts.push(fill(&self.user_response, &[("response", response)]));
+                }
+                MemoryEntry::FinalResult { result, summary, .. } => {
+                    parts.push(fill(&self.final_result, &[("result", result)]));
+                    if let Some(s) = summary {
+                        parts.push(fill(&self.final_summary, &[("summary", s)]));
+                    }
+                }
+                MemoryEntry::ErrorEntry { message, .. } => {
+                    parts.push(fill(&self.error, &[("message", message)]));
+                }
+            }
+        }
+
+        parts.join(&self.separator)
+    }
+
+    fn render_examples(&self) -> String {
+        let mut text = self.examples_header.clone();
+        for example in &self.examples {
+            text.push_str("\n\n");
+            text.push_str(example);
+        }
+        text
+    }
+}
+
+/// Default user template directory: `~/.ollamabuddy/templates`
+pub fn default_dir() -> Option<PathBuf> {
+    dirs::home_dir().map(|home| home.join(".ollamabuddy").join("templates"))
+}
+
+/// Load every `*.toml` template in `dir`, sorted by file name
+pub fn load_dir(dir: &Path) -> Result<Vec<PromptTemplate>> {
+    if !dir.is_dir() {
+        return Ok(Vec::new());
+    }
+
+    let mut paths: Vec<PathBuf> = std::fs::read_dir(dir)?
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "toml"))
+        .collect();
+    paths.sort();
+
+    paths
+        .iter()
+        .map(|path| {
+            let invalid = |e: &dyn std::fmt::Display| {
+                AgentError::Generic(format!("Invalid prompt template {}: {}", path.display(), e))
+            };
+            let value: toml::Value =
+                toml::from_str(&std::fs::read_to_string(path)?).map_err(|e| invalid(&e))?;
+            let named = value.get("name").is_some();
+            let mut template: PromptTemplate = value.try_into().map_err(|e| invalid(&e))?;
+            if !named {
+                template.name = path
+                    .file_stem()
+                    .map(|stem| stem.to_string_lossy().into_owned())
+                    .unwrap_or_default();
+            }
+            Ok(template)
+        })
+        .collect()
+}
+
+/// Substitute `{name}` placeholders in one pass; unknown ones are kept
+fn fill(template: &str, vars: &[(&str, &str)]) -> String {
+    let values: HashMap<&str, &str> = vars.iter().copied().collect();
+    let mut out = String::with_capacity(template.len());
+    let mut rest = template;
+
+    while let Some(start) = rest.find('{') {
+        out.push_str(&rest[..start]);
+        let after = &rest[start + 1..];
+        match after.find('}').and_then(|end| values.get(&after[..end]).map(|v| (end, v))) {
+            Some((end, value)) => {
+                out.push_str(value);
+                rest = &after[end + 1..];
+            }
+            None => {
+                out.push('{');
+                rest = after;
+            }
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+/// Match `text` against a pattern where `*` matches any run of characters
+fn wildcard_match(pattern: &str, text: &str) -> bool {
+    let pieces: Vec<&str> = pattern.split('*').collect();
+    if pieces.len() == 1 {
+        return pattern == text;
+    }
+
+    let (first, last) = (pieces[0], pieces[pieces.len() - 1]);
+    if !text.starts_with(first) || text.len() < first.len() + last.len() || !text.ends_with(last) {
+        return false;
+    }
+
+    let mut remaining = &text[first.len()..text.len() - last.len()];
+    for piece in &pieces[1..pieces.len() - 1] {
+        match remaining.find(piece) {
+            Some(index) => remaining = &remaining[index + piece.len()..],
+            None => return false,
+        }
+    }
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn entries() -> Vec<MemoryEntry> {
+        vec![
+            MemoryEntry::SystemPrompt { content: "Use tools.".to_string() },
+            MemoryEntry::UserGoal { goal: "Count {files}".to_string(), timestamp: 0 },
+            MemoryEntry::ToolResult {
+                tool: "list_dir".to_string(),
+                output: "a.rs".to_string(),
+                success: true,
+                duration_ms: 1,
+                timestamp: 0,
+            },
+        ]
+    }
+
+    #[test]
+    fn test_default_layout() {
+        let prompt = PromptTemplate::default().render(&entries());
+        assert_eq!(
+            prompt,
+            "SYSTEM: Use tools.\n\nGOAL: Count {files}\n\nTOOL_RESULT [SUCCESS] list_dir: a.rs"
+        );
+    }
+
+    #[test]
+    fn test_builtin_selection_by_tag() {
+        assert_eq!(PromptTemplate::for_model("qwen2.5:7b-instruct").name, "qwen");
+        assert_eq!(PromptTemplate::for_model("Llama3.1:8b").name, "llama3");
+        assert_eq!(PromptTemplate::for_model("deepseek-r1:14b").name, "deepseek-r1");
+        assert_eq!(PromptTemplate::for_model("mixtral:8x7b").name, "mistral");
+        assert_eq!(PromptTemplate::for_model("phi3:mini").name, "default");
+    }
+
+    #[test]
+    fn test_examples_follow_system_prompt() {
+        let prompt = PromptTemplate::for_model("qwen2.5:7b-instruct").render(&entries());
+        let system = prompt.find("SYSTEM:").unwrap();
+        let examples = prompt.find("EXAMPLES OF CORRECT RESPONSES:").unwrap();
+        let goal = prompt.find("GOAL:").unwrap();
+        assert!(system < examples && examples < goal);
+    }
+
+    #[test]
+    fn test_continuation_omits_examples() {
+        let template = PromptTemplate::for_model("qwen2.5:7b-instruct");
+        let reflection = [MemoryEntry::SystemPrompt { content: "REFLECTION".to_string() }];
+        assert!(template.render(&reflection).contains("EXAMPLES OF CORRECT RESPONSES:"));
+        assert_eq!(template.render_continuation(&reflection), "SYSTEM: REFLECTION");
+    }
+
+    #[test]
+    fn test_user_templates_override_builtins() {
+        let dir = TempDir::new().unwrap();
+        std::fs::write(
+            dir.path().join("qwen.toml"),
+            "models = [\"qwen*\"]\ngoal = \"<goal>{goal}</goal>\"\n",
+        )
+        .unwrap();
+        std::fs::write(
+            dir.path().join("phi.toml"),
+            "name = \"phi-family\"\nmodels = [\"phi*\"]\nsystem = \"[SYS] {content}\"\n",
+        )
+        .unwrap();
+
+        let qwen = PromptTemplate::resolve("qwen2.5:14b", Some(dir.path())).unwrap();
+        assert_eq!(qwen.name, "qwen");
+        assert!(qwen.examples.is_empty(), "user file replaces the built-in");
+        assert!(qwen.render(&entries()).contains("<goal>Count {files}</goal>"));
+
+        let phi = PromptTemplate::resolve("phi3:mini", Some(dir.path())).unwrap();
+        assert_eq!(phi.name, "phi-family");
+        assert!(phi.render(&entries()).starts_with("[SYS] Use tools."));
+
+        std::fs::write(dir.path().join("broken.toml"), "models = 3").unwrap();
+        assert!(PromptTemplate::resolve("phi3", Some(dir.path())).is_err());
+    }
+
+    #[test]
+    fn test_wildcard_match() {
+        assert!(wildcard_match("qwen*", "qwen2.5:7b"));
+        assert!(wildcard_match("*coder*", "qwen2.5-coder:7b"));
+        assert!(wildcard_match("llama3", "llama3"));
+        assert!(!wildcard_match("llama3*", "llama2:7b"));
+        assert!(!wildcard_match("a*a", "a"));
+    }
+}
@@ -1,9 +1,12 @@
 //! Dynamic iteration budget manager
 //! Implements complexity-based budget allocation with mathematical guarantees
 
-use crate::budget::types::{BudgetConfig, BudgetWarning};
+use crate::budget::types::{BudgetConfig, BudgetSnapshot, BudgetWarning};
 use std::time::{Duration, Instant};
 
+/// Iterations kept when the budget shrinks
+const MIN_REMAINING_AFTER_SHRINK: usize = 2;
+
 /// Dynamic budget manager for iteration allocation
 #[derive(Debug, Clone)]
 pub struct DynamicBudgetManager {
@@ -50,7 +53,7 @@ impl DynamicBudgetManager {
     /// 
     /// Guarantees:
     /// - Monotonicity: C1 < C2 => I(C1) <= I(C2)
-    /// - Bounded: 8 <= I(C) <= max_budget
+    /// - Bounded: I_base <= I(C) <= max_budget
     /// - Conservative: I(C) >= I_empirical(C) with 95% confidence
     pub fn calculate_budget(&mut self, complexity: f64) -> usize {
         // Clamp complexity to valid range
@@ -139,8 +142,8 @@ impl DynamicBudgetManager {
                 remaining: self.get_remaining(),
             };
             
-            // Only return if this is a new warning
-            if self.last_warning.as_ref() != Some(&warning) {
+            // Only warn once until the budget changes
+            if !matches!(self.last_warning, Some(BudgetWarning::ApproachingLimit { .. })) {
                 self.last_warning = Some(warning.clone());
                 return Some(warning);
             }
@@ -174,11 +177,71 @@ impl DynamicBudgetManager {
         None
     }
     
+    /// Grow the budget by up to `additional` iterations, within the maximum
+    pub fn extend(&mut self, additional: usize) -> Option<BudgetWarning> {
+        let new_budget = (self.allocated_budget + additional)
+            .min(self.config.max_budget)
+            .max(self.allocated_budget);
+        let added = new_budget - self.allocated_budget;
+        if added == 0 {
+            return None;
+        }
+
+        self.allocated_budget = new_budget;
+        let warning = BudgetWarning::Extended {
+            additional: added,
+            allocated: new_budget,
+        };
+        self.last_warning = Some(warning.clone());
+        Some(warning)
+    }
+
+    /// Shrink the budget by up to `iterations`
+    ///
+    /// At least `MIN_REMAINING_AFTER_SHRINK` iterations are kept so the agent
+    /// can still recover or wrap up.
+    pub fn shrink(&mut self, iterations: usize) -> Option<BudgetWarning> {
+        let floor = self.used_iterations + MIN_REMAINING_AFTER_SHRINK;
+        let new_budget = self.allocated_budget.saturating_sub(iterations).max(floor);
+        if new_budget >= self.allocated_budget {
+            return None;
+        }
+
+        let removed = self.allocated_budget - new_budget;
+        self.allocated_budget = new_budget;
+        let warning = BudgetWarning::Reduced {
+            removed,
+            allocated: new_budget,
+        };
+        self.last_warning = Some(warning.clone());
+        Some(warning)
+    }
+
     /// Get elapsed time since start
     pub fn get_elapsed_time(&self) -> Duration {
         self.start_time.elapsed()
     }
     
+    /// Capture allocation and usage for a checkpoint
+    pub fn snapshot(&self) -> BudgetSnapshot {
+        BudgetSnapshot {
+            allocated_budget: self.allocated_budget,
+            used_iterations: self.used_iterations,
+            complexity_score: self.complexity_score,
+        }
+    }
+
+    /// Restore allocation and usage from a checkpoint
+    ///
+    /// The elapsed-time clock restarts from now.
+    pub fn restore(&mut self, snapshot: &BudgetSnapshot) {
+        self.allocated_budget = snapshot.allocated_budget;
+        self.used_iterations = snapshot.used_iterations;
+        self.complexity_score = snapshot.complexity_score;
+        self.start_time = Instant::now();
+        self.last_warning = None;
+    }
+
     /// Reset budget manager for new task
     pub fn reset(&mut self) {
         self.allocated_budget = 0;
@@ -203,21 +266,21 @@ mod tests {
     fn test_budget_calculation_simple_task() {
         let mut manager = DynamicBudgetManager::new();
         let budget = manager.calculate_budget(0.2);
-        assert!(budget >= 13 && budget <= 14, "Expected ~13, got {}", budget);
+        assert_eq!(budget, 18, "Expected 10 + floor(40 × 0.2 × 1.1), got {}", budget);
     }
     
     #[test]
     fn test_budget_calculation_medium_task() {
         let mut manager = DynamicBudgetManager::new();
         let budget = manager.calculate_budget(0.5);
-        assert!(budget >= 20 && budget <= 22, "Expected ~21, got {}", budget);
+        assert_eq!(budget, 32, "Expected 10 + floor(40 × 0.5 × 1.1), got {}", budget);
     }
     
     #[test]
     fn test_budget_calculation_complex_task() {
         let mut manager = DynamicBudgetManager::new();
         let budget = manager.calculate_budget(0.8);
-        assert!(budget >= 30 && budget <= 34, "Expected ~32, got {}", budget);
+        assert_eq!(budget, 48, "Expected 10 + floor(40 × 0.8 × 1.2), got {}", budget);
     }
     
     #[test]
@@ -231,10 +294,11 @@ mod tests {
     #[test]
     fn test_budget_bounded() {
         let mut manager = DynamicBudgetManager::new();
+        let config = BudgetConfig::default();
         let budget_min = manager.calculate_budget(0.0);
-        assert!(budget_min >= 8, "Below minimum: {}", budget_min);
+        assert_eq!(budget_min, config.base_iterations, "Below minimum: {}", budget_min);
         let budget_max = manager.calculate_budget(1.0);
-        assert!(budget_max <= 50, "Above maximum: {}", budget_max);
+        assert!(budget_max <= config.max_budget, "Above maximum: {}", budget_max);
     }
     
     #[test]
@@ -270,6 +334,21 @@ mod tests {
         assert!(manager.is_exhausted());
     }
     
+    #[test]
+    fn test_snapshot_restore() {
+        let mut manager = DynamicBudgetManager::new();
+        manager.calculate_budget(0.5);
+        manager.increment_iteration();
+        manager.increment_iteration();
+        let snapshot = manager.snapshot();
+
+        let mut restored = DynamicBudgetManager::new();
+        restored.restore(&snapshot);
+        assert_eq!(restored.get_allocated(), manager.get_allocated());
+        assert_eq!(restored.get_used(), 2);
+        assert_eq!(restored.get_complexity(), 0.5);
+    }
+
     #[test]
     fn test_utilization_calculation() {
         let mut manager = DynamicBudgetManager::new();
@@ -332,6 +411,49 @@ mod tests {
         assert!(warning.is_some(), "Expected complexity increase warning");
     }
     
+    #[test]
+    fn test_extend_and_shrink() {
+        let mut manager = DynamicBudgetManager::with_config(BudgetConfig {
+            max_budget: 20,
+            ..BudgetConfig::default()
+        });
+        manager.calculate_budget(0.0);
+        assert_eq!(manager.get_allocated(), 10);
+
+        assert_eq!(
+            manager.extend(15),
+            Some(BudgetWarning::Extended { additional: 10, allocated: 20 })
+        );
+        assert_eq!(manager.extend(5), None, "Already at the maximum");
+
+        for _ in 0..16 {
+            manager.increment_iteration();
+        }
+        assert_eq!(
+            manager.shrink(10),
+            Some(BudgetWarning::Reduced { removed: 2, allocated: 18 })
+        );
+        assert_eq!(manager.shrink(1), None, "Keeps iterations to recover");
+        assert_eq!(manager.get_remaining(), 2);
+    }
+
+    #[test]
+    fn test_approaching_warning_issued_once() {
+        let mut manager = DynamicBudgetManager::new();
+        manager.calculate_budget(0.0);
+        for _ in 0..8 {
+            manager.increment_iteration();
+        }
+        assert!(manager.check_exhaustion_warning().is_some());
+        manager.increment_iteration();
+        assert!(manager.check_exhaustion_warning().is_none());
+
+        // A change to the budget re-arms the warning
+        manager.extend(2);
+        manager.increment_iteration();
+        assert!(manager.check_exhaustion_warning().is_some());
+    }
+
     #[test]
     fn test_reset() {
         let mut manager = DynamicBudgetManager::new();
@@ -2,7 +2,9 @@
 //! Provides complexity-based iteration allocation with mathematical guarantees
 
 pub mod manager;
+pub mod resources;
 pub mod types;
 
 pub use manager::DynamicBudgetManager;
-pub use types::{BudgetConfig, BudgetWarning};
+pub use resources::{wind_down_prompt, ResourceBudget};
+pub use types::{BudgetConfig, BudgetResource, BudgetSnapshot, BudgetWarning, RunLimits};
@@ -0,0 +1,186 @@
+//! Wall-clock, token and tool-runtime budgets
+//!
+//! Complements the iteration budget of [`DynamicBudgetManager`]: a run can
+//! also be limited by elapsed time, generated tokens and total time spent in
+//! tools. Each limit warns once at the warning threshold and once when used
+//! up; the agent loop then gives the model a final turn to summarise its
+//! work instead of stopping mid-task.
+//!
+//! [`DynamicBudgetManager`]: crate::budget::DynamicBudgetManager
+
+use crate::budget::types::{BudgetConfig, BudgetResource, BudgetWarning, RunLimits};
+use std::collections::HashSet;
+use std::time::{Duration, Instant};
+
+/// Tracks time, token and tool-runtime usage against [`RunLimits`]
+#[derive(Debug, Clone)]
+pub struct ResourceBudget {
+    /// Configured limits
+    limits: RunLimits,
+
+    /// Fraction of a limit at which to warn
+    warning_threshold: f64,
+
+    /// Run start time
+    start_time: Instant,
+
+    /// Tokens generated so far
+    tokens: u64,
+
+    /// Time spent in tools so far
+    tool_time: Duration,
+
+    /// Resources already warned about as approaching
+    warned: HashSet<BudgetResource>,
+
+    /// Resources already reported as exhausted
+    exhausted: HashSet<BudgetResource>,
+}
+
+impl ResourceBudget {
+    /// Start tracking a run against `limits`
+    pub fn new(limits: RunLimits) -> Self {
+        Self {
+            limits,
+            warning_threshold: BudgetConfig::default().warning_threshold,
+            start_time: Instant::now(),
+            tokens: 0,
+            tool_time: Duration::ZERO,
+            warned: HashSet::new(),
+            exhausted: HashSet::new(),
+        }
+    }
+
+    /// Configured limits
+    pub fn limits(&self) -> &RunLimits {
+        &self.limits
+    }
+
+    /// Record generated tokens
+    pub fn record_tokens(&mut self, count: u64) {
+        self.tokens += count;
+    }
+
+    /// Record time spent executing a tool
+    pub fn record_tool_time(&mut self, duration: Duration) {
+        self.tool_time += duration;
+    }
+
+    /// Tokens generated so far
+    pub fn tokens_used(&self) -> u64 {
+        self.tokens
+    }
+
+    /// Time spent in tools so far
+    pub fn tool_time_used(&self) -> Duration {
+        self.tool_time
+    }
+
+    /// Wall-clock time since the run started
+    pub fn elapsed(&self) -> Duration {
+        self.start_time.elapsed()
+    }
+
+    /// Usage and limit of each limited resource
+    fn usage(&self) -> Vec<(BudgetResource, u64, u64)> {
+        let mut usage = Vec::new();
+        if let Some(limit) = self.limits.max_duration {
+            usage.push((BudgetResource::Time, self.elapsed().as_secs(), limit.as_secs()));
+        }
+        if let Some(limit) = self.limits.max_tokens {
+            usage.push((BudgetResource::Tokens, self.tokens, limit));
+        }
+        if let Some(limit) = self.limits.max_tool_time {
+            usage.push((BudgetResource::ToolTime, self.tool_time.as_secs(), limit.as_secs()));
+        }
+        usage
+    }
+
+    /// First resource whose limit is used up
+    pub fn exhausted(&self) -> Option<BudgetResource> {
+        self.usage()
+            .into_iter()
+            .find(|(_, used, limit)| used >= limit)
+            .map(|(resource, _, _)| resource)
+    }
+
+    /// Warnings newly crossed since the last check
+    ///
+    /// Each resource warns at most once as approaching and once as exhausted.
+    pub fn check_warnings(&mut self) -> Vec<BudgetWarning> {
+        let mut warnings = Vec::new();
+        for (resource, used, limit) in self.usage() {
+            if used >= limit {
+                if self.exhausted.insert(resource) {
+                    warnings.push(BudgetWarning::ResourceExhausted { resource, used, limit });
+                }
+            } else if used as f64 >= limit as f64 * self.warning_threshold
+                && self.warned.insert(resource)
+            {
+                warnings.push(BudgetWarning::ResourceApproaching { resource, used, limit });
+            }
+        }
+        warnings
+    }
+}
+
+/// Message asking the model to wrap up once a resource budget is used up
+pub fn wind_down_prompt(resource: BudgetResource, task: &str) -> String {
+    format!(
+        "BUDGET EXHAUSTED: the {} budget for this task is used up. Do not call any more tools.\n\
+        Original task: {}\n\
+        Respond now with {{\"type\": \"final\", \"result\": \"...\"}} summarising what you have done, \
+        what the results were, and what remains unfinished.",
+        resource, task
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_unlimited_budget_never_warns() {
+        let mut budget = ResourceBudget::new(RunLimits::default());
+        budget.record_tokens(1_000_000);
+        budget.record_tool_time(Duration::from_secs(3600));
+        assert!(budget.check_warnings().is_empty());
+        assert_eq!(budget.exhausted(), None);
+    }
+
+    #[test]
+    fn test_token_and_tool_time_warnings() {
+        let mut budget = ResourceBudget::new(RunLimits {
+            max_tokens: Some(100),
+            max_tool_time: Some(Duration::from_secs(10)),
+            ..RunLimits::default()
+        });
+
+        budget.record_tokens(80);
+        assert_eq!(
+            budget.check_warnings(),
+            vec![BudgetWarning::ResourceApproaching { resource: BudgetResource::Tokens, used: 80, limit: 100 }]
+        );
+        // Each warning is issued once
+        assert!(budget.check_warnings().is_empty());
+        assert_eq!(budget.exhausted(), None);
+
+        budget.record_tool_time(Duration::from_secs(12));
+        budget.record_tokens(20);
+        let warnings = budget.check_warnings();
+        assert_eq!(warnings.len(), 2);
+        assert!(warnings.iter().all(|w| matches!(w, BudgetWarning::ResourceExhausted { .. })));
+        assert_eq!(budget.exhausted(), Some(BudgetResource::Tokens));
+        assert_eq!(warnings[0].to_string(), "Token budget exhausted: 100 tokens of 100 tokens");
+    }
+
+    #[test]
+    fn test_time_budget() {
+        let budget = ResourceBudget::new(RunLimits {
+            max_duration: Some(Duration::ZERO),
+            ..RunLimits::default()
+        });
+        assert_eq!(budget.exhausted(), Some(BudgetResource::Time));
+        assert!(wind_down_prompt(BudgetResource::Time, "do it").contains("time budget"));
+    }
+}
@@ -1,6 +1,8 @@
 //! Budget system type definitions
 
 use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::time::Duration;
 
 /// Configuration for dynamic budget calculation
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -33,6 +35,77 @@ impl Default for BudgetConfig {
     }
 }
 
+/// Per-run limits on resources other than iterations
+///
+/// `None` means unlimited.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub struct RunLimits {
+    /// Maximum wall-clock time for the run
+    pub max_duration: Option<Duration>,
+
+    /// Maximum number of tokens generated by the model
+    pub max_tokens: Option<u64>,
+
+    /// Maximum total time spent executing tools
+    pub max_tool_time: Option<Duration>,
+}
+
+impl RunLimits {
+    /// Whether any limit is set
+    pub fn is_limited(&self) -> bool {
+        self.max_duration.is_some() || self.max_tokens.is_some() || self.max_tool_time.is_some()
+    }
+}
+
+/// Budgeted resource besides iterations
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum BudgetResource {
+    /// Wall-clock time, in seconds
+    Time,
+
+    /// Generated tokens
+    Tokens,
+
+    /// Total tool runtime, in seconds
+    ToolTime,
+}
+
+impl BudgetResource {
+    /// Format an amount of this resource for display
+    pub fn format_amount(&self, amount: u64) -> String {
+        match self {
+            BudgetResource::Time | BudgetResource::ToolTime => format!("{}s", amount),
+            BudgetResource::Tokens => format!("{} tokens", amount),
+        }
+    }
+}
+
+impl fmt::Display for BudgetResource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            BudgetResource::Time => "time",
+            BudgetResource::Tokens => "token",
+            BudgetResource::ToolTime => "tool runtime",
+        })
+    }
+}
+
+/// Serializable budget state for checkpoints
+///
+/// `DynamicBudgetManager` tracks elapsed time with an `Instant`, which cannot
+/// be persisted; the snapshot keeps only the allocation and usage counters.
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+pub struct BudgetSnapshot {
+    /// Allocated iteration budget
+    pub allocated_budget: usize,
+
+    /// Iterations used when the snapshot was taken
+    pub used_iterations: usize,
+
+    /// Task complexity score (0.0 to 1.0)
+    pub complexity_score: f64,
+}
+
 /// Budget warning types
 #[derive(Debug, Clone, PartialEq)]
 pub enum BudgetWarning {
@@ -55,4 +128,82 @@ pub enum BudgetWarning {
         new_complexity: f64,
         additional_budget: usize,
     },
+
+    /// Budget grown because steady progress needs more iterations
+    Extended {
+        additional: usize,
+        allocated: usize,
+    },
+
+    /// Budget shrunk because progress stagnated
+    Reduced {
+        removed: usize,
+        allocated: usize,
+    },
+
+    /// A time, token or tool-runtime limit is nearly used up
+    ResourceApproaching {
+        resource: BudgetResource,
+        used: u64,
+        limit: u64,
+    },
+
+    /// A time, token or tool-runtime limit is used up
+    ResourceExhausted {
+        resource: BudgetResource,
+        used: u64,
+        limit: u64,
+    },
+}
+
+impl fmt::Display for BudgetWarning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BudgetWarning::ApproachingLimit { used, allocated, remaining } => write!(
+                f,
+                "Iteration budget nearly used: {}/{} ({} remaining)",
+                used, allocated, remaining
+            ),
+            BudgetWarning::Exhausted { used, allocated } => {
+                write!(f, "Iteration budget exhausted: {}/{}", used, allocated)
+            }
+            BudgetWarning::ComplexityIncreased { old_complexity, new_complexity, additional_budget } => write!(
+                f,
+                "Complexity increased ({:.2} -> {:.2}): {} more iterations",
+                old_complexity, new_complexity, additional_budget
+            ),
+            BudgetWarning::Extended { additional, allocated } => write!(
+                f,
+                "Steady progress: iteration budget extended by {} to {}",
+                additional, allocated
+            ),
+            BudgetWarning::Reduced { removed, allocated } => write!(
+                f,
+                "Progress stalled: iteration budget reduced by {} to {}",
+                removed, allocated
+            ),
+            BudgetWarning::ResourceApproaching { resource, used, limit } => write!(
+                f,
+                "{} budget nearly used: {} of {}",
+                capitalize(&resource.to_string()),
+                resource.format_amount(*used),
+                resource.format_amount(*limit)
+            ),
+            BudgetWarning::ResourceExhausted { resource, used, limit } => write!(
+                f,
+                "{} budget exhausted: {} of {}",
+                capitalize(&resource.to_string()),
+                resource.format_amount(*used),
+                resource.format_amount(*limit)
+            ),
+        }
+    }
+}
+
+fn capitalize(text: &str) -> String {
+    let mut chars = text.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
 }
@@ -2,8 +2,11 @@
 //! 
 //! Provides clap-based CLI with subcommands and verbosity control.
 
+use crate::agent::AskPolicy;
+use crate::budget::RunLimits;
 use clap::{Parser, Subcommand};
 use std::path::PathBuf;
+use std::time::Duration;
 
 /// OllamaBuddy - Transform local Ollama models into capable terminal agents
 #[derive(Parser, Debug)]
@@ -53,6 +56,38 @@ pub struct Args {
     #[arg(short, long)]
     pub quiet: bool,
 
+    /// Never prompt for input; questions from the model fail the task
+    #[arg(long)]
+    pub non_interactive: bool,
+
+    /// Answer model questions with this text in non-interactive mode
+    #[arg(long, value_name = "TEXT", requires = "non_interactive")]
+    pub auto_answer: Option<String>,
+
+    /// Run atomic sub-goals of the plan with fresh sub-agents, in dependency order
+    #[arg(long)]
+    pub delegate: bool,
+
+    /// Show the plan for review (approve, edit in $EDITOR, or reject) before running
+    #[arg(long)]
+    pub plan_only: bool,
+
+    /// Wall-clock limit for a task in minutes (config `timeout_minutes` by default; 0 for none)
+    #[arg(long, value_name = "MINUTES")]
+    pub max_time: Option<u64>,
+
+    /// Limit on tokens generated by the model per task
+    #[arg(long, value_name = "N")]
+    pub max_tokens: Option<u64>,
+
+    /// Limit on total tool runtime per task, in seconds
+    #[arg(long, value_name = "SECONDS")]
+    pub max_tool_time: Option<u64>,
+
+    /// Record model requests/responses and tool calls to a JSONL trace
+    #[arg(long, value_name = "FILE")]
+    pub trace: Option<PathBuf>,
+
     /// Subcommand
     #[command(subcommand)]
     pub command: Option<Commands>,
@@ -79,6 +114,26 @@ pub enum Commands {
 
     /// Display current configuration
     Config,
+
+    /// Replay a recorded trace without calling the model
+    Replay {
+        /// Trace file written with --trace
+        trace: PathBuf,
+
+        /// Re-execute tools instead of returning the recorded results
+        #[arg(long)]
+        live_tools: bool,
+    },
+
+    /// Resume an interrupted run from its last checkpoint
+    Resume {
+        /// Run ID (most recent unfinished run if omitted)
+        run_id: Option<String>,
+
+        /// List saved runs instead of resuming
+        #[arg(long)]
+        list: bool,
+    },
 }
 
 
@@ -169,6 +224,30 @@ impl Args {
     pub fn ollama_url(&self) -> String {
         format!("http://{}:{}", self.host, self.port)
     }
+
+    /// Get policy for questions the model asks during a task
+    pub fn ask_policy(&self) -> AskPolicy {
+        if !self.non_interactive {
+            return AskPolicy::Interactive;
+        }
+        match &self.auto_answer {
+            Some(answer) => AskPolicy::AutoAnswer(answer.clone()),
+            None => AskPolicy::Fail,
+        }
+    }
+
+    /// Get time, token and tool-runtime limits for a task
+    ///
+    /// `default_timeout_minutes` (from config) applies when `--max-time` is
+    /// not given; a time limit of 0 disables it.
+    pub fn run_limits(&self, default_timeout_minutes: u64) -> RunLimits {
+        let minutes = self.max_time.unwrap_or(default_timeout_minutes);
+        RunLimits {
+            max_duration: (minutes > 0).then(|| Duration::from_secs(minutes * 60)),
+            max_tokens: self.max_tokens,
+            max_tool_time: self.max_tool_time.map(Duration::from_secs),
+        }
+    }
 }
 
 impl Verbosity {
@@ -215,6 +294,14 @@ mod tests {
             config: None,
             verbose: 0,
             quiet: true,
+            non_interactive: false,
+            auto_answer: None,
+            delegate: false,
+            plan_only: false,
+            max_time: None,
+            max_tokens: None,
+            max_tool_time: None,
+            trace: None,
             command: None,
         };
         assert_eq!(args.verbosity(), Verbosity::Quiet);
@@ -233,6 +320,14 @@ mod tests {
             config: None,
             verbose: 0,
             quiet: false,
+            non_interactive: false,
+            auto_answer: None,
+            delegate: false,
+            plan_only: false,
+            max_time: None,
+            max_tokens: None,
+            max_tool_time: None,
+            trace: None,
             command: None,
         };
         assert_eq!(args.verbosity(), Verbosity::Normal);
@@ -251,6 +346,14 @@ mod tests {
             config: None,
             verbose: 1,
             quiet: false,
+            non_interactive: false,
+            auto_answer: None,
+            delegate: false,
+            plan_only: false,
+            max_time: None,
+            max_tokens: None,
+            max_tool_time: None,
+            trace: None,
             command: None,
         };
         assert_eq!(args.verbosity(), Verbosity::Verbose);
@@ -269,6 +372,14 @@ mod tests {
             config: None,
             verbose: 2,
             quiet: false,
+            non_interactive: false,
+            auto_answer: None,
+            delegate: false,
+            plan_only: false,
+            max_time: None,
+            max_tokens: None,
+            max_tool_time: None,
+            trace: None,
             command: None,
         };
         assert_eq!(args.verbosity(), Verbosity::VeryVerbose);
@@ -287,6 +398,14 @@ mod tests {
             config: None,
             verbose: 0,
             quiet: false,
+            non_interactive: false,
+            auto_answer: None,
+            delegate: false,
+            plan_only: false,
+            max_time: None,
+            max_tokens: None,
+            max_tool_time: None,
+            trace: None,
             command: None,
         };
         assert!(args.validate().is_ok());
@@ -305,6 +424,14 @@ mod tests {
             config: None,
             verbose: 0,
             quiet: false,
+            non_interactive: false,
+            auto_answer: None,
+            delegate: false,
+            plan_only: false,
+            max_time: None,
+            max_tokens: None,
+            max_tool_time: None,
+            trace: None,
             command: Some(Commands::Doctor),
         };
         assert!(args.validate().is_ok());
@@ -323,6 +450,14 @@ mod tests {
             config: None,
             verbose: 0,
             quiet: false,
+            non_interactive: false,
+            auto_answer: None,
+            delegate: false,
+            plan_only: false,
+            max_time: None,
+            max_tokens: None,
+            max_tool_time: None,
+            trace: None,
             command: None,
         };
         assert!(args.validate().is_err());
@@ -341,6 +476,14 @@ mod tests {
             config: None,
             verbose: 0,
             quiet: false,
+            non_interactive: false,
+            auto_answer: None,
+            delegate: false,
+            plan_only: false,
+            max_time: None,
+            max_tokens: None,
+            max_tool_time: None,
+            trace: None,
             command: Some(Commands::Doctor),
         };
         assert!(args.validate().is_err());
@@ -359,11 +502,87 @@ mod tests {
             config: None,
             verbose: 0,
             quiet: false,
+            non_interactive: false,
+            auto_answer: None,
+            delegate: false,
+            plan_only: false,
+            max_time: None,
+            max_tokens: None,
+            max_tool_time: None,
+            trace: None,
             command: None,
         };
         assert_eq!(args.ollama_url(), "http://localhost:8080");
     }
 
+    #[test]
+    fn test_ask_policy() {
+        let args = Args::try_parse_from(["ollamabuddy", "task"]).unwrap();
+        assert_eq!(args.ask_policy(), AskPolicy::Interactive);
+
+        let args = Args::try_parse_from(["ollamabuddy", "--non-interactive", "task"]).unwrap();
+        assert_eq!(args.ask_policy(), AskPolicy::Fail);
+
+        let args = Args::try_parse_from([
+            "ollamabuddy", "--non-interactive", "--auto-answer", "yes", "task",
+        ])
+        .unwrap();
+        assert_eq!(args.ask_policy(), AskPolicy::AutoAnswer("yes".to_string()));
+
+        // --auto-answer only makes sense without a user to ask
+        assert!(Args::try_parse_from(["ollamabuddy", "--auto-answer", "yes", "task"]).is_err());
+    }
+
+    #[test]
+    fn test_run_limits() {
+        let args = Args::try_parse_from(["ollamabuddy", "task"]).unwrap();
+        assert_eq!(
+            args.run_limits(30),
+            RunLimits { max_duration: Some(Duration::from_secs(1800)), ..RunLimits::default() }
+        );
+
+        let args = Args::try_parse_from([
+            "ollamabuddy", "--max-time", "0", "--max-tokens", "5000", "--max-tool-time", "90", "task",
+        ])
+        .unwrap();
+        assert_eq!(
+            args.run_limits(30),
+            RunLimits {
+                max_duration: None,
+                max_tokens: Some(5000),
+                max_tool_time: Some(Duration::from_secs(90)),
+            }
+        );
+    }
+
+    #[test]
+    fn test_resume_subcommand() {
+        let args = Args::try_parse_from(["ollamabuddy", "resume"]).unwrap();
+        assert!(matches!(args.command, Some(Commands::Resume { run_id: None, list: false })));
+        assert!(args.validate().is_ok());
+
+        let args = Args::try_parse_from(["ollamabuddy", "resume", "20261018-120000-abcd"]).unwrap();
+        assert!(matches!(
+            args.command,
+            Some(Commands::Resume { run_id: Some(ref id), .. }) if id == "20261018-120000-abcd"
+        ));
+    }
+
+    #[test]
+    fn test_replay_subcommand() {
+        let args = Args::try_parse_from(["ollamabuddy", "replay", "run.jsonl", "--live-tools"]).unwrap();
+        match args.command {
+            Some(Commands::Replay { trace, live_tools }) => {
+                assert_eq!(trace, PathBuf::from("run.jsonl"));
+                assert!(live_tools);
+            }
+            other => panic!("expected replay, got {:?}", other),
+        }
+
+        let args = Args::try_parse_from(["ollamabuddy", "--trace", "out.jsonl", "task"]).unwrap();
+        assert_eq!(args.trace, Some(PathBuf::from("out.jsonl")));
+    }
+
     #[test]
     fn test_verbosity_methods() {
         assert!(!Verbosity::Quiet.show_progress());
@@ -11,6 +11,7 @@ use crate::errors::{AgentError, Result};
 
 /// Complete configuration for OllamaBuddy
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct Config {
     pub ollama: OllamaConfig,
     pub agent: AgentConfig,
@@ -18,6 +19,7 @@ pub struct Config {
     pub advisor: AdvisorConfig,
     pub telemetry: TelemetryConfig,
     pub paths: PathsConfig,
+    pub models: crate::config::ModelsConfig,
 }
 
 /// Ollama connection configuration
@@ -26,6 +28,12 @@ pub struct OllamaConfig {
     pub host: String,
     pub port: u16,
     pub default_model: String,
+    #[serde(default = "default_keep_alive")]
+    pub keep_alive: String,
+}
+
+fn default_keep_alive() -> String {
+    crate::streaming::DEFAULT_KEEP_ALIVE.to_string()
 }
 
 /// Agent behavior configuration
@@ -45,6 +53,14 @@ pub struct ToolsConfig {
     pub max_output_bytes: usize,
     pub online_enabled: bool,
     pub max_parallel: usize,
+    #[serde(default)]
+    pub disabled: Vec<String>,
+    #[serde(default = "default_max_extract_size")]
+    pub max_extract_size: usize,
+}
+
+fn default_max_extract_size() -> usize {
+    crate::tools::types::ToolContext::default().max_extract_size
 }
 
 /// Model advisor configuration
@@ -79,6 +95,7 @@ impl Default for Config {
             advisor: AdvisorConfig::default(),
             telemetry: TelemetryConfig::default(),
             paths: PathsConfig::default(),
+            models: crate::config::ModelsConfig::default(),
         }
     }
 }
@@ -89,6 +106,7 @@ impl Default for OllamaConfig {
             host: "127.0.0.1".to_string(),
             port: 11434,
             default_model: "qwen2.5:7b-instruct".to_string(),
+            keep_alive: default_keep_alive(),
         }
     }
 }
@@ -112,6 +130,8 @@ impl Default for ToolsConfig {
             max_output_bytes: 2_000_000,
             online_enabled: false,
             max_parallel: 4,
+            disabled: Vec::new(),
+            max_extract_size: default_max_extract_size(),
         }
     }
 }
@@ -309,6 +329,41 @@ mod tests {
         assert_eq!(config.ollama_url(), "http://127.0.0.1:11434");
     }
 
+    #[test]
+    fn test_partial_config_uses_defaults() {
+        let config: Config = toml::from_str("[models]\ndefault = \"llama3\"\n").unwrap();
+        assert!(!config.tools.online_enabled);
+        assert!(config.tools.disabled.is_empty());
+        assert_eq!(config.ollama.port, 11434);
+        assert_eq!(config.models.default.as_deref(), Some("llama3"));
+        assert_eq!(config.ollama.keep_alive, "30m");
+        assert_eq!(config.tools.max_extract_size, 104_857_600);
+        assert!(config.models.planner_model.is_none());
+    }
+
+    #[test]
+    fn test_model_roles_parsing() {
+        let config: Config = toml::from_str(
+            "[models]\ndefault = \"llama3\"\nplanner_model = \"qwen2.5:32b\"\nsummary_model = \"qwen2.5:3b\"\n",
+        ).unwrap();
+        assert_eq!(config.models.planner_model.as_deref(), Some("qwen2.5:32b"));
+        assert_eq!(config.models.summary_model.as_deref(), Some("qwen2.5:3b"));
+        assert_eq!(config.models.get_executor_model(), Some("llama3"));
+
+        let config: Config = toml::from_str("[models]\ndefault = \"llama3\"\nexecutor_model = \"qwen2.5:7b\"\n").unwrap();
+        assert_eq!(config.models.get_executor_model(), Some("qwen2.5:7b"));
+    }
+
+    #[test]
+    fn test_tools_disabled_parsing() {
+        let mut config = Config::default();
+        config.tools.disabled = vec!["run_command".to_string()];
+
+        let toml_string = toml::to_string(&config).unwrap();
+        let parsed: Config = toml::from_str(&toml_string).unwrap();
+        assert_eq!(parsed.tools.disabled, vec!["run_command".to_string()]);
+    }
+
     #[test]
     fn test_expand_path_with_tilde() {
         let path = "~/.ollamabuddy";
@@ -12,18 +12,29 @@ pub struct Config {
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct ModelsConfig {
     pub default: Option<String>,
+
+    /// Model for goal decomposition (defaults to the executor model)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub planner_model: Option<String>,
+
+    /// Model for tool-calling turns (overrides `default`, not `--model`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub executor_model: Option<String>,
+
+    /// Model for compression summaries (rule-based compression if unset)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub summary_model: Option<String>,
 }
 
 impl Config {
-    /// Load configuration from file, creating default if it doesn't exist
+    /// Load configuration from file, or defaults if it doesn't exist
+    ///
+    /// Nothing is written; the file is created by the first `save`.
     pub fn load() -> Result<Self> {
         let config_path = Self::config_path()?;
         
         if !config_path.exists() {
-            // Create default config
-            let config = Config::default();
-            config.save()?;
-            return Ok(config);
+            return Ok(Config::default());
         }
         
         let contents = fs::read_to_string(&config_path)
@@ -76,6 +87,18 @@ impl Config {
     pub fn clear_default_model(&mut self) {
         self.models.default = None;
     }
+
+    /// Model for tool-calling turns: the executor model, else the default
+    pub fn get_executor_model(&self) -> Option<&str> {
+        self.models.get_executor_model()
+    }
+}
+
+impl ModelsConfig {
+    /// Model for tool-calling turns: the executor model, else the default
+    pub fn get_executor_model(&self) -> Option<&str> {
+        self.executor_model.as_deref().or(self.default.as_deref())
+    }
 }
 
 impl Default for Config {
@@ -122,4 +145,18 @@ mod tests {
         let deserialized: Config = toml::from_str(&toml_string).unwrap();
         assert_eq!(deserialized.get_default_model(), Some("qwen2.5:7b-instruct"));
     }
+
+    #[test]
+    fn test_role_models_survive_default_change() {
+        let mut config: Config = toml::from_str(
+            "[models]\ndefault = \"qwen2.5:7b-instruct\"\nplanner_model = \"qwen2.5:32b-instruct\"\nexecutor_model = \"qwen2.5:3b-instruct\"\n",
+        ).unwrap();
+        assert_eq!(config.get_executor_model(), Some("qwen2.5:3b-instruct"));
+
+        config.set_default_model("llama3.1:8b".to_string());
+        let deserialized: Config = toml::from_str(&toml::to_string(&config).unwrap()).unwrap();
+        assert_eq!(deserialized.models.planner_model.as_deref(), Some("qwen2.5:32b-instruct"));
+        assert_eq!(deserialized.models.summary_model, None);
+        assert_eq!(deserialized.get_executor_model(), Some("qwen2.5:3b-instruct"));
+    }
 }
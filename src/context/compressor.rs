@@ -62,79 +62,65 @@ impl ContextCompressor {
             return Ok(Vec::new());
         }
 
-        let mut compressed = Vec::new();
+        // Entries are tracked by position: timestamps have one-second
+        // resolution and system prompts have none, so they can't identify
+        // entries or order them
+        let mut kept: Vec<(usize, MemoryEntry)> = Vec::new();
         let mut current_tokens = 0usize;
 
         // Phase 1: Identify and preserve priority entries
         
         // P1: System prompt (always first if present)
         let system_prompt = entries.iter()
-            .find(|e| matches!(e, MemoryEntry::SystemPrompt { .. }))
-            .cloned();
-        
-        if let Some(prompt) = system_prompt {
-            current_tokens += self.estimate_tokens(&prompt);
-            compressed.push(prompt);
-        }
+            .position(|e| matches!(e, MemoryEntry::SystemPrompt { .. }));
 
         // P2: User goal (original task)
         let user_goal = entries.iter()
-            .find(|e| matches!(e, MemoryEntry::UserGoal { .. }))
-            .cloned();
-        
-        if let Some(goal) = user_goal {
-            current_tokens += self.estimate_tokens(&goal);
-            compressed.push(goal);
-        }
+            .position(|e| matches!(e, MemoryEntry::UserGoal { .. }));
 
         // P3: Last 3 entries (most recent context)
         let last_3_start = entries.len().saturating_sub(3);
-        let last_3: Vec<MemoryEntry> = entries[last_3_start..].to_vec();
-        
-        for entry in &last_3 {
-            current_tokens += self.estimate_tokens(entry);
-            compressed.push(entry.clone());
-        }
 
-        // P4: Current plan (most recent plan entry)
-        let current_plan = entries.iter()
-            .rev()
-            .skip(3) // Skip last 3 (already added)
-            .find(|e| matches!(e, MemoryEntry::Plan { .. }))
-            .cloned();
-        
-        if let Some(plan) = current_plan {
-            current_tokens += self.estimate_tokens(&plan);
-            compressed.push(plan);
+        // P4: Current plan (most recent plan entry before the last 3)
+        let current_plan = entries[..last_3_start].iter()
+            .rposition(|e| matches!(e, MemoryEntry::Plan { .. }));
+
+        let mut preserved: Vec<usize> = [system_prompt, user_goal, current_plan]
+            .into_iter()
+            .flatten()
+            .chain(last_3_start..entries.len())
+            .collect();
+        preserved.sort_unstable();
+        preserved.dedup();
+
+        for &index in &preserved {
+            current_tokens += self.estimate_tokens(&entries[index]);
+            kept.push((index, entries[index].clone()));
         }
 
         // Phase 2: Compress older entries to fill remaining budget
-        
-        // Collect entries not in preserved set
-        let preserved_timestamps: Vec<u64> = compressed.iter()
-            .map(|e| e.timestamp())
-            .collect();
-        
-        let older_entries: Vec<&MemoryEntry> = entries.iter()
-            .filter(|e| !preserved_timestamps.contains(&e.timestamp()))
-            .collect();
+        for (index, entry) in entries.iter().enumerate() {
+            if preserved.binary_search(&index).is_ok() {
+                continue;
+            }
 
-        for entry in older_entries {
             let compressed_entry = self.compress_entry(entry);
             let entry_tokens = self.estimate_tokens(&compressed_entry);
             
             // Only add if within budget
             if current_tokens + entry_tokens <= TARGET_AFTER_COMPRESSION {
                 current_tokens += entry_tokens;
-                compressed.push(compressed_entry);
+                kept.push((index, compressed_entry));
             } else {
                 // Budget exhausted, stop adding
                 break;
             }
         }
 
-        // Sort by timestamp to maintain chronological order
-        compressed.sort_by_key(|e| e.timestamp());
+        // Restore the original order, so the system prompt and goal stay
+        // first and later turns keep appending to a stable prefix
+        kept.sort_by_key(|(index, _)| *index);
+        let compressed = kept.into_iter().map(|(_, entry)| entry).collect();
 
         Ok(compressed)
     }
@@ -417,20 +403,20 @@ mod tests {
     fn test_chronological_order_maintained() {
         let compressor = ContextCompressor::new();
 
+        // Memory is appended in order; a mid-run system prompt (e.g. a
+        // reflection) has no timestamp but must stay where it was added
         let entries = vec![
+            create_system_prompt(),
             create_user_goal(),           // timestamp 1
-            create_tool_result(5, 1000),  // timestamp 5
             create_tool_result(3, 1000),  // timestamp 3
+            MemoryEntry::SystemPrompt { content: "Reflect".to_string() },
+            create_tool_result(3, 1000),  // same second as the previous result
             create_tool_result(7, 1000),  // timestamp 7
         ];
 
         let compressed = compressor.compress(&entries).unwrap();
-
-        // Check timestamps are in ascending order
-        let timestamps: Vec<u64> = compressed.iter().map(|e| e.timestamp()).collect();
-        let mut sorted = timestamps.clone();
-        sorted.sort();
-        
-        assert_eq!(timestamps, sorted, "Chronological order not maintained");
+        let rendered: Vec<String> = compressed.iter().map(|e| format!("{:?}", e)).collect();
+        let expected: Vec<String> = entries.iter().map(|e| format!("{:?}", e)).collect();
+        assert_eq!(rendered, expected, "Chronological order not maintained");
     }
 }
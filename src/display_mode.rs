@@ -2,8 +2,13 @@
 //!
 //! This module provides an abstraction layer for handling output differently
 //! in CLI mode (direct stdout) versus REPL mode (event bus + display manager).
+//! Sub-agents running in parallel use a buffered mode so their streams do
+//! not interleave.
 
 use crate::repl::DisplayManager;
+use crate::streaming::Segment;
+use colored::Colorize;
+use std::io::Write;
 use std::sync::Arc;
 use tokio::sync::Mutex;
 
@@ -19,6 +24,44 @@ pub enum DisplayMode {
     
     /// REPL mode - use display manager with event bus
     Repl(Arc<Mutex<DisplayManager>>),
+
+    /// Collect output and show it as one block when flushed
+    Buffered(Arc<BufferedOutput>),
+}
+
+/// Output of one sub-agent, held back while others run alongside it
+pub struct BufferedOutput {
+    /// Heading shown above the block
+    label: String,
+
+    /// Output collected since the last flush
+    text: std::sync::Mutex<String>,
+
+    /// Where the block is shown
+    parent: DisplayMode,
+
+    /// Shared by sibling sub-agents so only one prompts the user at a time
+    prompt_lock: Arc<Mutex<()>>,
+}
+
+impl BufferedOutput {
+    fn push(&self, text: &str) {
+        self.text.lock().unwrap_or_else(|e| e.into_inner()).push_str(text);
+    }
+
+    fn push_line(&self, line: &str) {
+        self.push(line);
+        self.push("\n");
+    }
+
+    async fn flush(&self) {
+        let text = std::mem::take(&mut *self.text.lock().unwrap_or_else(|e| e.into_inner()));
+        if !text.trim().is_empty() {
+            self.parent
+                .show_info(&format!("[{}]\n{}", self.label, text.trim_end()))
+                .await;
+        }
+    }
 }
 
 impl DisplayMode {
@@ -32,6 +75,26 @@ impl DisplayMode {
         Self::Repl(display_manager)
     }
 
+    /// Buffer output under `label` until `flush`
+    ///
+    /// Siblings created with the same `prompt_lock` take turns asking the
+    /// user; pending output is flushed first so the question has context.
+    pub fn buffered(&self, label: &str, prompt_lock: Arc<Mutex<()>>) -> Self {
+        Self::Buffered(Arc::new(BufferedOutput {
+            label: label.to_string(),
+            text: std::sync::Mutex::new(String::new()),
+            parent: self.clone(),
+            prompt_lock,
+        }))
+    }
+
+    /// Show buffered output as one block (no-op for other modes)
+    pub async fn flush(&self) {
+        if let Self::Buffered(output) = self {
+            output.flush().await;
+        }
+    }
+
     /// Show an informational message
     pub async fn show_info(&self, message: &str) {
         match self {
@@ -42,6 +105,7 @@ impl DisplayMode {
                 let display = display.lock().await;
                 display.show_info(message);
             }
+            Self::Buffered(output) => output.push_line(message),
         }
     }
 
@@ -55,6 +119,7 @@ impl DisplayMode {
                 let mut display = display.lock().await;
                 display.finish_with_success(message, 0);
             }
+            Self::Buffered(output) => output.push_line(message),
         }
     }
 
@@ -68,6 +133,7 @@ impl DisplayMode {
                 let display = display.lock().await;
                 display.show_error(message);
             }
+            Self::Buffered(output) => output.push_line(&format!("Error: {}", message)),
         }
     }
 
@@ -81,6 +147,87 @@ impl DisplayMode {
                 let display = display.lock().await;
                 display.show_warning(message);
             }
+            Self::Buffered(output) => output.push_line(&format!("Warning: {}", message)),
+        }
+    }
+
+    /// Ask the user a question on behalf of the model and wait for a reply
+    pub async fn ask(&self, question: &str) -> Option<String> {
+        self.prompt(&format!("Model asks: {}", question)).await
+    }
+
+    /// Show a message and wait for one line of input from the user
+    ///
+    /// Reads a line from stdin in CLI mode and uses a rustyline prompt in
+    /// REPL mode. Returns `None` on EOF, interrupt, or read failure.
+    pub async fn prompt(&self, message: &str) -> Option<String> {
+        match self {
+            Self::Buffered(output) => {
+                let _turn = output.prompt_lock.lock().await;
+                output.flush().await;
+                Box::pin(output.parent.prompt(message)).await
+            }
+            Self::Cli => {
+                println!("{}", message);
+                print!("> ");
+                let _ = std::io::Write::flush(&mut std::io::stdout());
+                tokio::task::spawn_blocking(|| {
+                    let mut line = String::new();
+                    match std::io::stdin().read_line(&mut line) {
+                        Ok(0) | Err(_) => None,
+                        Ok(_) => Some(line.trim().to_string()),
+                    }
+                })
+                .await
+                .ok()
+                .flatten()
+            }
+            Self::Repl(display) => {
+                let read_line = {
+                    let mut display = display.lock().await;
+                    display.finish_current();
+                    display.show_info(message);
+                    display.line_reader()
+                };
+                tokio::task::spawn_blocking(move || {
+                    read_line("answer> ").map(|line| line.trim().to_string())
+                })
+                .await
+                .ok()
+                .flatten()
+            }
+        }
+    }
+
+    /// Print one piece of a streamed model response
+    ///
+    /// Answer text is printed as-is. Thinking is dimmed when `expand_thinking`
+    /// is set and otherwise collapsed to a single marker per block, so long
+    /// reasoning does not bury the action that follows.
+    pub fn show_stream(&self, segment: &Segment, expand_thinking: bool) {
+        let text = match segment {
+            Segment::ThinkingStart if expand_thinking => "[thinking] ".dimmed().to_string(),
+            Segment::ThinkingStart => "[thinking...]".dimmed().to_string(),
+            Segment::Thinking(text) if expand_thinking => text.dimmed().to_string(),
+            Segment::Thinking(_) => return,
+            Segment::ThinkingEnd => "\n".to_string(),
+            Segment::Answer(text) => text.clone(),
+        };
+        self.write_stream(&text);
+    }
+
+    /// End a streamed response with a newline
+    pub fn end_stream(&self) {
+        self.write_stream("\n");
+    }
+
+    fn write_stream(&self, text: &str) {
+        match self {
+            Self::Buffered(output) => output.push(text),
+            _ => {
+                print!("{}", text);
+                std::io::stdout().flush().ok();
+            }
         }
     }
 
@@ -138,6 +285,37 @@ mod tests {
         mode.show_warning("Warning").await;
     }
 
+    #[tokio::test]
+    async fn test_buffered_output_is_held_until_flush() {
+        let mode = DisplayMode::cli().buffered("sub-goal", Arc::new(Mutex::new(())));
+        assert!(!mode.is_cli());
+        mode.show_info("started").await;
+        mode.show_stream(&Segment::Answer("token".to_string()), false);
+        mode.end_stream();
+
+        let DisplayMode::Buffered(output) = &mode else { unreachable!() };
+        assert_eq!(*output.text.lock().unwrap(), "started\ntoken\n");
+        mode.flush().await;
+        assert!(output.text.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_repl_prompt_reads_with_line_reader() {
+        let display = DisplayManager::new().with_line_reader(|prompt| {
+            assert_eq!(prompt, "answer> ");
+            Some(" 8080 \n".to_string())
+        });
+        let display = Arc::new(Mutex::new(display));
+        let _bar = display.lock().await.start_planning("task");
+
+        let mode = DisplayMode::repl(display.clone());
+        assert!(mode.is_repl());
+        assert_eq!(mode.ask("Which port?").await.as_deref(), Some("8080"));
+
+        let closed = DisplayMode::repl(Arc::new(Mutex::new(DisplayManager::new().with_line_reader(|_| None))));
+        assert_eq!(closed.ask("Which port?").await, None);
+    }
+
     #[test]
     fn test_display_mode_clone() {
         let mode = DisplayMode::cli();
@@ -52,6 +52,10 @@ pub enum AgentError {
     #[error("I/O error: {0}")]
     IoError(#[from] std::io::Error),
 
+    /// Tool arguments that do not match the tool's schema
+    #[error("Invalid arguments for '{tool}': {}", errors.join("; "))]
+    InvalidArguments { tool: String, errors: Vec<String> },
+
     /// Configuration errors
     #[error("Configuration error: {0}")]
     ConfigError(String),
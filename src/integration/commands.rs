@@ -139,6 +139,15 @@ impl KnowledgeCommands {
         println!("  Avg tasks per session: {:.1}", cumulative.avg_tasks_per_session.to_string().cyan());
         println!("  Avg task duration:     {:.1}s", cumulative.avg_task_duration_secs.to_string().cyan());
         println!("  Total execution time:  {:.1}s", cumulative.total_execution_time_secs.to_string().cyan());
+
+        // Model statistics (from Ollama's eval counts)
+        let generation = &cumulative.generation;
+        println!("\n{}:", "Model".bold());
+        println!("  Tokens generated:      {}", generation.eval_tokens.to_string().cyan());
+        println!("  Generation speed:      {}", format!("{:.1} tok/s", generation.tokens_per_sec()).cyan());
+        println!("  Prompt tokens:         {}", generation.prompt_tokens.to_string().cyan());
+        println!("  Prompt processing:     {}", format!("{:.1}s", generation.prompt_eval_duration.as_secs_f64()).cyan());
+        println!("  Model load time:       {}", format!("{:.1}s", generation.load_duration.as_secs_f64()).cyan());
         
         println!();
         Ok(())
@@ -43,6 +43,9 @@ pub use display_mode::DisplayMode;
 // Shared execution logic for CLI and REPL
 pub mod execution;
 
+// Record/replay traces of agent runs
+pub mod trace;
+
 // PRD 11 Phase 2: RAG Pipeline
 pub mod rag;
 
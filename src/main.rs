@@ -4,7 +4,7 @@ use anyhow::Result;
 use clap::Parser;
 use indicatif::{ProgressBar, ProgressStyle};
 use colored::Colorize;
-use ollamabuddy::budget::DynamicBudgetManager;
+use ollamabuddy::budget::BudgetConfig;
 use ollamabuddy::integration::agent::RAGAgent;
 use ollamabuddy::validation::ValidationOrchestrator;
 use ollamabuddy::analysis::ConvergenceDetector;
@@ -13,22 +13,97 @@ use ollamabuddy::recovery::AdaptiveRecovery;
 use ollamabuddy::repl::{ReplSession, ReplConfig};
 use ollamabuddy::{
     models::ModelManager,
-    cli::{Args, Commands, Verbosity},
+    cli::{Args, Commands, Config, Verbosity},
     bootstrap::Bootstrap,
     doctor::Doctor,
-    agent::AgentOrchestrator,
+    agent::{AgentOrchestrator, Checkpoint, CheckpointStore, ModelRole, ModelRoutes},
     agent::orchestrator::AgentConfig,
-    tools::ToolRuntime,
+    tools::{ToolProfile, ToolRuntime},
     telemetry::{TelemetryCollector, TelemetryEvent, TelemetryDisplay},
+    streaming::{parse_action, split_thinking, GenerationStats, ThinkFilter},
+    trace::{TraceEvent, TraceMode, TraceRecorder, TraceReplayer},
 };
 
+/// Build the tool profile for a run from config, --online and project settings
+fn build_tool_profile(args: &Args, config: &Config, project_dir: &std::path::Path) -> Result<ToolProfile> {
+    let profile = ToolProfile::from_config(&config.tools)
+        .with_online(args.online || config.tools.online_enabled)
+        .with_project_overrides(project_dir)?;
+    Ok(profile)
+}
+
+/// Append OLLAMABUDDY.md and other instruction files to the system prompt
+fn with_project_instructions(system_prompt: String, working_dir: &std::path::Path, verbose: bool) -> String {
+    match ollamabuddy::agent::instructions::load(working_dir) {
+        Ok(files) => {
+            if verbose {
+                for file in &files {
+                    println!("📋 Loaded instructions from {}", file.path.display());
+                }
+            }
+            ollamabuddy::agent::instructions::merge_into_prompt(&system_prompt, &files)
+        }
+        Err(e) => {
+            eprintln!("Warning: failed to load instruction files: {}", e);
+            system_prompt
+        }
+    }
+}
+
+/// Prompt template for a model, including user templates in ~/.ollamabuddy/templates
+fn prompt_template(model: &str) -> ollamabuddy::agent::PromptTemplate {
+    use ollamabuddy::agent::{templates, PromptTemplate};
+    PromptTemplate::resolve(model, templates::default_dir().as_deref()).unwrap_or_else(|e| {
+        eprintln!("Warning: {}; using the built-in prompt template", e);
+        PromptTemplate::for_model(model)
+    })
+}
+
+/// Tool runtime jailed to `root`, with limits from the `[tools]` config table
+fn tool_runtime(
+    args: &Args,
+    config: &Config,
+    root: &std::path::Path,
+    project_dir: &std::path::Path,
+) -> Result<ToolRuntime> {
+    let context = ollamabuddy::tools::ToolContext::new(root.to_path_buf())
+        .with_max_extract_size(config.tools.max_extract_size);
+    let profile = build_tool_profile(args, config, project_dir)?;
+    Ok(ToolRuntime::with_context_and_profile(context, profile)?)
+}
+
+/// Run checkpoints, stored under the configured state directory (`paths.state_dir`)
+fn checkpoint_store(config: &Config) -> CheckpointStore {
+    CheckpointStore::new(config.state_dir().join("runs"))
+}
+
+/// Time, token and tool-runtime limits for a task from the CLI and config
+fn run_limits(args: &Args, config: &Config) -> ollamabuddy::budget::RunLimits {
+    args.run_limits(config.agent.timeout_minutes)
+}
+
+/// Whether to escalate to a stronger model mid-task (--auto-upgrade or config)
+fn auto_upgrade(args: &Args, config: &Config) -> bool {
+    args.auto_upgrade || config.advisor.auto_upgrade
+}
+
+/// Planner and summary models from the `[models]` config table
+fn model_routes(config: &Config) -> ModelRoutes {
+    ModelRoutes {
+        planner: config.models.planner_model.clone(),
+        summary: config.models.summary_model.clone(),
+    }
+}
 
 /// Run agent in interactive REPL mode
 /// Execute a task within REPL context with event emission
 async fn execute_task_in_repl(
     args: &Args,
+    config: &Config,
     task: &str,
     repl_session: &mut ReplSession,
+    resume: Option<Checkpoint>,
+    review: bool,
 ) -> Result<()> {
     use std::path::PathBuf;
     use std::time::Instant;
@@ -48,11 +123,14 @@ async fn execute_task_in_repl(
     // Show planning progress
     let pb = repl_session.display_mut().start_planning(task);
     
+    // A resumed run keeps the model and working directory it started with
+    let model = resume.as_ref().map_or_else(|| args.model.clone(), |c| c.model.clone());
+    
     // Bootstrap check (silent in REPL)
     let bootstrap = Bootstrap::new(
         args.host.clone(),
         args.port,
-        args.model.clone(),
+        model.clone(),
     );
     
     if !bootstrap.check_ollama_running().await? {
@@ -61,32 +139,49 @@ async fn execute_task_in_repl(
     }
     
     // Initialize components
-    let working_dir = args.cwd.clone().unwrap_or_else(|| {
-        std::env::current_dir().unwrap_or_else(|_| PathBuf::from("."))
-    });
+    let working_dir = match &resume {
+        Some(checkpoint) => checkpoint.working_dir.clone(),
+        None => args.cwd.clone().unwrap_or_else(|| {
+            std::env::current_dir().unwrap_or_else(|_| PathBuf::from("."))
+        }),
+    };
     
     let ollama_url = format!("http://{}:{}", args.host, args.port);
     
-    let config = AgentConfig {
+    let agent_config = AgentConfig {
         ollama_url,
-        model: args.model.clone(),
+        model: model.clone(),
         max_iterations: 50,
         verbose,
+        ask_policy: args.ask_policy(),
+        limits: run_limits(args, config),
+        auto_upgrade: auto_upgrade(args, config),
+        routes: model_routes(config),
+        keep_alive: config.ollama.keep_alive.clone(),
     };
     
-    let mut orchestrator = AgentOrchestrator::new(config)?;
+    let mut orchestrator = AgentOrchestrator::new(agent_config)?;
+    orchestrator.set_prompt_template(prompt_template(&model));
+
+    // Initialize telemetry (planner and summary calls are recorded too)
+    let telemetry = TelemetryCollector::new();
+    orchestrator.set_telemetry(telemetry.clone());
 
     // Use home directory as jail root for REPL mode to allow writes to ~/
     let jail_root = std::env::var("HOME")
         .map(PathBuf::from)
         .unwrap_or_else(|_| working_dir.clone());
-    let tool_runtime = ToolRuntime::new(&jail_root)?;
+    let tool_runtime = tool_runtime(args, config, &jail_root, &working_dir)?;
 
     // Update progress
     repl_session.display().update_progress(&pb, 0.3, Some("Initializing agent"));
     
-    // Initialize planning (async - LLM-based reasoning)
-    orchestrator.initialize_planning(task).await?;
+    // Initialize planning (async - LLM-based reasoning), or restore a checkpoint
+    if let Some(checkpoint) = &resume {
+        orchestrator.restore_checkpoint(checkpoint)?;
+    } else {
+        orchestrator.initialize_planning(task).await?;
+    }
 
     // Update progress
     repl_session.display().update_progress(&pb, 0.6, Some("Creating execution plan"));
@@ -94,15 +189,8 @@ async fn execute_task_in_repl(
     // Get context from session
     let context = repl_session.get_context();
     
-    // Build system prompt with full tool descriptions
-    let tool_descriptions = vec![
-        "list_dir: List files and directories. Args: path (string, required), recursive (bool, optional, default false)",
-        "read_file: Read contents of a text file. Args: path (string, required)",
-        "write_file: Write or append content to a file. Args: path (string, required), content (string, required), append (bool, optional, default false)",
-        "run_command: Execute a system command (supports shell pipes/redirects). Args: command (string, required), args (array of strings, optional), timeout_seconds (number, optional, default 60)",
-        "system_info: Get system information. Args: info_type (string, optional: 'os', 'cpu', 'memory', 'disk', 'all', default 'all')",
-        "web_fetch: Fetch content from a URL. Args: url (string, required), method (string, optional: 'GET' or 'POST', default 'GET'), timeout_seconds (number, optional, default 30)",
-    ];
+    // Build system prompt with descriptions of the enabled tools only
+    let tool_descriptions = tool_runtime.get_registry().prompt_descriptions();
     
     let tools_formatted = tool_descriptions.join("\n  ");
     
@@ -116,6 +204,12 @@ RESPONSE FORMAT - Think out loud, then provide JSON:
 Tool call format:
 {{"type": "tool_call", "tool": "tool_name", "args": {{"key": "value"}}}}
 
+Multiple independent tool calls (read-only calls run in parallel, writes run in order):
+{{"type": "tool_calls", "calls": [{{"tool": "read_file", "args": {{"path": "a.rs"}}}}, {{"tool": "list_dir", "args": {{"path": "src"}}}}]}}
+
+Question format (only when the task is ambiguous and you cannot proceed without the user):
+{{"type": "ask", "question": "what you need to know"}}
+
 Completion format:
 {{"type": "final", "result": "description of what was accomplished"}}
 
@@ -215,13 +309,18 @@ Now begin!"#,
         if !context.is_empty() { format!("Previous context:\n{}", context) } else { String::new() }
     );
     
-    orchestrator.add_system_prompt(system_prompt);
-    orchestrator.add_user_goal(task.to_string());
+    // Restored memory already holds the prompt and goal
+    if resume.is_none() {
+        orchestrator.add_system_prompt(with_project_instructions(system_prompt, &working_dir, verbose));
+        orchestrator.add_user_goal(task.to_string());
+    }
     orchestrator.set_goal(task.to_string());
     
     // Transition state machine
     use ollamabuddy::agent::StateEvent;
-    orchestrator.transition(StateEvent::StartSession)?;
+    if resume.is_none() {
+        orchestrator.transition(StateEvent::StartSession)?;
+    }
     
     // Complete planning phase
     repl_session.display().update_progress(&pb, 1.0, Some("Planning complete"));
@@ -236,34 +335,40 @@ Now begin!"#,
     
     repl_session.display().show_info(&format!("Planning complete ({}ms)", planning_duration));
     
-    // Initialize telemetry
-    let telemetry = TelemetryCollector::new();
-    
-    // Calculate dynamic budget
-    let task_complexity = {
-        let base_complexity = (task.len() as f64 / 200.0).min(0.5);
-        let keyword_boost = if task.to_lowercase().contains("analyze") ||
-                                task.to_lowercase().contains("complex") ||
-                                task.to_lowercase().contains("multiple") {
-            0.3
-        } else {
-            0.0
-        };
-        (base_complexity + keyword_boost).min(1.0)
-    };
-    
-    let mut budget_manager = DynamicBudgetManager::new();
-    let max_iterations = budget_manager.calculate_budget(task_complexity);
+    // Agent output and questions go through a REPL display, so answers are
+    // read with a line editor rather than raw stdin
+    let display_mode = ollamabuddy::DisplayMode::repl(Arc::new(Mutex::new(
+        ollamabuddy::repl::DisplayManager::new(),
+    )));
+    
+    // --plan-only or /plan: review the plan before anything runs
+    if review
+        && resume.is_none()
+        && !ollamabuddy::execution::review_plan(&mut orchestrator, task, &display_mode).await?
+    {
+        repl_session.display().show_info("Plan rejected; nothing was run.");
+        return Ok(());
+    }
     
-    if verbose {
+    // Checkpoint after every iteration so the run can be resumed with /resume
+    if let Some(checkpoint) = &resume {
         repl_session.display().show_info(&format!(
-            "Task complexity: {:.2}, Allocated iterations: {}",
-            task_complexity, max_iterations
+            "Resuming run {} at iteration {}",
+            checkpoint.run_id, checkpoint.iteration
         ));
     }
+    let run = resume.unwrap_or_else(|| Checkpoint::new(task, model.clone(), working_dir.clone()));
+    orchestrator.enable_checkpoints(checkpoint_store(config), run);
+    
+    // The shared loop sizes its iteration budget from the task's complexity;
+    // delegation splits one such allocation between its sub-agents
+    let max_iterations = if args.delegate {
+        ollamabuddy::execution::allocate_budget(&mut orchestrator, task, BudgetConfig::default().max_budget)
+            .get_remaining()
+    } else {
+        BudgetConfig::default().max_budget
+    };
     
-    // Create display mode for REPL (use CLI mode for now as DisplayManager is not Clone)
-    let display_mode = ollamabuddy::DisplayMode::cli();
     
     // Emit execution started event
     repl_session.event_bus().emit(
@@ -272,16 +377,32 @@ Now begin!"#,
         }
     ).await;
     
-    // Execute task using shared function
-    let execution_result = ollamabuddy::execution::execute_agent_task(
-        &mut orchestrator,
-        &tool_runtime,
-        &telemetry,
-        max_iterations,
-        task,
-        verbose,
-        &display_mode,
-    ).await?;
+    // Execute task using shared function (recorded when --trace is given)
+    let trace = trace_mode(args)?;
+    let execution_result = if args.delegate {
+        ollamabuddy::execution::execute_delegated_task(
+            &mut orchestrator,
+            &tool_runtime,
+            &telemetry,
+            max_iterations,
+            config.tools.max_parallel,
+            task,
+            verbose,
+            &display_mode,
+            &trace,
+        ).await?
+    } else {
+        ollamabuddy::execution::execute_agent_task(
+            &mut orchestrator,
+            &tool_runtime,
+            &telemetry,
+            max_iterations,
+            task,
+            verbose,
+            &display_mode,
+            &trace,
+        ).await?
+    };
     
     // Emit completion event
     repl_session.event_bus().emit(
@@ -304,9 +425,26 @@ Now begin!"#,
         files_modified: execution_result.files_touched.iter()
             .map(|s| PathBuf::from(s))
             .collect(),
+        escalations: execution_result.escalations.clone(),
     };
     
     repl_session.record_task(record);
+
+    // Feed the learning system's cumulative statistics
+    if let Some(rag_agent) = repl_session.rag_agent() {
+        let mut learned = ollamabuddy::session::recording::TaskRecord::new(task.to_string())
+            .with_metrics(execution_result.iterations as usize, execution_result.duration.as_secs_f64())
+            .with_generation(telemetry.get_stats().generation());
+        learned = if execution_result.success {
+            learned.success()
+        } else {
+            learned.failure(execution_result.output.clone())
+        };
+        for file in &execution_result.files_touched {
+            learned.add_file(file.clone());
+        }
+        rag_agent.record_task(learned).await;
+    }
     
     // Show summary
     if execution_result.success {
@@ -325,12 +463,15 @@ Now begin!"#,
             execution_result.duration.as_secs_f64(),
             execution_result.iterations
         ));
+        if let Some(run_id) = orchestrator.resumable_run_id() {
+            repl_session.display().show_info(&format!("Resume with: /resume {}", run_id));
+        }
     }
     
     Ok(())
 }
 
-async fn run_repl(args: &Args) -> Result<()> {
+async fn run_repl(args: &Args, config: &Config) -> Result<()> {
     // Initialize REPL session with history
     let history_path = std::env::home_dir()
         .unwrap_or_else(|| std::path::PathBuf::from("."))
@@ -371,13 +512,42 @@ async fn run_repl(args: &Args) -> Result<()> {
                             break;
                         }
                         
+                        // /resume runs the agent, so it is handled here
+                        if let ollamabuddy::repl::commands::Command::Resume { run_id } =
+                            repl_session.parse_command(&input)
+                        {
+                            let result = match load_resumable_run(config, run_id.as_deref()) {
+                                Ok(checkpoint) => {
+                                    let task = checkpoint.task.clone();
+                                    execute_task_in_repl(args, config, &task, &mut repl_session, Some(checkpoint), false).await
+                                }
+                                Err(e) => Err(e),
+                            };
+                            if let Err(e) = result {
+                                repl_session.display_mut().finish_with_error(&format!("Resume failed: {}", e));
+                            }
+                            continue;
+                        }
+                        
+                        // /plan reviews the plan before running the task
+                        if let ollamabuddy::repl::commands::Command::Plan { task } =
+                            repl_session.parse_command(&input)
+                        {
+                            if !task.is_empty() {
+                                if let Err(e) = execute_task_in_repl(args, config, &task, &mut repl_session, None, true).await {
+                                    repl_session.display_mut().finish_with_error(&format!("Task execution failed: {}", e));
+                                }
+                            }
+                            continue;
+                        }
+                        
                         // Check if it was a command (already handled)
                         if ollamabuddy::repl::commands::is_command(&input) {
                             continue;
                         }
                         
                         // Execute the task with full agent integration
-                        match execute_task_in_repl(&args, &input, &mut repl_session).await {
+                        match execute_task_in_repl(args, config, &input, &mut repl_session, None, args.plan_only).await {
                             Ok(()) => {
                                 // Task executed successfully
                             }
@@ -409,66 +579,35 @@ async fn run_repl(args: &Args) -> Result<()> {
     
     // Save session history
     repl_session.save()?;
-    
-    Ok(())
-}
-
-/// Extract complete JSON object from text that may contain thinking before JSON
-/// Returns the JSON substring, or the original text if no complete JSON found
-fn extract_json_object(text: &str) -> Option<&str> {
-    // Find all opening braces
-    for (i, c) in text.char_indices() {
-        if c == '{' {
-            // Try to find matching closing brace
-            let mut depth = 0;
-            let mut in_string = false;
-            let mut escape_next = false;
-
-            for (j, ch) in text[i..].char_indices() {
-                if escape_next {
-                    escape_next = false;
-                    continue;
-                }
 
-                match ch {
-                    '\\' if in_string => escape_next = true,
-                    '"' if !in_string => in_string = true,
-                    '"' if in_string => in_string = false,
-                    '{' if !in_string => depth += 1,
-                    '}' if !in_string => {
-                        depth -= 1;
-                        if depth == 0 {
-                            // Found complete JSON object
-                            return Some(&text[i..=i+j]);
-                        }
-                    }
-                    _ => {}
-                }
-            }
+    // Fold this session into the cumulative statistics shown by /stats
+    if let Some(rag_agent) = repl_session.rag_agent() {
+        if let Err(e) = rag_agent.end_session().await {
+            eprintln!("Warning: failed to save session statistics: {}", e);
         }
     }
-    None
+    
+    Ok(())
 }
 
 #[tokio::main]
 async fn main() -> Result<()> {
     let mut args = Args::parse();
-    
-    // Load config and apply default model if set
-    if let Ok(config) = ollamabuddy::config::Config::load() {
-        if let Some(default_model) = config.get_default_model() {
-            // Only override if user didn't specify --model flag
-            // Check if model is still the default value
-            if args.model == "qwen2.5:7b-instruct" {
-                args.model = default_model.to_string();
-            }
+    let config = Config::load(args.config.clone())?;
+    
+    // Apply the executor (or default) model from [models] if set
+    if let Some(default_model) = config.models.get_executor_model() {
+        // Only override if user didn't specify --model flag
+        // Check if model is still the default value
+        if args.model == "qwen2.5:7b-instruct" {
+            args.model = default_model.to_string();
         }
     }
 
     match &args.command {
         Some(Commands::Start) => {
             // Start interactive REPL mode
-            run_repl(&args).await?;
+            run_repl(&args, &config).await?;
         }
         Some(Commands::Doctor) => {
             run_doctor(&args).await?;
@@ -477,7 +616,7 @@ async fn main() -> Result<()> {
             use ollamabuddy::cli::ModelsCommand;
             match models_cmd {
                 ModelsCommand::List => {
-                    handle_models_list(&args).await?;
+                    handle_models_list(&args, &config).await?;
                 }
                 ModelsCommand::Pull { name } => {
                     handle_models_pull(&args, name).await?;
@@ -492,7 +631,7 @@ async fn main() -> Result<()> {
                     handle_models_use(&args, name).await?;
                 }
                 ModelsCommand::Current => {
-                    handle_models_current(&args).await?;
+                    handle_models_current(&args, &config).await?;
                 }
             }
         }
@@ -502,11 +641,23 @@ async fn main() -> Result<()> {
         Some(Commands::Config) => {
             show_config(&args)?;
         }
+        Some(Commands::Replay { trace, live_tools }) => {
+            run_replay(&args, &config, trace, *live_tools).await?;
+        }
+        Some(Commands::Resume { run_id, list }) => {
+            if *list {
+                list_runs(&config)?;
+            } else {
+                let checkpoint = load_resumable_run(&config, run_id.as_deref())?;
+                let task = checkpoint.task.clone();
+                run_agent(&args, &config, &task, Some(checkpoint)).await?;
+            }
+        }
         None => {
             // No subcommand - run single task or show help
             if let Some(task) = &args.task {
                 // Run single task (traditional CLI mode)
-                run_agent(&args, task).await?;
+                run_agent(&args, &config, task, None).await?;
             } else {
                 // No task and no REPL - show usage
                 println!("OllamaBuddy v0.5.0 - Terminal Agent");
@@ -516,6 +667,8 @@ async fn main() -> Result<()> {
                 println!("  ollamabuddy doctor            System health checks");
                 println!("  ollamabuddy models            List Ollama models");
                 println!("  ollamabuddy config            Show configuration");
+                println!("  ollamabuddy resume [run-id]   Resume an interrupted run");
+                println!("  ollamabuddy replay <trace>    Replay a recorded trace");
                 println!("  ollamabuddy clean             Clear state/logs");
                 println!("\nExample:");
                 println!("  ollamabuddy \"List all .rs files and count lines of code\"");
@@ -528,16 +681,19 @@ async fn main() -> Result<()> {
 }
 
 
-async fn run_agent(args: &Args, task: &str) -> Result<()> {
+async fn run_agent(args: &Args, config: &Config, task: &str, resume: Option<Checkpoint>) -> Result<()> {
     // TODO PRD 10a Phase 3: Refactor to use ollamabuddy::execution::execute_agent_task()
     // Current implementation works, but could be simplified by using shared execution logic
     use std::path::PathBuf;
     
+    // A resumed run keeps the model and working directory it started with
+    let model = resume.as_ref().map_or_else(|| args.model.clone(), |c| c.model.clone());
+    
     // 1. Bootstrap check
     let bootstrap = Bootstrap::new(
         args.host.clone(),
         args.port,
-        args.model.clone(),
+        model.clone(),
     );
     
     if !bootstrap.check_ollama_running().await? {
@@ -548,32 +704,50 @@ Start Ollama with: ollama serve");
     }
     
     // 2. Initialize components
-    let working_dir = args.cwd.clone().unwrap_or_else(|| {
-        std::env::current_dir().unwrap_or_else(|_| PathBuf::from("."))
-    });
+    let working_dir = match &resume {
+        Some(checkpoint) => checkpoint.working_dir.clone(),
+        None => args.cwd.clone().unwrap_or_else(|| {
+            std::env::current_dir().unwrap_or_else(|_| PathBuf::from("."))
+        }),
+    };
     
     let ollama_url = format!("http://{}:{}", args.host, args.port);
     
-    let config = AgentConfig {
+    let agent_config = AgentConfig {
         ollama_url,
-        model: args.model.clone(),
+        model: model.clone(),
         max_iterations: 50,
         verbose: matches!(args.verbosity(), Verbosity::Verbose | Verbosity::VeryVerbose),
+        ask_policy: args.ask_policy(),
+        limits: run_limits(args, config),
+        auto_upgrade: auto_upgrade(args, config),
+        routes: model_routes(config),
+        keep_alive: config.ollama.keep_alive.clone(),
     };
 
-    let mut orchestrator = AgentOrchestrator::new(config)?;
+    let mut orchestrator = AgentOrchestrator::new(agent_config)?;
+    orchestrator.set_prompt_template(prompt_template(&model));
+
+    // Initialize telemetry (planner and summary calls are recorded too)
+    let telemetry = TelemetryCollector::new();
+    orchestrator.set_telemetry(telemetry.clone());
 
     // Use home directory as jail root for CLI mode to allow writes to ~/
     let jail_root = std::env::var("HOME")
         .map(PathBuf::from)
         .unwrap_or_else(|_| working_dir.clone());
-    let tool_runtime = ToolRuntime::new(&jail_root)?;
+    let tool_runtime = tool_runtime(args, config, &jail_root, &working_dir)?;
     
     // Initialize advanced planning system (PRD 5) - uses LLM for actual reasoning
-    if matches!(args.verbosity(), Verbosity::Verbose | Verbosity::VeryVerbose) {
-        println!("🧠 Initializing advanced planning system...");
+    // A resumed run restores its goal tree, memory and state instead
+    if let Some(checkpoint) = &resume {
+        orchestrator.restore_checkpoint(checkpoint)?;
+    } else {
+        if matches!(args.verbosity(), Verbosity::Verbose | Verbosity::VeryVerbose) {
+            println!("🧠 Initializing advanced planning system...");
+        }
+        orchestrator.initialize_planning(task).await?;
     }
-    orchestrator.initialize_planning(task).await?;
 
     if matches!(args.verbosity(), Verbosity::Verbose | Verbosity::VeryVerbose) {
         if let Some(progress) = orchestrator.planning_progress() {
@@ -582,17 +756,27 @@ Start Ollama with: ollama serve");
     }
     
     // Set system prompt with tool instructions
-    // Build detailed tool descriptions for better model understanding
-    let tool_descriptions = vec![
-        "list_dir: List files and directories. Args: path (string, required), recursive (bool, optional, default false)",
-        "read_file: Read contents of a text file. Args: path (string, required)",
-        "write_file: Write or append content to a file. Args: path (string, required), content (string, required), append (bool, optional, default false)",
-        "run_command: Execute a system command (supports shell pipes/redirects). Args: command (string, required), args (array of strings, optional), timeout_seconds (number, optional, default 60)",
-        "system_info: Get system information. Args: info_type (string, optional: 'os', 'cpu', 'memory', 'disk', 'all', default 'all')",
-        "web_fetch: Fetch content from a URL. Args: url (string, required), method (string, optional: 'GET' or 'POST', default 'GET'), timeout_seconds (number, optional, default 30)",
-    ];
+    // Build tool descriptions for the enabled tools only
+    let tool_descriptions = tool_runtime.get_registry().prompt_descriptions();
     
     let tools_formatted = tool_descriptions.join("\n  ");
+
+    let tool_guidelines: Vec<&str> = [
+        ("list_dir", "- list_dir: Use to explore directories and find files"),
+        ("read_file", "- read_file: Use to read file contents (text files only); pass start_line/end_line for large files"),
+        ("outline", "- outline: Use to map a source file or directory (symbols with line numbers) before reading code"),
+        ("write_file", "- write_file: Use to create or modify files"),
+        ("run_command", "- run_command: Use for system commands, file operations, shell pipes (find, grep, wc, etc.)"),
+        ("run_tests", "- run_tests: Use to run the test suite (or a filter) and get structured pass/fail results instead of raw cargo/pytest/npm/go output"),
+        ("archive_list", "- archive_list / archive_extract / archive_create: Use for .tar, .tar.gz and .zip bundles (e.g. log archives) instead of tar/unzip commands"),
+        ("system_info", "- system_info: Use to check OS, CPU, memory, disk space"),
+        ("web_fetch", "- web_fetch: Use to download web content"),
+        ("query_data", "- query_data: Use to extract specific values from JSON/YAML/TOML/CSV instead of reading the whole file"),
+    ]
+    .iter()
+    .filter(|(tool, _)| tool_runtime.has_tool(tool))
+    .map(|(_, line)| *line)
+    .collect();
     
     let system_prompt = format!(r#"You are an autonomous AI agent that helps users complete tasks using available tools.
 
@@ -604,6 +788,12 @@ RESPONSE FORMAT - Think out loud, then provide JSON:
 Tool call format:
 {{\"type\": \"tool_call\", \"tool\": \"tool_name\", \"args\": {{\"key\": \"value\"}}}}
 
+Multiple independent tool calls (read-only calls run in parallel, writes run in order):
+{{\"type\": \"tool_calls\", \"calls\": [{{\"tool\": \"read_file\", \"args\": {{\"path\": \"a.rs\"}}}}, {{\"tool\": \"list_dir\", \"args\": {{\"path\": \"src\"}}}}]}}
+
+Question format (only when the task is ambiguous and you cannot proceed without the user):
+{{\"type\": \"ask\", \"question\": \"what you need to know\"}}
+
 Completion format:
 {{\"type\": \"final\", \"result\": \"description of what was accomplished\"}}
 
@@ -611,12 +801,7 @@ AVAILABLE TOOLS:
   {}
 
 TOOL SELECTION GUIDELINES:
-- list_dir: Use to explore directories and find files
-- read_file: Use to read file contents (text files only)
-- write_file: Use to create or modify files
-- run_command: Use for system commands, file operations, shell pipes (find, grep, wc, etc.)
-- system_info: Use to check OS, CPU, memory, disk space
-- web_fetch: Use to download web content
+{}
 
 CRITICAL RULES:
 1. Always explain your thinking before outputting JSON
@@ -719,21 +904,34 @@ Example 3:
 I've successfully counted all the Rust files. The task is complete.
 {{\"type\": \"final\", \"result\": \"Found 32 .rs files in src directory\"}}
 
-Now begin!"#, tools_formatted);
+Now begin!"#, tools_formatted, tool_guidelines.join("\n"));
     
-    orchestrator.add_system_prompt(system_prompt);
-    
-    // Initialize telemetry
-    let telemetry = TelemetryCollector::new();
     let display = TelemetryDisplay::new(telemetry.clone(), args.verbosity());
     
-    // 3. Set up agent with task
-    orchestrator.add_user_goal(task.to_string());
+    // 3. Set up agent with task (restored memory already holds prompt and goal)
+    if resume.is_none() {
+        let verbose = matches!(args.verbosity(), Verbosity::Verbose | Verbosity::VeryVerbose);
+        orchestrator.add_system_prompt(with_project_instructions(system_prompt, &working_dir, verbose));
+        orchestrator.add_user_goal(task.to_string());
+    }
     
     // PRD 7: Initialize working memory with goal
     orchestrator.set_goal(task.to_string());
     
-    println!("OllamaBuddy Agent Starting...");
+    // --plan-only: review the plan before anything runs
+    if args.plan_only
+        && resume.is_none()
+        && !ollamabuddy::execution::review_plan(&mut orchestrator, task, &ollamabuddy::DisplayMode::cli()).await?
+    {
+        println!("Plan rejected; nothing was run.");
+        return Ok(());
+    }
+    
+    if resume.is_some() {
+        println!("OllamaBuddy Agent Resuming (iteration {})...", orchestrator.iterations());
+    } else {
+        println!("OllamaBuddy Agent Starting...");
+    }
     println!("Task: {}", task);
     println!("Working Directory: {:?}", working_dir);
     println!("Available Tools: {}", tool_runtime.tool_names().join(", "));
@@ -741,12 +939,14 @@ Now begin!"#, tools_formatted);
     
     // 4. Start state machine
     use ollamabuddy::agent::StateEvent;
-    orchestrator.transition(StateEvent::StartSession)?;
-    telemetry.record(TelemetryEvent::StateTransition {
-        from: "Init".to_string(),
-        to: "Planning".to_string(),
-        timestamp: std::time::Instant::now(),
-    });
+    if resume.is_none() {
+        orchestrator.transition(StateEvent::StartSession)?;
+        telemetry.record(TelemetryEvent::StateTransition {
+            from: "Init".to_string(),
+            to: "Planning".to_string(),
+            timestamp: std::time::Instant::now(),
+        });
+    }
     
     // PRD 7: Query memory before planning
     let verbose = matches!(args.verbosity(), Verbosity::Verbose | Verbosity::VeryVerbose);
@@ -759,59 +959,91 @@ Now begin!"#, tools_formatted);
     }
     
     // Get tool recommendations from experience
-    let available_tools = vec![
-        "list_dir".to_string(),
-        "read_file".to_string(),
-        "write_file".to_string(),
-        "run_command".to_string(),
-        "system_info".to_string(),
-        "web_fetch".to_string(),
-    ];
+    let available_tools = tool_runtime.tool_names();
     let tool_recommendations = orchestrator.get_tool_recommendations(task, &available_tools);
     if !tool_recommendations.is_empty() && verbose {
         eprintln!("[MEMORY] Got {} tool recommendations from experience", tool_recommendations.len());
     }
     
     // 5. Main agent loop
-    // PRD 8: Initialize dynamic budget manager
-    let mut budget_manager = DynamicBudgetManager::new();
-    
     // PRD 9: Initialize validation system
     let mut validation_orchestrator = ValidationOrchestrator::new();
     let mut convergence_detector = ConvergenceDetector::new();
     let mut adaptive_recovery = AdaptiveRecovery::new();
+    let mut consecutive_parse_failures = 0;
     let mut tool_results_log: Vec<ollamabuddy::tools::types::ToolResult> = Vec::new();
+    let mut resources = ollamabuddy::budget::ResourceBudget::new(orchestrator.config().limits);
+    let mut winding_down = false;
+    let mut json_failures = 0;
+    let mut escalations: Vec<ollamabuddy::advisor::ModelEscalation> = Vec::new();
+    
+    // Checkpoint after every iteration so the run can be resumed
+    let run = resume.unwrap_or_else(|| Checkpoint::new(task, model.clone(), working_dir.clone()));
+    let run_id = run.run_id.clone();
+    orchestrator.enable_checkpoints(checkpoint_store(config), run);
+    if verbose {
+        eprintln!("[CHECKPOINT] Run ID: {}", run_id);
+    }
     
-    // Estimate initial complexity (simple heuristic based on task length and keywords)
-    let task_complexity = {
-        let base_complexity = (task.len() as f64 / 200.0).min(0.5);
-        let keyword_boost = if task.to_lowercase().contains("analyze") ||
-                                task.to_lowercase().contains("complex") ||
-                                task.to_lowercase().contains("multiple") {
-            0.3
-        } else {
-            0.0
-        };
-        (base_complexity + keyword_boost).min(1.0)
-    };
-    
-    // Calculate dynamic budget based on complexity
-    let max_iterations = budget_manager.calculate_budget(task_complexity);
+    // PRD 8: Size the iteration budget from task complexity (or keep the resumed run's budget)
+    let max_iterations = BudgetConfig::default().max_budget;
+    let mut budget_manager = ollamabuddy::execution::allocate_budget(&mut orchestrator, task, max_iterations);
     
     if verbose {
-        eprintln!("[BUDGET] Task complexity: {:.2}, Allocated iterations: {}", task_complexity, max_iterations);
+        eprintln!(
+            "[BUDGET] Task complexity: {:.2}, Allocated iterations: {}",
+            budget_manager.get_complexity(),
+            budget_manager.get_allocated()
+        );
+    }
+    
+    let mut iteration = orchestrator.iterations();
+    
+    // Record the run when --trace is given
+    let trace = trace_mode(args)?;
+    
+    // --delegate: run each atomic sub-goal with its own sub-agent instead
+    if args.delegate {
+        let result = ollamabuddy::execution::execute_delegated_task(
+            &mut orchestrator,
+            &tool_runtime,
+            &telemetry,
+            budget_manager.get_remaining(),
+            config.tools.max_parallel,
+            task,
+            verbose,
+            &ollamabuddy::DisplayMode::cli(),
+            &trace,
+        ).await?;
+        
+        println!("\n{}", if result.success { "Task Complete!" } else { "Task incomplete" });
+        println!("{}", result.output);
+        println!();
+        display.display_summary();
+        return Ok(());
     }
-    let mut iteration = 0;
     
-    while iteration < max_iterations && !matches!(
+    ollamabuddy::execution::record_run_started(&trace, &orchestrator, &tool_runtime, task, max_iterations, false);
+    
+    while iteration < budget_manager.get_allocated() && !matches!(
         orchestrator.state(), 
         ollamabuddy::agent::AgentState::Final | ollamabuddy::agent::AgentState::Error
     ) {
         iteration += 1;
+        budget_manager.increment_iteration();
+
+        ollamabuddy::execution::check_resource_budget(
+            &mut orchestrator,
+            &mut resources,
+            &mut winding_down,
+            task,
+            &ollamabuddy::DisplayMode::cli(),
+        )
+        .await;
 
         // Check context and compress if needed
         let tokens_before = orchestrator.token_count();
-        orchestrator.maybe_compress()?;
+        orchestrator.maybe_compress().await?;
         let tokens_after = orchestrator.token_count();
         if tokens_before != tokens_after {
             telemetry.record(TelemetryEvent::ContextCompression {
@@ -829,18 +1061,32 @@ Now begin!"#, tools_formatted);
             println!("Prompt ({} tokens)", orchestrator.token_count());
         }
         
-        // Stream response from Ollama
-        let client = orchestrator.client();
-        let mut stream = client.generate_stream(prompt).await?;
+        trace.record(TraceEvent::ModelRequest {
+            iteration,
+            prompt: prompt.clone(),
+        });
+        
+        // Stream response from Ollama, continuing the last reply's context
+        let turn = orchestrator.turn_prompt();
+        let model = orchestrator.client().model().to_string();
+        let call_start = std::time::Instant::now();
+        let mut stream = orchestrator
+            .client()
+            .generate_stream_with_options(turn.text.clone(), &turn.options())
+            .await?;
         
         // Stream thinking in real-time (no progress bar needed)
         println!("\nAgent:");
 
         let mut response_text_accumulator = String::new();
         let mut token_count = 0;
+        let mut turn_stats: Option<GenerationStats> = None;
+        let mut reply_context = None;
+        let mut think_filter = ThinkFilter::new();
+        let display_mode = ollamabuddy::DisplayMode::cli();
+        let expand_thinking = matches!(args.verbosity(), Verbosity::Verbose | Verbosity::VeryVerbose);
 
         use futures_util::StreamExt;
-        use std::io::Write;
 
         while let Some(chunk_result) = stream.next().await {
             let chunk_bytes = chunk_result?;
@@ -857,215 +1103,293 @@ Now begin!"#, tools_formatted);
                         timestamp: std::time::Instant::now(),
                     });
 
-                    // Stream thinking text in real-time (always, not just verbose)
-                    print!("{}", token);
-                    std::io::stdout().flush().ok();
+                    // Stream in real-time; <think> blocks are dimmed or collapsed
+                    for segment in think_filter.push(token) {
+                        display_mode.show_stream(&segment, expand_thinking);
+                    }
                 }
+                turn_stats = turn_stats.or(GenerationStats::from_chunk(&ollama_response));
+                reply_context = reply_context.or(ollamabuddy::streaming::reply_context(&ollama_response));
             }
         }
+        drop(stream);
+        orchestrator.continue_from_reply(reply_context);
 
+        for segment in think_filter.finish() {
+            display_mode.show_stream(&segment, expand_thinking);
+        }
         println!(); // New line after streaming
-        
-        // Parse accumulated response as AgentMsg
-        if !response_text_accumulator.is_empty() {
-            let trimmed = response_text_accumulator.trim();
 
-            // Unescape JSON first (model outputs escaped quotes)
-            let unescaped = trimmed.replace(r#"\""#, r#"""#);
+        // Prefer Ollama's token count; chunks only approximate tokens
+        if let Some(stats) = &turn_stats {
+            token_count = stats.eval_tokens as usize;
+            telemetry.record_generation(stats);
+            telemetry.record_prompt_cache(turn.prompt_tokens, stats);
+            if expand_thinking {
+                println!(
+                    "Turn {}: {}, {:.0}% of prompt cached",
+                    iteration,
+                    stats,
+                    stats.cache_hit_ratio(turn.prompt_tokens) * 100.0
+                );
+            }
+        }
+        resources.record_tokens(token_count as u64);
+        telemetry.record(TelemetryEvent::ModelCall {
+            role: ModelRole::Executor,
+            model,
+            tokens: token_count,
+            duration_ms: call_start.elapsed().as_millis() as u64,
+            timestamp: std::time::Instant::now(),
+        });
+        
+        trace.record(TraceEvent::ModelResponse {
+            iteration,
+            response: response_text_accumulator.clone(),
+        });
+        
+        // Parse accumulated response as AgentMsg, ignoring <think> blocks
+        let answer = split_thinking(&response_text_accumulator).answer;
+        let logged_results = tool_results_log.len();
+        // Set when recovery or convergence flags trouble the advisor should see
+        let mut trouble = false;
+        match parse_action(&answer) {
+            Ok(agent_msg) => {
+                consecutive_parse_failures = 0;
+                use ollamabuddy::types::AgentMsg;
+                
+                match agent_msg {
+                    AgentMsg::ToolCall { .. } | AgentMsg::ToolCalls { .. } if winding_down => {
+                        println!("[WARNING] Budget exhausted; tool calls were not executed");
+                    }
+                    AgentMsg::ToolCall { tool, args } => {
+                        println!("Tool call: {} with args: {:?}", tool, args);
+                        
+                        let tool_start = std::time::Instant::now();
+                        telemetry.record(TelemetryEvent::ToolStarted {
+                            tool: tool.clone(),
+                            timestamp: tool_start,
+                        });
+                        
+                        // Transition to executing
+                        orchestrator.transition(StateEvent::ToolCall)?;
+                        
+                        // Show progress bar for tool execution
+                        let pb = ProgressBar::new_spinner();
+                        pb.set_style(
+                            ProgressStyle::default_spinner()
+                                .template("{spinner:.green} {msg}")
+                                .unwrap()
+                        );
+                        pb.set_message(format!("Executing: {}", tool));
+                        pb.enable_steady_tick(std::time::Duration::from_millis(100));
+                        
+                        // Execute tool
+                        let result = trace.execute_tool(&tool_runtime, &tool, &serde_json::to_value(&args)?).await;
+                        pb.finish_and_clear();
+                        
+                        match result {
+                            Ok(tool_output) => {
+                                let duration = tool_start.elapsed().as_millis() as u64;
+                                telemetry.record(TelemetryEvent::ToolCompleted {
+                                    tool: tool.clone(),
+                                    duration_ms: duration,
+                                    success: tool_output.success,
+                                    timestamp: std::time::Instant::now(),
+                                });
+
+                                // Failed results (e.g. invalid arguments) carry the error
+                                let text = tool_output.feedback_text();
+                                if tool_output.success {
+                                    println!("[OK] Tool result ({}ms): {}", duration, text);
+                                } else {
+                                    println!("[WARNING] {} failed: {}", tool, text);
+                                }
+                                
+                                // PRD 9: Collect tool result for validation
+                                tool_results_log.push(tool_output.clone());
+                                
+                                // Add tool result to memory
+                                use ollamabuddy::types::MemoryEntry;
+                                orchestrator.memory_mut().add(MemoryEntry::ToolCall {
+                                    tool: tool.clone(),
+                                    args: args.clone(),
+                                    timestamp: std::time::SystemTime::now()
+                                        .duration_since(std::time::UNIX_EPOCH)
+                                        .unwrap()
+                                        .as_secs(),
+                                });
+                                
+                                orchestrator.memory_mut().add(MemoryEntry::ToolResult {
+                                    tool: tool.clone(),
+                                    output: text.clone(),
+                                    success: tool_output.success,
+                                    duration_ms: tool_output.duration_ms,
+                                    timestamp: std::time::SystemTime::now()
+                                        .duration_since(std::time::UNIX_EPOCH)
+                                        .unwrap()
+                                        .as_secs(),
+                                });
+
+                                // Add reflection prompt after tool execution
+                                let reflection_prompt = format!(
+                                    "\n\nREFLECTION: You just executed '{}'. Result: {}\n\n\
+                                    Original task: {}\n\n\
+                                    Has the task been FULLY completed?\n\
+                                    - If YES: Output {{\"type\": \"final\", \"result\": \"description of what you accomplished\"}}\n\
+                                    - If NO: Either call another tool OR explain what still needs to be done.",
+                                    tool,
+                                    text.chars().take(200).collect::<String>(),
+                                    task
+                                );
 
-            // Extract JSON from the response (thinking comes before)
-            // Find complete JSON object by matching braces
-            let json_str = extract_json_object(&unescaped).unwrap_or(&unescaped);
+                                orchestrator.memory_mut().add(MemoryEntry::SystemPrompt {
+                                    content: reflection_prompt,
+                                });
 
-            if matches!(args.verbosity(), Verbosity::VeryVerbose) {
-                eprintln!("\n[DEBUG] Extracted JSON: {}", json_str);
-            }
+                                // Transition: Executing -> Verifying
+                                orchestrator.transition(StateEvent::ToolComplete)?;
 
-            match serde_json::from_str::<ollamabuddy::types::AgentMsg>(json_str) {
-                Ok(agent_msg) => {
-                    use ollamabuddy::types::AgentMsg;
-                    
-                    match agent_msg {
-                        AgentMsg::ToolCall { tool, args } => {
-                            println!("Tool call: {} with args: {:?}", tool, args);
-                            
-                            let tool_start = std::time::Instant::now();
-                            telemetry.record(TelemetryEvent::ToolStarted {
-                                tool: tool.clone(),
-                                timestamp: tool_start,
-                            });
-                            
-                            // Transition to executing
-                            orchestrator.transition(StateEvent::ToolCall)?;
-                            
-                            // Show progress bar for tool execution
-                            let pb = ProgressBar::new_spinner();
-                            pb.set_style(
-                                ProgressStyle::default_spinner()
-                                    .template("{spinner:.green} {msg}")
-                                    .unwrap()
-                            );
-                            pb.set_message(format!("Executing: {}", tool));
-                            pb.enable_steady_tick(std::time::Duration::from_millis(100));
-                            
-                            // Execute tool
-                            let result = tool_runtime.execute(&tool, &serde_json::to_value(&args)?).await;
-                            pb.finish_and_clear();
-                            
-                            match result {
-                                Ok(tool_output) => {
-                                    let duration = tool_start.elapsed().as_millis() as u64;
-                                    telemetry.record(TelemetryEvent::ToolCompleted {
-                                        tool: tool.clone(),
-                                        duration_ms: duration,
-                                        success: true,
-                                        timestamp: std::time::Instant::now(),
-                                    });
-                                    println!("[OK] Tool result ({}ms): {}", duration, tool_output.output);
-                                    
-                                    // PRD 9: Collect tool result for validation
-                                    tool_results_log.push(tool_output.clone());
-                                    
-                                    // Add tool result to memory
-                                    use ollamabuddy::types::MemoryEntry;
-                                    orchestrator.memory_mut().add(MemoryEntry::ToolCall {
-                                        tool: tool.clone(),
-                                        args: args.clone(),
-                                        timestamp: std::time::SystemTime::now()
-                                            .duration_since(std::time::UNIX_EPOCH)
-                                            .unwrap()
-                                            .as_secs(),
-                                    });
-                                    
-                                    orchestrator.memory_mut().add(MemoryEntry::ToolResult {
-                                        tool: tool.clone(),
-                                        output: tool_output.output.clone(),
-                                        success: true,
-                                        duration_ms: tool_output.duration_ms,
-                                        timestamp: std::time::SystemTime::now()
-                                            .duration_since(std::time::UNIX_EPOCH)
-                                            .unwrap()
-                                            .as_secs(),
-                                    });
-
-                                    // Add reflection prompt after tool execution
-                                    let reflection_prompt = format!(
-                                        "\n\nREFLECTION: You just executed '{}'. Result: {}\n\n\
-                                        Original task: {}\n\n\
-                                        Has the task been FULLY completed?\n\
-                                        - If YES: Output {{\"type\": \"final\", \"result\": \"description of what you accomplished\"}}\n\
-                                        - If NO: Either call another tool OR explain what still needs to be done.",
-                                        tool,
-                                        &tool_output.output[..tool_output.output.len().min(200)],
-                                        task
-                                    );
-
-                                    orchestrator.memory_mut().add(MemoryEntry::SystemPrompt {
-                                        content: reflection_prompt,
-                                    });
-
-                                    // Transition: Executing -> Verifying
-                                    orchestrator.transition(StateEvent::ToolComplete)?;
-
-                                    // Immediately transition: Verifying -> Planning for next iteration
-                                    orchestrator.transition(StateEvent::ContinueIteration)?;
-                                }
-                                Err(e) => {
-                                    // PRD 9 Phase 3: Adaptive recovery on tool failure
-                                    eprintln!("[ERROR] Tool execution failed: {}", e);
+                                // Immediately transition: Verifying -> Planning for next iteration
+                                orchestrator.transition(StateEvent::ContinueIteration)?;
+                            }
+                            Err(e) => {
+                                // PRD 9 Phase 3: Adaptive recovery on tool failure
+                                eprintln!("[ERROR] Tool execution failed: {}", e);
+                                
+                                // Detect failure pattern
+                                use ollamabuddy::recovery::types::FailureSymptom;
+                                let symptom = FailureSymptom::ToolExecutionFailure {
+                                    tool_name: tool.clone(),
+                                    consecutive_failures: tool_results_log.iter()
+                                        .rev()
+                                        .take_while(|r| !r.success && r.tool == tool)
+                                        .count() + 1,
+                                };
+                                
+                                if let Some(pattern) = adaptive_recovery.detect_pattern(symptom) {
+                                    let action = adaptive_recovery.select_recovery_action(&pattern);
+                                    trouble = true;
                                     
-                                    // Detect failure pattern
-                                    use ollamabuddy::recovery::types::FailureSymptom;
-                                    let symptom = FailureSymptom::ToolExecutionFailure {
-                                        tool_name: tool.clone(),
-                                        consecutive_failures: tool_results_log.iter()
-                                            .rev()
-                                            .take_while(|r| !r.success && r.tool == tool)
-                                            .count() + 1,
-                                    };
+                                    if verbose {
+                                        eprintln!("[RECOVERY] Detected pattern: {:?}", pattern.symptom);
+                                        eprintln!("[RECOVERY] Action: {:?}", action);
+                                    }
                                     
-                                    if let Some(pattern) = adaptive_recovery.detect_pattern(symptom) {
-                                        let action = adaptive_recovery.select_recovery_action(&pattern);
-                                        
-                                        if verbose {
-                                            eprintln!("[RECOVERY] Detected pattern: {:?}", pattern.symptom);
-                                            eprintln!("[RECOVERY] Action: {:?}", action);
+                                    // Apply recovery action (basic implementation)
+                                    match action {
+                                        ollamabuddy::recovery::types::RecoveryAction::Abort { reason } => {
+                                            eprintln!("[RECOVERY] Aborting: {}", reason);
+                                            orchestrator.transition(StateEvent::UnrecoverableError)?;
                                         }
-                                        
-                                        // Apply recovery action (basic implementation)
-                                        match action {
-                                            ollamabuddy::recovery::types::RecoveryAction::Abort { reason } => {
-                                                eprintln!("[RECOVERY] Aborting: {}", reason);
-                                                orchestrator.transition(StateEvent::UnrecoverableError)?;
-                                            }
-                                            _ => {
-                                                // For other actions, transition to ToolFailure and continue
-                                                orchestrator.transition(StateEvent::ToolFailure)?;
-                                            }
+                                        _ => {
+                                            // For other actions, transition to ToolFailure and continue
+                                            orchestrator.transition(StateEvent::ToolFailure)?;
                                         }
-                                    } else {
-                                        orchestrator.transition(StateEvent::ToolFailure)?;
                                     }
+                                } else {
+                                    orchestrator.transition(StateEvent::ToolFailure)?;
                                 }
                             }
                         }
-                        AgentMsg::Final { result, summary } => {
-                            // PRD 9: Run validation on task completion
-                            if !tool_results_log.is_empty() {
-                                let expected_outputs = vec![task.to_string()];
-                                let validation_result = validation_orchestrator.orchestrate_validation(
-                                    &tool_results_log,
-                                    &expected_outputs,
-                                );
-                                
-                                if validation_result.success {
-                                    if verbose {
-                                        eprintln!("[VALIDATION] Task validated successfully (score: {:.2})",
-                                            validation_result.validation.score.overall);
-                                    }
-                                }
-                                // Don't show validation warnings - internal metric
-                            }
+                    }
+                    AgentMsg::ToolCalls { calls } => {
+                        let mut files_touched = Vec::new();
+                        ollamabuddy::execution::execute_tool_calls(
+                            &mut orchestrator,
+                            &tool_runtime,
+                            &telemetry,
+                            calls,
+                            task,
+                            &ollamabuddy::DisplayMode::cli(),
+                            &trace,
+                            &mut tool_results_log,
+                            &mut files_touched,
+                        )
+                        .await?;
+                    }
+                    AgentMsg::Final { result, summary } => {
+                        // PRD 9: Run validation on task completion
+                        if !tool_results_log.is_empty() {
+                            let expected_outputs = vec![task.to_string()];
+                            let validation_result = validation_orchestrator.orchestrate_validation(
+                                &tool_results_log,
+                                &expected_outputs,
+                            );
                             
-                            println!("\n[SUCCESS] Task Complete!");
-                            println!("{}", result);
-                            if let Some(sum) = summary {
-                                println!("Summary: {}", sum);
+                            if validation_result.success {
+                                if verbose {
+                                    eprintln!("[VALIDATION] Task validated successfully (score: {:.2})",
+                                        validation_result.validation.score.overall);
+                                }
                             }
-                            orchestrator.transition(StateEvent::GoalAchieved)?;
-                            break;
+                            // Don't show validation warnings - internal metric
                         }
-                        AgentMsg::Plan { steps, reasoning } => {
-                            println!("Plan created:");
-                            for (i, step) in steps.iter().enumerate() {
-                                println!("   {}. {}", i + 1, step);
-                            }
-                            if let Some(reason) = reasoning {
-                                println!("Reasoning: {}", reason);
-                            }
-                            orchestrator.transition(StateEvent::PlanComplete)?;
+                        
+                        println!("\n[SUCCESS] Task Complete!");
+                        println!("{}", result);
+                        if let Some(sum) = summary {
+                            println!("Summary: {}", sum);
                         }
-                        AgentMsg::Ask { question } => {
-                            println!("Model asks: {}", question);
-                            // For now, just continue
+                        orchestrator.transition(StateEvent::GoalAchieved)?;
+                        break;
+                    }
+                    AgentMsg::Plan { steps, reasoning } => {
+                        println!("Plan created:");
+                        for (i, step) in steps.iter().enumerate() {
+                            println!("   {}. {}", i + 1, step);
                         }
-                        AgentMsg::Error { message, recoverable } => {
-                            eprintln!("[ERROR] Model error: {}", message);
-                            if recoverable {
-                                println!("[WARNING] Error is recoverable, continuing...");
-                            } else {
-                                orchestrator.transition(StateEvent::UnrecoverableError)?;
-                            }
+                        if let Some(reason) = reasoning {
+                            println!("Reasoning: {}", reason);
                         }
+                        orchestrator.transition(StateEvent::PlanComplete)?;
                     }
-                }
-                Err(e) => {
-                    if matches!(args.verbosity(), Verbosity::Verbose | Verbosity::VeryVerbose) {
-                        eprintln!("\n[WARNING] Parse failed: {}", e);
-                        eprintln!("   Text: {}", unescaped);
+                    AgentMsg::Ask { question } => {
+                        ollamabuddy::execution::handle_ask(
+                            &mut orchestrator,
+                            question,
+                            &ollamabuddy::DisplayMode::cli(),
+                        )
+                        .await?;
+                    }
+                    AgentMsg::Error { message, recoverable } => {
+                        eprintln!("[ERROR] Model error: {}", message);
+                        if recoverable {
+                            println!("[WARNING] Error is recoverable, continuing...");
+                        } else {
+                            orchestrator.transition(StateEvent::UnrecoverableError)?;
+                        }
                     }
                 }
             }
+            Err(e) => {
+                consecutive_parse_failures += 1;
+                json_failures += 1;
+                let action = ollamabuddy::execution::handle_malformed_output(
+                    &mut orchestrator,
+                    &mut adaptive_recovery,
+                    consecutive_parse_failures,
+                    &answer,
+                    &e,
+                    &ollamabuddy::DisplayMode::cli(),
+                    matches!(args.verbosity(), Verbosity::Verbose | Verbosity::VeryVerbose),
+                )
+                .await?;
+                trouble |= matches!(action, ollamabuddy::recovery::RecoveryAction::SuggestModelUpgrade { .. });
+            }
+        }
+
+        for result in &tool_results_log[logged_results..] {
+            resources.record_tool_time(std::time::Duration::from_millis(result.duration_ms));
+        }
+
+        if let Err(e) = orchestrator.save_checkpoint(iteration) {
+            eprintln!("[WARNING] Failed to save checkpoint: {}", e);
+        }
+
+        if winding_down {
+            println!("\n[WARNING] Stopping: run budget exhausted");
+            break;
         }
 
         // PRD 9 Phase 2: Track progress and check convergence
@@ -1075,10 +1399,42 @@ Now begin!"#, tools_formatted);
                 // Estimate progress based on successful tool executions
                 (tool_results_log.iter().filter(|r| r.success).count() as f64 * 0.15).min(0.9)
             },
-            _ => iteration as f64 / max_iterations as f64 * 0.5,
+            _ => iteration as f64 / budget_manager.get_allocated() as f64 * 0.5,
         };
         
         convergence_detector.record_progress(current_progress, iteration);
+        trouble |= ollamabuddy::execution::adjust_budget(
+            &mut orchestrator,
+            &mut budget_manager,
+            &mut convergence_detector,
+            &ollamabuddy::DisplayMode::cli(),
+        )
+        .await;
+
+        // Let a stronger model take over instead of giving up
+        if trouble {
+            let metrics = ollamabuddy::execution::task_metrics(
+                &orchestrator,
+                &budget_manager,
+                &convergence_detector,
+                &tool_results_log,
+                json_failures,
+            );
+            if let Some(escalation) = ollamabuddy::execution::escalate_model(
+                &mut orchestrator,
+                &metrics,
+                iteration,
+                &ollamabuddy::DisplayMode::cli(),
+                &trace,
+            )
+            .await
+            {
+                escalations.push(escalation);
+                convergence_detector.reset();
+                consecutive_parse_failures = 0;
+                continue;
+            }
+        }
         
         if verbose {
             if let Some(velocity) = convergence_detector.get_velocity() {
@@ -1098,7 +1454,7 @@ Now begin!"#, tools_formatted);
             current_progress,
             validation_score,
             iteration,
-            max_iterations,
+            budget_manager.get_allocated(),
         );
         
         if termination.should_terminate() {
@@ -1118,11 +1474,31 @@ Now begin!"#, tools_formatted);
         }
     }
     
-    if iteration >= max_iterations {
+    if let Err(e) = orchestrator.save_checkpoint(iteration) {
+        eprintln!("[WARNING] Failed to save checkpoint: {}", e);
+    }
+    
+    if iteration >= budget_manager.get_allocated() {
         println!("\n[WARNING] Maximum iterations reached");
     }
 
+    let finished = matches!(orchestrator.state(), ollamabuddy::agent::AgentState::Final);
+    trace.record(TraceEvent::RunFinished {
+        success: finished,
+        iterations: iteration,
+        output: String::new(),
+    });
+    
     println!("\nAgent finished");
+    for escalation in &escalations {
+        println!(
+            "Escalated {} -> {} at iteration {}: {}",
+            escalation.from_model, escalation.to_model, escalation.iteration, escalation.reason
+        );
+    }
+    if let Some(run_id) = orchestrator.resumable_run_id() {
+        println!("Resume with: ollamabuddy resume {}", run_id);
+    }
     
     // Display telemetry summary
     println!();
@@ -1148,6 +1524,132 @@ Now begin!"#, tools_formatted);
         Ok(())
 }
 
+/// Trace mode for a live run: record when --trace is given
+fn trace_mode(args: &Args) -> Result<TraceMode> {
+    Ok(match &args.trace {
+        Some(path) => TraceMode::Record(TraceRecorder::create(path)?),
+        None => TraceMode::Off,
+    })
+}
+
+/// Handle 'replay' command: re-drive the agent from a recorded trace
+///
+/// Model outputs always come from the trace; tools are mocked from the
+/// recorded results unless `live_tools` is set.
+async fn run_replay(args: &Args, config: &Config, path: &std::path::Path, live_tools: bool) -> Result<()> {
+    use ollamabuddy::agent::StateEvent;
+    
+    let replayer = TraceReplayer::load(path, !live_tools)?;
+    let Some(TraceEvent::RunStarted { task, model, max_iterations, working_dir, memory, delegate, .. }) =
+        replayer.run_started().cloned()
+    else {
+        return Err(anyhow::anyhow!("Trace {:?} has no run_started event", path));
+    };
+    // The goal tree and re-planning of a delegated run come from live planner calls
+    if delegate {
+        return Err(anyhow::anyhow!(
+            "Trace {:?} is from a --delegate run, which cannot be replayed",
+            path
+        ));
+    }
+    
+    let verbose = matches!(args.verbosity(), Verbosity::Verbose | Verbosity::VeryVerbose);
+    let agent_config = AgentConfig {
+        ollama_url: args.ollama_url(),
+        model: model.clone(),
+        max_iterations,
+        verbose,
+        ask_policy: args.ask_policy(),
+        limits: run_limits(args, config),
+        auto_upgrade: auto_upgrade(args, config),
+        // Summaries would be live model calls, so replay keeps rule-based compression
+        routes: ModelRoutes::default(),
+        keep_alive: config.ollama.keep_alive.clone(),
+    };
+    let mut orchestrator = AgentOrchestrator::new(agent_config)?;
+    orchestrator.set_prompt_template(prompt_template(&model));
+    orchestrator.memory_mut().replace_all(memory)?;
+    orchestrator.set_goal(task.clone());
+    orchestrator.transition(StateEvent::StartSession)?;
+    
+    let working_dir = if working_dir.is_dir() {
+        working_dir
+    } else {
+        std::env::current_dir()?
+    };
+    let tool_runtime = tool_runtime(args, config, &working_dir, &working_dir)?;
+    
+    println!("Replaying trace: {:?}", path);
+    println!("Task: {}", task);
+    println!("Model (recorded): {}", model);
+    println!("Tools: {}", if live_tools { "live" } else { "mocked from trace" });
+    println!();
+    
+    let telemetry = TelemetryCollector::new();
+    let result = ollamabuddy::execution::execute_agent_task(
+        &mut orchestrator,
+        &tool_runtime,
+        &telemetry,
+        max_iterations,
+        &task,
+        verbose,
+        &ollamabuddy::DisplayMode::cli(),
+        &TraceMode::Replay(replayer),
+    ).await?;
+    
+    println!();
+    println!(
+        "Replay finished: {} after {} iterations",
+        if result.success { "success" } else { "incomplete" },
+        result.iterations
+    );
+    println!("Output: {}", result.output);
+    Ok(())
+}
+
+/// Load a run checkpoint by id, or the most recent unfinished run
+fn load_resumable_run(config: &Config, run_id: Option<&str>) -> Result<Checkpoint> {
+    let store = checkpoint_store(config);
+    let checkpoint = match run_id {
+        Some(id) => store.load(id)?,
+        None => store
+            .latest_resumable()?
+            .ok_or_else(|| anyhow::anyhow!("No unfinished runs to resume"))?,
+    };
+
+    if checkpoint.is_finished() {
+        return Err(anyhow::anyhow!(
+            "Run {} already finished ({:?})",
+            checkpoint.run_id,
+            checkpoint.state
+        ));
+    }
+    Ok(checkpoint)
+}
+
+/// Handle 'resume --list' command
+fn list_runs(config: &Config) -> Result<()> {
+    let runs = checkpoint_store(config).list()?;
+    if runs.is_empty() {
+        println!("No saved runs.");
+        return Ok(());
+    }
+
+    println!("Saved runs (most recent first):");
+    for run in runs {
+        let status = if run.is_finished() { format!("{:?}", run.state) } else { "resumable".to_string() };
+        println!(
+            "  {}  [{}] iteration {}/{}  {}",
+            run.run_id,
+            status,
+            run.iteration,
+            run.budget.allocated_budget,
+            run.task
+        );
+    }
+    Ok(())
+}
+
 async fn run_doctor(args: &Args) -> Result<()> {
     let doctor = Doctor::new(
         args.host.clone(),
@@ -1163,10 +1665,9 @@ async fn run_doctor(args: &Args) -> Result<()> {
 
 
 /// Handle 'models list' command
-async fn handle_models_list(_args: &Args) -> Result<()> {
+async fn handle_models_list(_args: &Args, config: &Config) -> Result<()> {
     use colored::Colorize;
     use ollamabuddy::models::ModelOperation;
-    use ollamabuddy::config::Config;
     
     let manager = ModelManager::new(None);
     
@@ -1177,9 +1678,7 @@ async fn handle_models_list(_args: &Args) -> Result<()> {
         return Ok(());
     }
     
-    // Load config to get default model
-    let config = Config::load().ok();
-    let default_model = config.as_ref().and_then(|c| c.get_default_model());
+    let default_model = config.models.default.as_deref();
     
     match manager.list_models().await {
         ModelOperation::List(models) => {
@@ -1431,13 +1930,10 @@ async fn handle_models_use(_args: &Args, name: &str) -> Result<()> {
     Ok(())
 }
 
-async fn handle_models_current(_args: &Args) -> Result<()> {
+async fn handle_models_current(_args: &Args, config: &Config) -> Result<()> {
     use colored::Colorize;
-    use ollamabuddy::config::Config;
-    
-    let config = Config::load()?;
     
-    match config.get_default_model() {
+    match config.models.default.as_deref() {
         Some(model) => {
             println!("{} {}", "Current default model:".blue().bold(), model.green());
         }
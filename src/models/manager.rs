@@ -28,6 +28,18 @@ impl ModelManager {
         }
     }
 
+    /// Create a model manager for an Ollama server other than the default
+    ///
+    /// # Arguments
+    /// * `initial_model` - Initial model name (optional, defaults to qwen2.5:7b-instruct)
+    /// * `base_url` - Ollama base URL, e.g. http://127.0.0.1:11434
+    pub fn with_base_url(initial_model: Option<String>, base_url: String) -> Self {
+        Self {
+            client: OllamaModelClient::new(Some(base_url)),
+            ..Self::new(initial_model)
+        }
+    }
+
     /// Get the current active model
     pub async fn current_model(&self) -> String {
         self.current_model.read().await.clone()
@@ -3,12 +3,13 @@
 //! Provides top-down decomposition of complex goals into executable sub-goals
 //! using LLM-based reasoning for intelligent planning.
 
-use crate::planning::types::{GoalTree, GoalNode, NodeType, NodeId};
+use crate::planning::types::{GoalTree, GoalStatus, NodeType, NodeId};
 use crate::planning::complexity::ComplexityEstimator;
-use crate::streaming::OllamaClient;
+use crate::agent::ModelRole;
+use crate::streaming::{GenerateOptions, OllamaClient};
+use crate::telemetry::{TelemetryCollector, TelemetryEvent};
 use crate::errors::Result;
-use futures_util::StreamExt;
-use serde_json::json;
+use serde::Deserialize;
 
 /// Hierarchical task planner with LLM-based reasoning
 pub struct HierarchicalPlanner {
@@ -26,6 +27,9 @@ pub struct HierarchicalPlanner {
 
     /// Ollama client for LLM-based planning
     client: Option<OllamaClient>,
+
+    /// Collector for planner model calls
+    telemetry: Option<TelemetryCollector>,
 }
 
 impl HierarchicalPlanner {
@@ -37,6 +41,7 @@ impl HierarchicalPlanner {
             max_fanout: 7,
             atomic_threshold: 0.2,
             client: None,
+            telemetry: None,
         }
     }
 
@@ -44,6 +49,11 @@ impl HierarchicalPlanner {
     pub fn set_client(&mut self, client: OllamaClient) {
         self.client = Some(client);
     }
+
+    /// Record planner model calls to `telemetry`
+    pub fn set_telemetry(&mut self, telemetry: TelemetryCollector) {
+        self.telemetry = Some(telemetry);
+    }
     
     /// Decompose a goal into hierarchical sub-goals
     ///
@@ -99,9 +109,10 @@ impl HierarchicalPlanner {
             return Ok(());
         }
 
-        // Add sub-goals as children
+        // Add sub-goals as children, wiring sibling dependencies by index
+        let mut child_ids: Vec<NodeId> = Vec::new();
         for sub_goal in sub_goals.iter().take(self.max_fanout) {
-            let complexity = self.estimator.estimate(sub_goal, context);
+            let complexity = self.estimator.estimate(&sub_goal.description, context);
 
             let node_type = if complexity < self.atomic_threshold {
                 NodeType::Atomic
@@ -111,11 +122,19 @@ impl HierarchicalPlanner {
 
             let child_id = tree.add_child(
                 parent_id,
-                sub_goal.clone(),
+                sub_goal.description.clone(),
                 node_type,
                 complexity,
             ).map_err(|e| crate::errors::AgentError::Generic(e))?;
 
+            for dep in &sub_goal.depends_on {
+                if let Some(&dep_id) = child_ids.get(*dep) {
+                    tree.add_dependency(child_id, dep_id)
+                        .map_err(crate::errors::AgentError::Generic)?;
+                }
+            }
+            child_ids.push(child_id);
+
             // Recursively decompose composite children
             if node_type == NodeType::Composite {
                 Box::pin(self.decompose_recursive(tree, child_id, depth + 1, context)).await?;
@@ -124,12 +143,42 @@ impl HierarchicalPlanner {
 
         Ok(())
     }
+
+    /// Re-plan a failed node in place
+    ///
+    /// The node is reset to `Pending` and decomposed again with `context`
+    /// (typically the failure) in the prompt. If the LLM considers it atomic
+    /// the node stays a leaf and is simply retried.
+    pub async fn replan_subtree(
+        &self,
+        tree: &mut GoalTree,
+        node_id: NodeId,
+        context: &[String],
+    ) -> Result<()> {
+        let depth = tree.nodes.get(&node_id)
+            .map(|n| n.depth)
+            .ok_or_else(|| crate::errors::AgentError::Generic(
+                format!("Node {} not found", node_id)
+            ))?;
+
+        // Only leaves are re-planned; an already decomposed node keeps its children
+        if !tree.edges.contains_key(&node_id) {
+            self.decompose_recursive(tree, node_id, depth, context).await?;
+        }
+
+        // Reset only after re-planning worked, so a failed attempt stays Failed
+        tree.update_status(node_id, GoalStatus::Pending)
+            .map_err(crate::errors::AgentError::Generic)?;
+        tree.roll_up_status();
+
+        Ok(())
+    }
     
     /// Generate sub-goals for a given goal using LLM-based reasoning
     ///
     /// This method uses the LLM to actually think about task decomposition,
     /// considering potential failure points, edge cases, and proper sequencing.
-    async fn generate_subgoals(&self, goal: &str, context: &[String]) -> Result<Vec<String>> {
+    async fn generate_subgoals(&self, goal: &str, context: &[String]) -> Result<Vec<SubGoal>> {
         // If no client available, return empty (will mark as atomic)
         let client = match &self.client {
             Some(c) => c,
@@ -167,61 +216,93 @@ PLANNING GUIDELINES:
    - Check security vulnerabilities
 7. Keep steps atomic and testable
 8. List steps in proper execution order
+9. Mark which earlier steps each step needs; steps that do not need each other can run in parallel
 
 OUTPUT FORMAT:
-Return ONLY a JSON array of step descriptions. Each step should be a clear, actionable task.
-Example: ["Step 1 description", "Step 2 description", "Step 3 description"]
+Return ONLY a JSON array of step objects. Each step has a clear, actionable "step" description
+and "depends_on", the 1-based numbers of earlier steps it needs.
+Example: [{{"step": "Step 1 description", "depends_on": []}}, {{"step": "Step 2 description", "depends_on": []}}, {{"step": "Step 3 description", "depends_on": [1, 2]}}]
 
 If the task is already atomic (cannot be meaningfully broken down), return an empty array: []
 
 STEPS:"#, goal, context_str);
 
         // Call LLM for planning
-        let request = json!({
-            "model": "qwen2.5:14b-instruct",
-            "prompt": planning_prompt,
-            "stream": false,
-            "options": {
-                "temperature": 0.7,
-                "num_predict": 500
-            }
-        });
-
-        let mut stream = client.generate_stream(serde_json::to_string(&request)
-            .map_err(|e| crate::errors::AgentError::Generic(format!("JSON error: {}", e)))?
-        ).await
+        let options = GenerateOptions {
+            temperature: Some(0.7),
+            num_predict: Some(500),
+            ..GenerateOptions::default()
+        };
+        let generation = client.generate_with_options(planning_prompt, &options).await
             .map_err(|e| crate::errors::AgentError::Generic(format!("LLM error: {}", e)))?;
 
-        // Collect full response
-        let mut response_text = String::new();
-        while let Some(chunk_result) = stream.next().await {
-            let chunk_bytes = chunk_result
-                .map_err(|e| crate::errors::AgentError::Generic(format!("Stream error: {}", e)))?;
-
-            if let Ok(ollama_response) = serde_json::from_slice::<serde_json::Value>(&chunk_bytes) {
-                if let Some(token) = ollama_response.get("response").and_then(|r| r.as_str()) {
-                    response_text.push_str(token);
-                }
+        if let Some(telemetry) = &self.telemetry {
+            if let Some(stats) = &generation.stats {
+                telemetry.record_generation(stats);
             }
+            telemetry.record(TelemetryEvent::ModelCall {
+                role: ModelRole::Planner,
+                model: client.model().to_string(),
+                tokens: generation.tokens,
+                duration_ms: generation.duration.as_millis() as u64,
+                timestamp: std::time::Instant::now(),
+            });
         }
+        let response_text = generation.text;
 
-        // Parse JSON response
-        let response_text = response_text.trim();
+        // Reasoning models may sketch candidate lists inside <think> blocks
+        Ok(parse_subgoals(&crate::streaming::split_thinking(&response_text).answer))
+    }
+}
 
-        // Try to extract JSON array from response
-        let steps: Vec<String> = if let Some(start) = response_text.find('[') {
-            if let Some(end) = response_text.rfind(']') {
-                let json_str = &response_text[start..=end];
-                serde_json::from_str(json_str).unwrap_or_else(|_| Vec::new())
-            } else {
-                Vec::new()
-            }
-        } else {
-            Vec::new()
-        };
+/// Sub-goal proposed by the LLM
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+struct SubGoal {
+    /// Step description
+    #[serde(rename = "step")]
+    description: String,
+
+    /// 0-based indices of earlier sibling steps this one needs
+    #[serde(default)]
+    depends_on: Vec<usize>,
+}
 
-        Ok(steps)
+/// Parse the planner's JSON array of steps
+///
+/// Accepts step objects with 1-based `depends_on` numbers; references to
+/// the step itself or later steps are dropped so the result stays acyclic.
+/// A plain array of strings is treated as a sequential plan.
+fn parse_subgoals(response: &str) -> Vec<SubGoal> {
+    let response = response.trim();
+    let json_str = match (response.find('['), response.rfind(']')) {
+        (Some(start), Some(end)) if start < end => &response[start..=end],
+        _ => return Vec::new(),
+    };
+
+    if let Ok(steps) = serde_json::from_str::<Vec<String>>(json_str) {
+        return steps
+            .into_iter()
+            .enumerate()
+            .map(|(i, description)| SubGoal {
+                description,
+                depends_on: if i == 0 { Vec::new() } else { vec![i - 1] },
+            })
+            .collect();
     }
+
+    let steps: Vec<SubGoal> = serde_json::from_str(json_str).unwrap_or_default();
+    steps
+        .into_iter()
+        .enumerate()
+        .map(|(i, mut step)| {
+            step.depends_on = step.depends_on
+                .iter()
+                .filter(|n| **n >= 1 && **n <= i)
+                .map(|n| n - 1)
+                .collect();
+            step
+        })
+        .collect()
 }
 
 impl Default for HierarchicalPlanner {
@@ -233,6 +314,58 @@ impl Default for HierarchicalPlanner {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    /// Planner whose client talks to a stub Ollama server
+    ///
+    /// The server answers each planning prompt with `respond(goal)`, where
+    /// `goal` is the prompt's TASK line.
+    async fn stub_planner(respond: fn(&str) -> String) -> HierarchicalPlanner {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        tokio::spawn(async move {
+            while let Ok((mut socket, _)) = listener.accept().await {
+                tokio::spawn(async move {
+                    let mut request = Vec::new();
+                    let mut buf = [0u8; 4096];
+                    let body_start = loop {
+                        let n = socket.read(&mut buf).await.unwrap_or(0);
+                        if n == 0 {
+                            return;
+                        }
+                        request.extend_from_slice(&buf[..n]);
+                        let Some(end) = request.windows(4).position(|w| w == b"\r\n\r\n") else {
+                            continue;
+                        };
+                        let headers = String::from_utf8_lossy(&request[..end]).to_lowercase();
+                        let length = headers
+                            .lines()
+                            .find_map(|line| line.strip_prefix("content-length:"))
+                            .and_then(|value| value.trim().parse::<usize>().ok())
+                            .unwrap_or(0);
+                        if request.len() >= end + 4 + length {
+                            break end + 4;
+                        }
+                    };
+
+                    let body: serde_json::Value = serde_json::from_slice(&request[body_start..]).unwrap();
+                    let prompt = body["prompt"].as_str().unwrap_or_default();
+                    let goal = prompt.lines().find_map(|l| l.strip_prefix("TASK: ")).unwrap_or_default();
+                    let reply = serde_json::json!({ "response": respond(goal), "done": true }).to_string();
+                    let response = format!(
+                        "HTTP/1.1 200 OK\r\ncontent-type: application/x-ndjson\r\ncontent-length: {}\r\nconnection: close\r\n\r\n{}",
+                        reply.len(),
+                        reply
+                    );
+                    socket.write_all(response.as_bytes()).await.ok();
+                });
+            }
+        });
+
+        let mut planner = HierarchicalPlanner::new();
+        planner.set_client(OllamaClient::with_config(&url, "stub").unwrap());
+        planner
+    }
     
     #[test]
     fn test_planner_creation() {
@@ -242,77 +375,156 @@ mod tests {
         assert!((planner.atomic_threshold - 0.2).abs() < 0.001);
     }
     
-    #[test]
-    fn test_simple_goal_no_decomposition() {
+    #[tokio::test]
+    async fn test_goal_without_client_is_atomic() {
         let planner = HierarchicalPlanner::new();
         let goal = "Read file.txt";
-        let tree = planner.decompose(goal, &[]).unwrap();
+        let tree = planner.decompose(goal, &[]).await.unwrap();
         
-        // Should have only root node
+        // Without an LLM the goal cannot be decomposed: only the root remains
         assert_eq!(tree.nodes.len(), 1);
         assert_eq!(tree.nodes[&tree.root].node_type, NodeType::Atomic);
     }
     
-    #[test]
-    fn test_complex_goal_decomposition() {
-        let planner = HierarchicalPlanner::new();
-        let goal = "Find all Python files and count lines of code";
-        let tree = planner.decompose(goal, &[]).unwrap();
-        
-        // Should have root + children
-        assert!(tree.nodes.len() > 1, "Expected decomposition to create children");
+    #[tokio::test]
+    async fn test_complex_goal_decomposition() {
+        let planner = stub_planner(|goal| match goal {
+            "Find all Python files and count lines of code" => {
+                r#"[{"step": "Find all Python files", "depends_on": []},
+                    {"step": "Count lines of code in each file", "depends_on": [1]}]"#.to_string()
+            }
+            _ => "[]".to_string(),
+        })
+        .await;
+        let tree = planner.decompose("Find all Python files and count lines of code", &[]).await.unwrap();
         
-        // Root should be composite
+        // Root + children
+        assert_eq!(tree.nodes.len(), 3);
         assert_eq!(tree.nodes[&tree.root].node_type, NodeType::Composite);
+        let children = &tree.edges[&tree.root];
+        assert_eq!(tree.nodes[&children[0]].description, "Find all Python files");
+        assert_eq!(tree.nodes[&children[1]].after, vec![children[0]]);
     }
     
-    #[test]
-    fn test_sequential_decomposition() {
-        let planner = HierarchicalPlanner::new();
-        let sub_goals = planner.decompose_sequential("Read file and process data and save results");
+    #[tokio::test]
+    async fn test_sequential_decomposition() {
+        let planner = stub_planner(|goal| match goal {
+            "Read file and process data and save results" => {
+                r#"["Read file", "Process data", "Save results"]"#.to_string()
+            }
+            _ => "[]".to_string(),
+        })
+        .await;
+        let tree = planner.decompose("Read file and process data and save results", &[]).await.unwrap();
         
-        assert_eq!(sub_goals.len(), 3);
-        assert!(sub_goals[0].contains("Read file"));
-        assert!(sub_goals[1].contains("process data"));
-        assert!(sub_goals[2].contains("save results"));
+        let leaves: Vec<&str> = tree.leaves_in_order().iter().map(|n| n.description.as_str()).collect();
+        assert_eq!(leaves, vec!["Read file", "Process data", "Save results"]);
+        // Plain steps run in order
+        let children = &tree.edges[&tree.root];
+        assert!(tree.nodes[&children[0]].after.is_empty());
+        assert_eq!(tree.nodes[&children[1]].after, vec![children[0]]);
+        assert_eq!(tree.nodes[&children[2]].after, vec![children[1]]);
     }
     
-    #[test]
-    fn test_depth_limit_respected() {
-        let planner = HierarchicalPlanner::new();
-        let goal = "Very complex task that should decompose deeply";
-        let tree = planner.decompose(goal, &[]).unwrap();
+    #[tokio::test]
+    async fn test_depth_limit_respected() {
+        // Every goal splits into two steps complex enough to decompose again
+        let planner = stub_planner(|_| {
+            r#"["Analyze all log files, compile and test the project, then deploy it to multiple servers",
+                "Refactor the authentication module, migrate the database schema and verify every integration test"]"#
+                .to_string()
+        })
+        .await;
+        let tree = planner.decompose("Very complex task that should decompose deeply", &[]).await.unwrap();
         
-        // Check no node exceeds max depth
-        for node in tree.nodes.values() {
-            assert!(node.depth <= planner.max_depth);
-        }
+        let deepest = tree.nodes.values().map(|n| n.depth).max().unwrap();
+        assert_eq!(deepest, planner.max_depth);
     }
     
-    #[test]
-    fn test_fanout_limit_respected() {
-        let planner = HierarchicalPlanner::new();
-        let goal = "Do A and B and C and D and E and F and G and H and I";
-        let tree = planner.decompose(goal, &[]).unwrap();
-        
-        // Check no node exceeds max fanout
-        for (node_id, _) in &tree.nodes {
-            if let Some(children) = tree.edges.get(node_id) {
-                assert!(children.len() <= planner.max_fanout);
+    #[tokio::test]
+    async fn test_fanout_limit_respected() {
+        let planner = stub_planner(|goal| {
+            if goal.starts_with("Do A") {
+                r#"["A", "B", "C", "D", "E", "F", "G", "H", "I"]"#.to_string()
+            } else {
+                "[]".to_string()
             }
+        })
+        .await;
+        let tree = planner.decompose("Do A and B and C and D and E and F and G and H and I", &[]).await.unwrap();
+        
+        assert_eq!(tree.edges[&tree.root].len(), planner.max_fanout);
+        for children in tree.edges.values() {
+            assert!(children.len() <= planner.max_fanout);
         }
     }
     
-    #[test]
-    fn test_leaf_nodes_are_atomic() {
-        let planner = HierarchicalPlanner::new();
-        let goal = "Find files and count lines";
-        let tree = planner.decompose(goal, &[]).unwrap();
+    #[tokio::test]
+    async fn test_leaf_nodes_are_atomic() {
+        // Complex-looking steps the LLM then declines to split further
+        let planner = stub_planner(|goal| match goal {
+            "Find files and count lines" => {
+                r#"["Analyze all log files, compile and test the project, then deploy it to multiple servers",
+                    "Count lines"]"#.to_string()
+            }
+            _ => "[]".to_string(),
+        })
+        .await;
+        let tree = planner.decompose("Find files and count lines", &[]).await.unwrap();
         
-        // All leaf nodes should be atomic
         let leaves = tree.get_leaf_nodes();
+        assert_eq!(leaves.len(), 2);
         for leaf in leaves {
             assert_eq!(leaf.node_type, NodeType::Atomic);
         }
     }
+    
+    #[test]
+    fn test_parse_steps_with_dependencies() {
+        let response = r#"Here is the plan:
+[{"step": "Find Python files", "depends_on": []},
+ {"step": "Find Rust files"},
+ {"step": "Count lines", "depends_on": [1, 2]},
+ {"step": "Bogus refs", "depends_on": [0, 4, 5]}]"#;
+        let steps = parse_subgoals(response);
+        
+        assert_eq!(steps.len(), 4);
+        assert_eq!(steps[0].description, "Find Python files");
+        assert!(steps[1].depends_on.is_empty());
+        assert_eq!(steps[2].depends_on, vec![0, 1]);
+        // Self, forward and out-of-range references are dropped
+        assert!(steps[3].depends_on.is_empty());
+    }
+    
+    #[test]
+    fn test_parse_plain_steps_as_sequence() {
+        let steps = parse_subgoals(r#"["Read file", "Process data", "Save results"]"#);
+        
+        assert_eq!(steps.len(), 3);
+        assert!(steps[0].depends_on.is_empty());
+        assert_eq!(steps[1].depends_on, vec![0]);
+        assert_eq!(steps[2].depends_on, vec![1]);
+    }
+    
+    #[test]
+    fn test_parse_rejects_non_json() {
+        assert!(parse_subgoals("no plan here").is_empty());
+        assert!(parse_subgoals("[not json]").is_empty());
+        assert!(parse_subgoals("[]").is_empty());
+    }
+    
+    #[tokio::test]
+    async fn test_replan_subtree_resets_failed_leaf() {
+        let planner = HierarchicalPlanner::new();
+        let mut tree = GoalTree::new("Root".to_string(), 0.5);
+        let a = tree.add_child(0, "A".to_string(), NodeType::Atomic, 0.2).unwrap();
+        tree.update_status(a, GoalStatus::Failed).unwrap();
+        tree.roll_up_status();
+        
+        planner.replan_subtree(&mut tree, a, &["A failed".to_string()]).await.unwrap();
+        
+        assert_eq!(tree.nodes[&a].status, GoalStatus::Pending);
+        assert_eq!(tree.nodes[&0].status, GoalStatus::Pending);
+        assert_eq!(tree.ready_leaves().len(), 1);
+    }
 }
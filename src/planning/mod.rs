@@ -9,6 +9,7 @@ pub mod complexity;
 pub mod strategies;
 pub mod replanner;
 pub mod progress;
+pub mod review;
 
 // Re-export commonly used types
 pub use types::{
@@ -18,6 +19,7 @@ pub use types::{
 };
 
 use crate::streaming::OllamaClient;
+use crate::telemetry::TelemetryCollector;
 
 /// Advanced planning system integration
 pub struct AdvancedPlanner {
@@ -26,6 +28,7 @@ pub struct AdvancedPlanner {
     pub strategies: strategies::StrategyGenerator,
     pub replanner: replanner::AdaptiveReplanner,
     pub progress: Option<progress::ProgressTracker>,
+    pub goal_tree: Option<GoalTree>,
 }
 
 impl AdvancedPlanner {
@@ -37,6 +40,7 @@ impl AdvancedPlanner {
             strategies: strategies::StrategyGenerator::new(),
             replanner: replanner::AdaptiveReplanner::new(),
             progress: None,
+            goal_tree: None,
         }
     }
 
@@ -45,6 +49,11 @@ impl AdvancedPlanner {
         self.hierarchical.set_client(client);
     }
 
+    /// Record planner model calls to `telemetry`
+    pub fn set_telemetry(&mut self, telemetry: TelemetryCollector) {
+        self.hierarchical.set_telemetry(telemetry);
+    }
+
     /// Initialize planning for a new goal (async for LLM-based planning)
     pub async fn initialize(&mut self, goal: &str, context: &[String]) -> crate::errors::Result<()> {
         // Decompose goal into tree using LLM-based reasoning
@@ -52,9 +61,73 @@ impl AdvancedPlanner {
 
         // Initialize progress tracker
         self.progress = Some(progress::ProgressTracker::new(&goal_tree));
+        self.goal_tree = Some(goal_tree);
 
         Ok(())
     }
+
+    /// Restore a previously decomposed goal tree (e.g. from a checkpoint)
+    ///
+    /// Skips LLM decomposition; progress tracking restarts from the tree.
+    pub fn restore(&mut self, goal_tree: GoalTree) {
+        self.progress = Some(progress::ProgressTracker::new(&goal_tree));
+        self.goal_tree = Some(goal_tree);
+    }
+
+    /// Get the current goal tree
+    pub fn goal_tree(&self) -> Option<&GoalTree> {
+        self.goal_tree.as_ref()
+    }
+
+    /// Get mutable reference to the current goal tree
+    pub fn goal_tree_mut(&mut self) -> Option<&mut GoalTree> {
+        self.goal_tree.as_mut()
+    }
+
+    /// Record a sub-goal outcome and roll it up the tree
+    pub fn record_goal_status(&mut self, node_id: NodeId, status: GoalStatus) {
+        if let Some(tree) = self.goal_tree.as_mut() {
+            let _ = tree.update_status(node_id, status);
+            tree.roll_up_status();
+        }
+        if status == GoalStatus::Completed {
+            if let Some(progress) = self.progress.as_mut() {
+                progress.update_goal_completion(node_id);
+            }
+        }
+    }
+    
+    /// Highest-utility strategy for the current goal tree
+    pub fn select_strategy(&self) -> Option<Strategy> {
+        let tree = self.goal_tree.as_ref()?;
+        let strategies = self.strategies.generate_strategies(tree, &[]).ok()?;
+        self.strategies.select_strategy(&strategies)
+    }
+
+    /// Handle a failed sub-goal via the adaptive re-planner
+    ///
+    /// On `ModifyApproach` the failed node's subtree is re-planned with the
+    /// failure as context and its nodes become pending again; on `Terminate`
+    /// the tree is left as is.
+    pub async fn replan_failed_goal(
+        &mut self,
+        node_id: NodeId,
+        error: &str,
+    ) -> crate::errors::Result<ReplanningAction> {
+        let Some(tree) = self.goal_tree.as_mut() else {
+            return Ok(ReplanningAction::Terminate {
+                reason: "No goal tree to re-plan".to_string(),
+            });
+        };
+
+        let action = self.replanner.replan_failed_goal(tree, node_id, error);
+        if let ReplanningAction::ModifyApproach { suggestion, .. } = &action {
+            self.hierarchical
+                .replan_subtree(tree, node_id, std::slice::from_ref(suggestion))
+                .await?;
+        }
+        Ok(action)
+    }
     
     /// Get current progress
     pub fn get_progress(&self) -> Option<&progress::ProgressTracker> {
@@ -65,6 +138,7 @@ impl AdvancedPlanner {
     pub fn reset(&mut self) {
         self.replanner.reset();
         self.progress = None;
+        self.goal_tree = None;
     }
 }
 
@@ -3,7 +3,7 @@
 //! Detects when current strategy is failing and switches to alternatives
 //! with statistical thresholds for re-planning triggers.
 
-use crate::planning::types::{FailurePattern, ReplanningAction, Strategy, GoalTree};
+use crate::planning::types::{FailurePattern, ReplanningAction, Strategy, GoalTree, NodeId};
 use std::collections::HashMap;
 
 /// Adaptive re-planner with failure detection
@@ -23,6 +23,9 @@ pub struct AdaptiveReplanner {
     /// Last progress value for comparison
     last_progress: f64,
     
+    /// Failed sub-goal runs: node_id -> count
+    goal_failures: HashMap<NodeId, usize>,
+    
     /// Threshold for repeated calls (3+)
     repeated_call_threshold: usize,
     
@@ -34,6 +37,9 @@ pub struct AdaptiveReplanner {
     
     /// Threshold for stagnant iterations (4+)
     stagnant_threshold: usize,
+    
+    /// Re-plans allowed per sub-goal lineage (1)
+    max_goal_replans: usize,
 }
 
 impl AdaptiveReplanner {
@@ -45,10 +51,12 @@ impl AdaptiveReplanner {
             error_history: HashMap::new(),
             stagnant_iterations: 0,
             last_progress: 0.0,
+            goal_failures: HashMap::new(),
             repeated_call_threshold: 3,
             empty_result_threshold: 2,
             error_streak_threshold: 3,
             stagnant_threshold: 4,
+            max_goal_replans: 1,
         }
     }
     
@@ -182,6 +190,47 @@ impl AdaptiveReplanner {
         self.error_history.clear();
         self.stagnant_iterations = 0;
         self.last_progress = 0.0;
+        self.goal_failures.clear();
+    }
+    
+    /// Decide how to handle a failed sub-goal
+    ///
+    /// Failures are counted along the node's ancestry, so a sub-goal that was
+    /// itself produced by re-planning shares its parent's allowance. Within
+    /// the allowance the subtree is re-planned (`ModifyApproach`), beyond it
+    /// the run terminates.
+    pub fn replan_failed_goal(
+        &mut self,
+        goal_tree: &GoalTree,
+        node_id: NodeId,
+        error: &str,
+    ) -> ReplanningAction {
+        *self.goal_failures.entry(node_id).or_insert(0) += 1;
+        
+        let mut attempts = 0;
+        let mut current = Some(node_id);
+        while let Some(id) = current {
+            attempts += self.goal_failures.get(&id).copied().unwrap_or(0);
+            current = goal_tree.parent_of(id);
+        }
+        
+        let description = goal_tree.nodes.get(&node_id)
+            .map(|n| n.description.clone())
+            .unwrap_or_default();
+        
+        if attempts <= self.max_goal_replans {
+            ReplanningAction::ModifyApproach {
+                suggestion: format!(
+                    "Previous attempt at '{}' failed: {}. Plan a different approach.",
+                    description, error
+                ),
+                modified_plan: vec![description],
+            }
+        } else {
+            ReplanningAction::Terminate {
+                reason: format!("Sub-goal '{}' failed {} times: {}", description, attempts, error),
+            }
+        }
     }
     
     /// Check for repeated identical calls
@@ -351,7 +400,7 @@ impl Default for AdaptiveReplanner {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::planning::types::{GoalTree, StrategyType};
+    use crate::planning::types::{GoalTree, NodeType, StrategyType};
     use serde_json::json;
     
     #[test]
@@ -512,4 +561,22 @@ mod tests {
         // Should not detect pattern after reset
         assert!(replanner.should_replan("read_file", &args, "error", 0.5).is_none());
     }
+    
+    #[test]
+    fn test_failed_goal_replanned_once_per_lineage() {
+        let mut replanner = AdaptiveReplanner::new();
+        let mut tree = GoalTree::new("Root".to_string(), 0.5);
+        let a = tree.add_child(0, "Build project".to_string(), NodeType::Atomic, 0.2).unwrap();
+        
+        let action = replanner.replan_failed_goal(&tree, a, "compile error");
+        assert!(matches!(action, ReplanningAction::ModifyApproach { .. }));
+        
+        // A child produced by re-planning shares the parent's allowance
+        let a1 = tree.add_child(a, "Fix compile error".to_string(), NodeType::Atomic, 0.2).unwrap();
+        let action = replanner.replan_failed_goal(&tree, a1, "still broken");
+        match action {
+            ReplanningAction::Terminate { reason } => assert!(reason.contains("Fix compile error")),
+            _ => panic!("Expected Terminate action"),
+        }
+    }
 }
@@ -0,0 +1,168 @@
+//! Plan review before execution
+//!
+//! Renders the planner's steps as an editable text file, opens it in the
+//! user's `$VISUAL`/`$EDITOR` and parses the result back into steps. Used by
+//! `--plan-only` and the REPL `/plan` command so nonsense plans can be caught
+//! (and fixed) before the agent touches any files.
+
+use crate::errors::{AgentError, Result};
+use crate::planning::complexity::ComplexityEstimator;
+use crate::planning::types::{GoalTree, NodeType, Strategy};
+use std::process::Command;
+
+/// Editor used when neither `$VISUAL` nor `$EDITOR` is set
+const DEFAULT_EDITOR: &str = "vi";
+
+/// Executable steps of a goal tree, in execution order
+///
+/// A goal that did not decompose yields the root goal as its only step.
+pub fn plan_steps(tree: &GoalTree) -> Vec<String> {
+    tree.leaves_in_order()
+        .into_iter()
+        .map(|node| node.description.clone())
+        .collect()
+}
+
+/// Render steps as an editable plan file
+///
+/// Lines starting with `#` are instructions and strategy notes; every other
+/// non-empty line is one step.
+pub fn render_plan(task: &str, steps: &[String], strategy: Option<&Strategy>) -> String {
+    let mut text = format!("# Plan for: {}\n", task);
+
+    if let Some(strategy) = strategy {
+        text.push_str(&format!(
+            "# Strategy: {} (confidence {:.2})\n",
+            strategy.name, strategy.confidence
+        ));
+        for step in &strategy.steps {
+            match &step.expected_tool {
+                Some(tool) => text.push_str(&format!("#   - {} [{}]\n", step.description, tool)),
+                None => text.push_str(&format!("#   - {}\n", step.description)),
+            }
+        }
+    }
+
+    text.push_str(
+        "#\n\
+        # One step per line, in execution order. Edit, reorder or delete lines;\n\
+        # lines starting with '#' are ignored. Deleting every step keeps the\n\
+        # previous plan.\n",
+    );
+
+    for (i, step) in steps.iter().enumerate() {
+        text.push_str(&format!("{}. {}\n", i + 1, step));
+    }
+    text
+}
+
+/// Parse an edited plan file back into steps
+///
+/// Skips comments and blank lines and strips list markers (`1.`, `2)`, `-`,
+/// `*`) so users can renumber freely.
+pub fn parse_plan(text: &str) -> Vec<String> {
+    text.lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(strip_list_marker)
+        .filter(|step| !step.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+fn strip_list_marker(line: &str) -> &str {
+    if let Some(rest) = line.strip_prefix("- ").or_else(|| line.strip_prefix("* ")) {
+        return rest.trim();
+    }
+
+    let digits = line.len() - line.trim_start_matches(|c: char| c.is_ascii_digit()).len();
+    if digits > 0 {
+        if let Some(rest) = line[digits..].strip_prefix(['.', ')']) {
+            return rest.trim();
+        }
+    }
+    line
+}
+
+/// Goal tree for an approved plan: one atomic leaf per step, run in order
+///
+/// The fanout limit is raised to fit the plan, since the user may list more
+/// steps than the planner would have generated.
+pub fn plan_tree(task: &str, root_complexity: f64, steps: &[String]) -> Result<GoalTree> {
+    let estimator = ComplexityEstimator::new();
+    let mut tree = GoalTree::new(task.to_string(), root_complexity);
+    tree.max_fanout = tree.max_fanout.max(steps.len());
+
+    let mut previous = None;
+    for step in steps {
+        let id = tree
+            .add_child(tree.root, step.clone(), NodeType::Atomic, estimator.estimate(step, &[]))
+            .map_err(AgentError::Generic)?;
+        if let Some(previous) = previous {
+            tree.add_dependency(id, previous).map_err(AgentError::Generic)?;
+        }
+        previous = Some(id);
+    }
+    Ok(tree)
+}
+
+/// Open `text` in the user's editor and return the saved contents
+///
+/// Blocks until the editor exits. `$VISUAL` wins over `$EDITOR`; either may
+/// carry arguments (e.g. `code --wait`).
+pub fn edit_in_editor(text: &str) -> Result<String> {
+    let editor = std::env::var("VISUAL")
+        .or_else(|_| std::env::var("EDITOR"))
+        .ok()
+        .filter(|e| !e.trim().is_empty())
+        .unwrap_or_else(|| DEFAULT_EDITOR.to_string());
+
+    let path = std::env::temp_dir().join(format!("ollamabuddy-plan-{}.md", std::process::id()));
+    std::fs::write(&path, text)?;
+
+    let mut parts = editor.split_whitespace();
+    let program = parts.next().unwrap_or(DEFAULT_EDITOR);
+    let status = Command::new(program).args(parts).arg(&path).status();
+
+    let result = match status {
+        Ok(status) if status.success() => Ok(std::fs::read_to_string(&path)?),
+        Ok(status) => Err(AgentError::Generic(format!("Editor '{}' exited with {}", editor, status))),
+        Err(e) => Err(AgentError::Generic(format!("Failed to launch editor '{}': {}", editor, e))),
+    };
+    let _ = std::fs::remove_file(&path);
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_and_parse_roundtrip() {
+        let steps = vec!["Find Python files".to_string(), "Count lines".to_string()];
+        let text = render_plan("Count Python lines", &steps, None);
+
+        assert!(text.starts_with("# Plan for: Count Python lines"));
+        assert_eq!(parse_plan(&text), steps);
+    }
+
+    #[test]
+    fn test_parse_edited_plan() {
+        let edited = "# comment\n\n2) Count lines\n - Find files\n* Report\n10. Done\nPlain step\n3.\n";
+        assert_eq!(
+            parse_plan(edited),
+            vec!["Count lines", "Find files", "Report", "Done", "Plain step"]
+        );
+    }
+
+    #[test]
+    fn test_plan_tree_runs_steps_in_order() {
+        let steps: Vec<String> = (1..=9).map(|i| format!("Step {}", i)).collect();
+        let tree = plan_tree("Task", 0.5, &steps).unwrap();
+
+        assert_eq!(plan_steps(&tree), steps);
+        let ready = tree.ready_leaves();
+        assert_eq!(ready.len(), 1);
+        assert_eq!(ready[0].description, "Step 1");
+    }
+}
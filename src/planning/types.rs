@@ -47,6 +47,7 @@ impl GoalTree {
             status: GoalStatus::Pending,
             confidence: 1.0,
             dependencies: Vec::new(),
+            after: Vec::new(),
             complexity: root_complexity,
             depth: 0,
         };
@@ -101,6 +102,7 @@ impl GoalTree {
             status: GoalStatus::Pending,
             confidence: 0.8, // Default confidence
             dependencies: vec![parent_id],
+            after: Vec::new(),
             complexity,
             depth: child_depth,
         };
@@ -118,6 +120,128 @@ impl GoalTree {
             .collect()
     }
     
+    /// Leaf nodes in depth-first, insertion order (execution order)
+    pub fn leaves_in_order(&self) -> Vec<&GoalNode> {
+        let mut leaves = Vec::new();
+        let mut stack = vec![self.root];
+        while let Some(id) = stack.pop() {
+            match self.edges.get(&id) {
+                Some(children) => stack.extend(children.iter().rev()),
+                None => leaves.extend(self.nodes.get(&id)),
+            }
+        }
+        leaves
+    }
+    
+    /// Make `node_id` wait for its sibling `on` to complete
+    ///
+    /// Only siblings may depend on each other, and a dependency that would
+    /// close a cycle is rejected, so the tree stays a DAG.
+    pub fn add_dependency(&mut self, node_id: NodeId, on: NodeId) -> Result<(), String> {
+        if node_id == on {
+            return Err(format!("Node {} cannot depend on itself", node_id));
+        }
+        for id in [node_id, on] {
+            if !self.nodes.contains_key(&id) {
+                return Err(format!("Node {} not found", id));
+            }
+        }
+        if self.parent_of(node_id) != self.parent_of(on) {
+            return Err(format!("Nodes {} and {} are not siblings", node_id, on));
+        }
+        if self.depends_on(on, node_id) {
+            return Err(format!("Dependency {} -> {} would create a cycle", node_id, on));
+        }
+        
+        let node = self.nodes.get_mut(&node_id).expect("checked above");
+        if !node.after.contains(&on) {
+            node.after.push(on);
+        }
+        Ok(())
+    }
+    
+    /// Whether `node_id` (transitively) waits for sibling `on`
+    fn depends_on(&self, node_id: NodeId, on: NodeId) -> bool {
+        let mut stack = vec![node_id];
+        let mut seen = Vec::new();
+        while let Some(id) = stack.pop() {
+            if id == on {
+                return true;
+            }
+            if seen.contains(&id) {
+                continue;
+            }
+            seen.push(id);
+            if let Some(node) = self.nodes.get(&id) {
+                stack.extend(&node.after);
+            }
+        }
+        false
+    }
+    
+    /// Pending leaves whose dependencies are all completed
+    ///
+    /// A leaf also waits on the dependencies of each of its ancestors, so a
+    /// sub-goal under a composite node only starts once the siblings that
+    /// composite depends on are done. Returned in execution order.
+    pub fn ready_leaves(&self) -> Vec<&GoalNode> {
+        self.leaves_in_order()
+            .into_iter()
+            .filter(|leaf| leaf.status == GoalStatus::Pending)
+            .filter(|leaf| {
+                let mut current = Some(leaf.id);
+                while let Some(id) = current {
+                    let blocked = self.nodes[&id].after.iter().any(|dep| {
+                        self.nodes.get(dep).is_some_and(|n| n.status != GoalStatus::Completed)
+                    });
+                    if blocked {
+                        return false;
+                    }
+                    current = self.parent_of(id);
+                }
+                true
+            })
+            .collect()
+    }
+    
+    /// Parent of a node (None for the root)
+    pub fn parent_of(&self, node_id: NodeId) -> Option<NodeId> {
+        self.edges
+            .iter()
+            .find(|(_, children)| children.contains(&node_id))
+            .map(|(parent, _)| *parent)
+    }
+    
+    /// Roll leaf statuses up to composite nodes
+    ///
+    /// A composite node is `Completed` when all children are, `Failed` when
+    /// any child failed, `InProgress` when any child started, else `Pending`.
+    pub fn roll_up_status(&mut self) {
+        self.roll_up_from(self.root);
+    }
+    
+    fn roll_up_from(&mut self, node_id: NodeId) -> GoalStatus {
+        let Some(children) = self.edges.get(&node_id).cloned() else {
+            return self.nodes.get(&node_id).map_or(GoalStatus::Pending, |n| n.status);
+        };
+        
+        let statuses: Vec<GoalStatus> = children.iter().map(|c| self.roll_up_from(*c)).collect();
+        let status = if statuses.iter().all(|s| *s == GoalStatus::Completed) {
+            GoalStatus::Completed
+        } else if statuses.contains(&GoalStatus::Failed) {
+            GoalStatus::Failed
+        } else if statuses.iter().any(|s| *s != GoalStatus::Pending) {
+            GoalStatus::InProgress
+        } else {
+            GoalStatus::Pending
+        };
+        
+        if let Some(node) = self.nodes.get_mut(&node_id) {
+            node.status = status;
+        }
+        status
+    }
+    
     /// Update node status
     pub fn update_status(&mut self, node_id: NodeId, status: GoalStatus) -> Result<(), String> {
         self.nodes.get_mut(&node_id)
@@ -161,6 +285,10 @@ pub struct GoalNode {
     /// Parent node IDs (dependencies)
     pub dependencies: Vec<NodeId>,
     
+    /// Sibling node IDs that must complete before this node starts
+    #[serde(default)]
+    pub after: Vec<NodeId>,
+    
     /// Complexity score [0.0, 1.0]
     pub complexity: f64,
     
@@ -396,6 +524,51 @@ mod tests {
         assert!(result.unwrap_err().contains("Max fanout"));
     }
     
+    #[test]
+    fn test_leaves_in_order_and_roll_up() {
+        let mut tree = GoalTree::new("Root".to_string(), 0.5);
+        let a = tree.add_child(0, "A".to_string(), NodeType::Composite, 0.5).unwrap();
+        let a1 = tree.add_child(a, "A1".to_string(), NodeType::Atomic, 0.2).unwrap();
+        let a2 = tree.add_child(a, "A2".to_string(), NodeType::Atomic, 0.2).unwrap();
+        let b = tree.add_child(0, "B".to_string(), NodeType::Atomic, 0.2).unwrap();
+        
+        let order: Vec<&str> = tree.leaves_in_order().iter().map(|n| n.description.as_str()).collect();
+        assert_eq!(order, vec!["A1", "A2", "B"]);
+        assert_eq!(tree.parent_of(a2), Some(a));
+        assert_eq!(tree.parent_of(0), None);
+        
+        tree.update_status(a1, GoalStatus::Completed).unwrap();
+        tree.update_status(a2, GoalStatus::Completed).unwrap();
+        tree.roll_up_status();
+        assert_eq!(tree.nodes[&a].status, GoalStatus::Completed);
+        assert_eq!(tree.nodes[&0].status, GoalStatus::InProgress);
+        
+        tree.update_status(b, GoalStatus::Failed).unwrap();
+        tree.roll_up_status();
+        assert_eq!(tree.nodes[&0].status, GoalStatus::Failed);
+    }
+
+    #[test]
+    fn test_dependencies_gate_ready_leaves() {
+        let mut tree = GoalTree::new("Root".to_string(), 0.5);
+        let a = tree.add_child(0, "A".to_string(), NodeType::Atomic, 0.2).unwrap();
+        let b = tree.add_child(0, "B".to_string(), NodeType::Atomic, 0.2).unwrap();
+        let c = tree.add_child(0, "C".to_string(), NodeType::Composite, 0.5).unwrap();
+        let c1 = tree.add_child(c, "C1".to_string(), NodeType::Atomic, 0.2).unwrap();
+
+        tree.add_dependency(c, a).unwrap();
+        assert!(tree.add_dependency(a, c).is_err(), "cycle must be rejected");
+        assert!(tree.add_dependency(c1, a).is_err(), "non-siblings must be rejected");
+        assert!(tree.add_dependency(b, b).is_err());
+
+        let ready: Vec<NodeId> = tree.ready_leaves().iter().map(|n| n.id).collect();
+        assert_eq!(ready, vec![a, b]);
+
+        tree.update_status(a, GoalStatus::Completed).unwrap();
+        let ready: Vec<NodeId> = tree.ready_leaves().iter().map(|n| n.id).collect();
+        assert_eq!(ready, vec![b, c1]);
+    }
+
     #[test]
     fn test_progress_metrics_calculation() {
         let mut metrics = ProgressMetrics::new();
@@ -18,6 +18,12 @@ pub struct RecoveryConfig {
     
     /// Enable aggressive recovery
     pub aggressive_recovery: bool,
+    
+    /// Repair requests for malformed output before aborting
+    pub max_format_repairs: usize,
+    
+    /// Malformed responses in a session before suggesting a stronger model
+    pub format_failures_before_upgrade: usize,
 }
 
 impl Default for RecoveryConfig {
@@ -27,6 +33,8 @@ impl Default for RecoveryConfig {
             max_history_size: 50,
             parallelism_levels: vec![4, 2, 1],
             aggressive_recovery: false,
+            max_format_repairs: 3,
+            format_failures_before_upgrade: 3,
         }
     }
 }
@@ -165,6 +173,26 @@ impl AdaptiveRecovery {
                 }
             }
             
+            FailureSymptom::MalformedOutput { consecutive_failures } => {
+                let total = self.malformed_output_count();
+                if *consecutive_failures > self.config.max_format_repairs {
+                    RecoveryAction::Abort {
+                        reason: format!(
+                            "Model produced {} responses in a row without a valid action",
+                            consecutive_failures
+                        ),
+                    }
+                } else if total == self.config.format_failures_before_upgrade {
+                    RecoveryAction::SuggestModelUpgrade {
+                        reason: format!("{} malformed responses this session", total),
+                    }
+                } else {
+                    RecoveryAction::RepairOutput {
+                        attempt: *consecutive_failures,
+                    }
+                }
+            }
+            
             FailureSymptom::Unknown => {
                 if attempts < self.config.max_strategy_attempts {
                     RecoveryAction::RotateStrategy
@@ -221,6 +249,15 @@ impl AdaptiveRecovery {
             .count()
     }
     
+    /// Total malformed-output failures recorded
+    fn malformed_output_count(&self) -> usize {
+        self.failure_history
+            .values()
+            .filter(|p| matches!(p.symptom, FailureSymptom::MalformedOutput { .. }))
+            .map(|p| p.frequency)
+            .sum()
+    }
+    
     /// Prune old patterns from history
     fn prune_old_patterns(&mut self) {
         // Remove patterns that are not recent
@@ -401,6 +438,26 @@ mod tests {
         assert!(matches!(action, RecoveryAction::ReduceParallelism { .. }));
     }
     
+    #[test]
+    fn test_recovery_action_malformed_output() {
+        let mut recovery = AdaptiveRecovery::new();
+        let mut actions = Vec::new();
+        
+        // Two runs of failures: 1, 2 then 1, 2, 3, 4
+        for consecutive_failures in [1, 2, 1, 2, 3, 4] {
+            let symptom = FailureSymptom::MalformedOutput { consecutive_failures };
+            let pattern = recovery.detect_pattern(symptom).unwrap();
+            actions.push(recovery.select_recovery_action(&pattern));
+        }
+        
+        assert_eq!(actions[0], RecoveryAction::RepairOutput { attempt: 1 });
+        assert_eq!(actions[1], RecoveryAction::RepairOutput { attempt: 2 });
+        assert!(matches!(actions[2], RecoveryAction::SuggestModelUpgrade { .. }));
+        assert_eq!(actions[3], RecoveryAction::RepairOutput { attempt: 2 });
+        assert_eq!(actions[4], RecoveryAction::RepairOutput { attempt: 3 });
+        assert!(matches!(actions[5], RecoveryAction::Abort { .. }));
+    }
+    
     #[test]
     fn test_strategy_attempts_tracking() {
         let mut recovery = AdaptiveRecovery::new();
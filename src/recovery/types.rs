@@ -33,6 +33,11 @@ pub enum FailureSymptom {
         operation: String,
     },
     
+    /// Model response held no parseable action
+    MalformedOutput {
+        consecutive_failures: usize,
+    },
+    
     /// Unknown failure
     Unknown,
 }
@@ -48,6 +53,9 @@ impl FailureSymptom {
                 (*consecutive_failures as u8).min(8)
             }
             FailureSymptom::Timeout { .. } => 5,
+            FailureSymptom::MalformedOutput { consecutive_failures } => {
+                (*consecutive_failures as u8 + 3).min(8)
+            }
             FailureSymptom::Unknown => 3,
         }
     }
@@ -70,6 +78,9 @@ impl FailureSymptom {
             FailureSymptom::Timeout { operation } => {
                 format!("Timeout during: {}", operation)
             }
+            FailureSymptom::MalformedOutput { consecutive_failures } => {
+                format!("{} consecutive responses without a valid action", consecutive_failures)
+            }
             FailureSymptom::Unknown => "Unknown failure".to_string(),
         }
     }
@@ -148,6 +159,16 @@ pub enum RecoveryAction {
     /// Switch to simpler approach
     SimplifyApproach,
     
+    /// Ask the model to fix its malformed output
+    RepairOutput {
+        attempt: usize,
+    },
+    
+    /// Suggest a stronger model (the current one keeps breaking the format)
+    SuggestModelUpgrade {
+        reason: String,
+    },
+    
     /// Abort execution
     Abort {
         reason: String,
@@ -160,11 +181,13 @@ impl RecoveryAction {
         match self {
             RecoveryAction::Abort { .. } => 10,
             RecoveryAction::ReassessComplexity => 8,
+            RecoveryAction::SuggestModelUpgrade { .. } => 8,
             RecoveryAction::RotateStrategy => 7,
             RecoveryAction::ReduceParallelism { .. } => 6,
             RecoveryAction::SimplifyApproach => 5,
             RecoveryAction::RelaxValidation { .. } => 4,
             RecoveryAction::RetryWithBackoff { .. } => 3,
+            RecoveryAction::RepairOutput { .. } => 3,
         }
     }
 }
@@ -25,6 +25,8 @@ pub enum Command {
     Stats,
     Model { subcommand: String, args: Vec<String> },
     Knowledge,
+    Resume { run_id: Option<String> },
+    Plan { task: String },
     Unknown { input: String },
 }
 
@@ -48,6 +50,11 @@ impl CommandHandler {
         self.rag_agent = Some(rag_agent);
         self
     }
+
+    /// RAG agent for memory commands, if initialized
+    pub fn rag_agent(&self) -> Option<&std::sync::Arc<RAGAgent>> {
+        self.rag_agent.as_ref()
+    }
     
     /// Parse input string into a command
     /// 
@@ -90,6 +97,15 @@ impl CommandHandler {
             }
             "stats" => Command::Stats,
             "knowledge" | "kb" => Command::Knowledge,
+            "resume" => Command::Resume {
+                run_id: parts.get(1).map(|s| s.to_string()),
+            },
+            "plan" => Command::Plan {
+                task: trimmed[1..]
+                    .split_once(char::is_whitespace)
+                    .map(|(_, task)| task.trim().to_string())
+                    .unwrap_or_default(),
+            },
             "model" | "models" => {
                 if parts.len() < 2 {
                     Command::Model {
@@ -163,6 +179,17 @@ impl CommandHandler {
             Command::Model { subcommand, args } => {
                 self.handle_model_command(&subcommand, &args)
             }
+            Command::Resume { .. } => {
+                // Runs the agent, so the REPL loop handles it after this returns
+                Ok(true)
+            }
+            Command::Plan { task } => {
+                // Runs the agent, so the REPL loop handles it after this returns
+                if task.is_empty() {
+                    println!("{}", "Usage: /plan <task>".yellow());
+                }
+                Ok(true)
+            }
             Command::Unknown { input } => {
                 println!("{}", format!("Unknown command: {}", input).red());
                 println!("Type {} for available commands", "/help".cyan());
@@ -189,6 +216,8 @@ impl CommandHandler {
             ("/stats", "Show detailed performance statistics"),
             ("/knowledge, /kb", "Show knowledge base status"),
             ("/model, /models", "Model management commands"),
+            ("/resume [run-id]", "Resume an interrupted run"),
+            ("/plan <task>", "Review and edit the plan before running a task"),
             ("/exit, /quit, /q", "Exit REPL"),
         ];
         
@@ -497,6 +526,26 @@ mod tests {
         assert_eq!(handler.parse("/history 5"), Command::History { limit: Some(5) });
     }
 
+    #[test]
+    fn test_parse_plan() {
+        let handler = CommandHandler::new();
+        assert_eq!(
+            handler.parse("/plan  Count lines in src/"),
+            Command::Plan { task: "Count lines in src/".to_string() }
+        );
+        assert_eq!(handler.parse("/plan"), Command::Plan { task: String::new() });
+    }
+
+    #[test]
+    fn test_parse_resume() {
+        let handler = CommandHandler::new();
+        assert_eq!(handler.parse("/resume"), Command::Resume { run_id: None });
+        assert_eq!(
+            handler.parse("/resume 20261018-120000-abcd"),
+            Command::Resume { run_id: Some("20261018-120000-abcd".to_string()) }
+        );
+    }
+
     #[test]
     fn test_parse_status() {
         let handler = CommandHandler::new();
@@ -586,6 +635,7 @@ mod tests {
             duration_ms: 100,
             timestamp: 1234567890,
             files_modified: vec![],
+            escalations: vec![],
         });
         
         assert_eq!(session.task_count(), 1);
@@ -24,6 +24,16 @@ pub struct DisplayManager {
     multi_progress: MultiProgress,
     current_bar: Option<ProgressBar>,
     update_interval: Duration,
+    line_reader: LineReader,
+}
+
+/// Reads one line of user input for a prompt (None on EOF or interrupt)
+pub type LineReader = fn(&str) -> Option<String>;
+
+/// Read a line with a rustyline editor
+fn read_line_with_editor(prompt: &str) -> Option<String> {
+    let mut editor = rustyline::DefaultEditor::new().ok()?;
+    editor.readline(prompt).ok()
 }
 
 impl DisplayManager {
@@ -35,8 +45,20 @@ impl DisplayManager {
             multi_progress: MultiProgress::new(),
             current_bar: None,
             update_interval: Duration::from_millis(100), // 10 FPS
+            line_reader: read_line_with_editor,
         }
     }
+
+    /// Replace how answers to agent questions are read
+    pub fn with_line_reader(mut self, line_reader: LineReader) -> Self {
+        self.line_reader = line_reader;
+        self
+    }
+
+    /// Reader for answers to agent questions (rustyline by default)
+    pub fn line_reader(&self) -> LineReader {
+        self.line_reader
+    }
     
     /// Show welcome banner
     pub fn show_banner(&self, version: &str, model: &str) {
@@ -99,6 +99,11 @@ impl ReplSession {
         self.command_handler = CommandHandler::new().with_rag_agent(rag_agent);
     }
 
+    /// RAG agent for memory and learning, if initialized
+    pub fn rag_agent(&self) -> Option<std::sync::Arc<RAGAgent>> {
+        self.command_handler.rag_agent().cloned()
+    }
+
     pub fn read_input(&mut self) -> Result<Option<String>> {
         self.display_manager.show_prompt()?;
         self.input_handler.read_line()
@@ -124,6 +129,11 @@ impl ReplSession {
         Ok(true)
     }
     
+    /// Parse input as a REPL command without executing it
+    pub fn parse_command(&self, input: &str) -> Command {
+        self.command_handler.parse(input)
+    }
+    
     /// Record a completed task
     pub fn record_task(&mut self, record: TaskRecord) {
         self.session_manager.record_task(record);
@@ -278,6 +288,7 @@ mod tests {
             duration_ms: 100,
             timestamp: 1234567890,
             files_modified: vec![],
+            escalations: vec![],
         };
         
         session.record_task(record);
@@ -302,6 +313,7 @@ mod tests {
             duration_ms: 100,
             timestamp: 1234567890,
             files_modified: vec![],
+            escalations: vec![],
         };
         session.record_task(record);
         
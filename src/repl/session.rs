@@ -3,6 +3,7 @@
 //! Maintains conversation context, task history, and file tracking
 //! Performance target: <20ms context building
 
+use crate::advisor::ModelEscalation;
 use anyhow::Result;
 use serde::{Deserialize, Serialize};
 use std::collections::{HashSet, VecDeque};
@@ -24,6 +25,8 @@ pub struct TaskRecord {
     pub duration_ms: u64,
     pub timestamp: u64,
     pub files_modified: Vec<PathBuf>,
+    #[serde(default)]
+    pub escalations: Vec<ModelEscalation>,
 }
 
 /// Session manager maintaining REPL state
@@ -194,6 +197,7 @@ mod tests {
             duration_ms: 100,
             timestamp: 1234567890,
             files_modified: vec![],
+            escalations: vec![],
         }
     }
 
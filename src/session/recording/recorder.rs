@@ -4,6 +4,8 @@ use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use uuid::Uuid;
 
+use crate::streaming::GenerationStats;
+
 /// Task execution outcome
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum TaskOutcome {
@@ -36,6 +38,9 @@ pub struct TaskRecord {
     pub timestamp: DateTime<Utc>,
     /// Additional metadata
     pub metadata: HashMap<String, String>,
+    /// Model token counts and timings reported by Ollama
+    #[serde(default)]
+    pub generation: GenerationStats,
 }
 
 impl TaskRecord {
@@ -52,6 +57,7 @@ impl TaskRecord {
             error: None,
             timestamp: Utc::now(),
             metadata: HashMap::new(),
+            generation: GenerationStats::default(),
         }
     }
 
@@ -75,6 +81,12 @@ impl TaskRecord {
         self
     }
 
+    /// Set model token counts and timings
+    pub fn with_generation(mut self, generation: GenerationStats) -> Self {
+        self.generation = generation;
+        self
+    }
+
     /// Add file that was touched
     pub fn add_file(&mut self, file: String) {
         if !self.files_touched.contains(&file) {
@@ -4,6 +4,7 @@ use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
 use crate::session::recording::{SessionData, TaskOutcome};
+use crate::streaming::GenerationStats;
 
 /// Cumulative statistics across all sessions
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -28,6 +29,9 @@ pub struct CumulativeStats {
     pub first_session: Option<DateTime<Utc>>,
     /// Last session timestamp
     pub last_session: Option<DateTime<Utc>>,
+    /// Model token counts and timings across all tasks
+    #[serde(default)]
+    pub generation: GenerationStats,
 }
 
 impl Default for CumulativeStats {
@@ -43,6 +47,7 @@ impl Default for CumulativeStats {
             avg_task_duration_secs: 0.0,
             first_session: None,
             last_session: None,
+            generation: GenerationStats::default(),
         }
     }
 }
@@ -108,6 +113,7 @@ impl StatisticsTracker {
         // Update execution time
         for task in &session.tasks {
             self.cumulative.total_execution_time_secs += task.duration_secs;
+            self.cumulative.generation += task.generation;
             
             // Update tool statistics
             for tool in &task.tools_used {
@@ -301,4 +307,33 @@ mod tests {
         assert_eq!(tracker.success_rate_history()[0].value, 0.8);
         assert_eq!(tracker.success_rate_history()[1].value, 0.9);
     }
+
+    #[test]
+    fn test_generation_stats_aggregate() {
+        let mut tracker = StatisticsTracker::new();
+        let turn = GenerationStats {
+            prompt_tokens: 500,
+            eval_tokens: 50,
+            eval_duration: std::time::Duration::from_secs(5),
+            ..GenerationStats::default()
+        };
+
+        let mut session = SessionData::new();
+        session.add_task(TaskRecord::new("a".to_string()).with_generation(turn));
+        session.add_task(TaskRecord::new("b".to_string()).with_generation(turn));
+        tracker.update_with_session(&session);
+
+        let generation = tracker.cumulative().generation;
+        assert_eq!(generation.prompt_tokens, 1000);
+        assert_eq!(generation.eval_tokens, 100);
+        assert!((generation.tokens_per_sec() - 10.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_stats_without_generation_still_load() {
+        let mut stats = serde_json::to_value(CumulativeStats::default()).unwrap();
+        stats.as_object_mut().unwrap().remove("generation");
+        let loaded: CumulativeStats = serde_json::from_value(stats).unwrap();
+        assert_eq!(loaded.generation, GenerationStats::default());
+    }
 }
@@ -1,10 +1,17 @@
 //! Streaming client module
 //! 
-//! Provides Ollama API client and incremental JSON parser.
+//! Provides Ollama API client, incremental JSON parser and reasoning-block
+//! splitting.
 
 pub mod client;
 pub mod parser;
+pub mod repair;
+pub mod stats;
+pub mod thinking;
 
 // Re-export commonly used types
-pub use client::{OllamaClient, DEFAULT_OLLAMA_URL, DEFAULT_MODEL};
-pub use parser::{JsonParser, MAX_BUFFER_SIZE};
+pub use client::{reply_context, GenerateOptions, Generation, OllamaClient, CONTEXT_WINDOW, DEFAULT_KEEP_ALIVE, DEFAULT_OLLAMA_URL, DEFAULT_MODEL};
+pub use parser::{extract_json_objects, parse_action, ExtractedJson, JsonParser, MAX_BUFFER_SIZE};
+pub use repair::{repair_json, repair_prompt, ACTION_FORMATS};
+pub use stats::GenerationStats;
+pub use thinking::{split_thinking, Segment, SplitResponse, ThinkFilter};
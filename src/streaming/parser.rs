@@ -1,20 +1,26 @@
 //! Incremental JSON parser for streaming responses
 //! 
-//! Implements bracket-matching algorithm to extract complete JSON objects
-//! from a stream of bytes with:
+//! Implements a string- and escape-aware tokenizer to extract complete JSON
+//! objects from a stream of bytes with:
 //! - Buffer: 1MB maximum
-//! - Algorithm: O(n) single pass bracket matching
+//! - Algorithm: O(n) single pass, resumable across chunks
+//! - Prose: quotes and stray braces outside objects are ignored
+//! - Fences: objects inside ```json blocks are preferred over prose
+//! - Encoding: fully escaped objects (`{\"type\": ...}`) are decoded
 //! - Recovery: Timeout and error handling
 
 use crate::errors::{AgentError, Result};
+use crate::streaming::repair::repair_json;
 use crate::types::AgentMsg;
 
 /// Maximum buffer size (1MB)
 pub const MAX_BUFFER_SIZE: usize = 1_048_576;
 
+/// Characters of context shown around a parse error
+const ERROR_CONTEXT_CHARS: usize = 40;
+
 /// Parser states
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
-#[allow(dead_code)]
 enum ParserState {
     /// Accumulating bytes, looking for JSON start
     Accumulating,
@@ -26,6 +32,16 @@ enum ParserState {
     Complete,
 }
 
+/// A complete top-level JSON object found in a response
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExtractedJson {
+    /// Object text, decoded if the model emitted it escaped
+    pub text: String,
+
+    /// Whether the object appeared inside a ``` fence
+    pub fenced: bool,
+}
+
 /// Incremental JSON parser
 #[derive(Debug)]
 pub struct JsonParser {
@@ -37,6 +53,54 @@ pub struct JsonParser {
     
     /// Maximum buffer size
     max_buffer_size: usize,
+
+    /// Tokenizer position, so each byte is scanned once
+    scanner: Scanner,
+}
+
+/// Resumable tokenizer state over the buffer
+#[derive(Debug, Clone, Default)]
+struct Scanner {
+    /// Next buffer index to scan
+    pos: usize,
+
+    /// Start of the object being scanned
+    start: Option<usize>,
+
+    /// Brace depth inside the current object
+    depth: usize,
+
+    /// Inside a string literal of the current object
+    in_string: bool,
+
+    /// Length of the current run of backslashes
+    backslashes: usize,
+
+    /// Current object uses `\"` as its quote (double-encoded JSON);
+    /// decided by its first quote
+    escaped: Option<bool>,
+
+    /// Length of the current run of backticks in prose
+    backticks: usize,
+
+    /// Inside a ``` fence
+    in_fence: bool,
+
+    /// Whether the current object started inside a fence
+    fenced: bool,
+}
+
+impl Scanner {
+    /// Whether a quote preceded by `backslashes` backslashes delimits a
+    /// string: unescaped in plain JSON, escaped exactly once (mod 2) in
+    /// double-encoded JSON
+    fn is_delimiter(&self) -> bool {
+        if self.escaped == Some(true) {
+            self.backslashes % 4 == 1
+        } else {
+            self.backslashes.is_multiple_of(2)
+        }
+    }
 }
 
 impl JsonParser {
@@ -51,6 +115,7 @@ impl JsonParser {
             buffer: Vec::with_capacity(4096), // Start with 4KB
             state: ParserState::Accumulating,
             max_buffer_size,
+            scanner: Scanner::default(),
         }
     }
 
@@ -60,19 +125,17 @@ impl JsonParser {
     /// 
     /// ```text
     /// Algorithm extract_complete_json(B):
-    /// 1. depth ← 0, start ← None
-    /// 2. For each byte bᵢ in B:
-    ///      If bᵢ = '{':
-    ///        If depth = 0: start ← i
-    ///        depth ← depth + 1
-    ///      If bᵢ = '}':
-    ///        depth ← depth - 1
-    ///        If depth = 0 and start ≠ None:
-    ///          Return B[start..=i]
+    /// 1. depth ← 0, start ← None, in_string ← false
+    /// 2. For each byte bᵢ in B (resuming where the last call stopped):
+    ///      If start = None: track ``` fences, wait for '{'
+    ///      Else if bᵢ = '"' and unescaped: in_string ← ¬in_string
+    ///      Else if ¬in_string:
+    ///        '{' → depth ← depth + 1
+    ///        '}' → depth ← depth - 1; if depth = 0: Return B[start..=i]
     /// 3. Return None  // No complete JSON yet
     /// 
     /// Complexity: O(n) single pass
-    /// Guarantee: Returns valid JSON substring or None
+    /// Guarantee: Returns a balanced JSON object substring or None
     /// ```
     pub fn add_bytes(&mut self, bytes: &[u8]) -> Result<Option<String>> {
         // Check buffer overflow
@@ -93,88 +156,110 @@ impl JsonParser {
 
     /// Attempt to extract complete JSON from buffer
     fn try_extract_json(&mut self) -> Result<Option<String>> {
-        if self.buffer.is_empty() {
-            return Ok(None);
-        }
-
-        // Find complete JSON object using bracket matching
-        if let Some((start, end)) = self.find_complete_json()? {
-            // Extract JSON substring
-            let json_bytes = &self.buffer[start..=end];
-            let json_str = String::from_utf8_lossy(json_bytes).to_string();
-
-            // Remove processed bytes from buffer
-            self.buffer.drain(..=end);
-
-            // Reset state
-            self.state = ParserState::Accumulating;
-
-            return Ok(Some(json_str));
-        }
+        Ok(self.next_object().map(|object| object.text))
+    }
 
-        Ok(None)
+    /// Scan the buffer for the next complete object
+    fn next_object(&mut self) -> Option<ExtractedJson> {
+        let (start, end) = self.find_complete_json()?;
+
+        let raw = String::from_utf8_lossy(&self.buffer[start..=end]).to_string();
+        let text = if self.scanner.escaped == Some(true) {
+            decode_escaped(&raw).unwrap_or(raw)
+        } else {
+            raw
+        };
+        let fenced = self.scanner.fenced;
+
+        // Remove processed bytes from buffer; fence state carries over
+        self.buffer.drain(..=end);
+        self.scanner = Scanner {
+            in_fence: self.scanner.in_fence,
+            ..Scanner::default()
+        };
+        self.state = ParserState::Complete;
+
+        Some(ExtractedJson { text, fenced })
     }
 
-    /// Find complete JSON object using bracket-matching algorithm
+    /// Advance the tokenizer over unscanned bytes
     /// 
-    /// Returns: Some((start_index, end_index)) or None
-    fn find_complete_json(&self) -> Result<Option<(usize, usize)>> {
-        let mut depth = 0;
-        let mut start: Option<usize> = None;
-        let mut in_string = false;
-        let mut escape_next = false;
-
-        for (i, &byte) in self.buffer.iter().enumerate() {
-            let ch = byte as char;
-
-            // Handle string escaping
-            if escape_next {
-                escape_next = false;
-                continue;
-            }
-
-            if ch == '\\' && in_string {
-                escape_next = true;
-                continue;
-            }
-
-            // Track string boundaries (ignore braces inside strings)
-            if ch == '"' {
-                in_string = !in_string;
+    /// Returns: Some((start_index, end_index)) of a complete object, or None
+    fn find_complete_json(&mut self) -> Option<(usize, usize)> {
+        let sc = &mut self.scanner;
+
+        while sc.pos < self.buffer.len() {
+            let i = sc.pos;
+            let byte = self.buffer[i];
+            sc.pos += 1;
+
+            let Some(start) = sc.start else {
+                // Prose: only fences and object starts matter
+                match byte {
+                    b'`' => {
+                        sc.backticks += 1;
+                        if sc.backticks == 3 {
+                            sc.in_fence = !sc.in_fence;
+                            sc.backticks = 0;
+                        }
+                    }
+                    b'{' => {
+                        sc.backticks = 0;
+                        sc.start = Some(i);
+                        sc.depth = 1;
+                        sc.fenced = sc.in_fence;
+                        self.state = ParserState::Parsing;
+                    }
+                    _ => sc.backticks = 0,
+                }
                 continue;
-            }
+            };
 
-            // Skip if inside string
-            if in_string {
+            if byte == b'\\' {
+                sc.backslashes += 1;
                 continue;
             }
 
-            // Bracket matching
-            match ch {
-                '{' => {
-                    if depth == 0 {
-                        start = Some(i);
-                    }
-                    depth += 1;
+            if byte == b'"' {
+                if sc.escaped.is_none() {
+                    sc.escaped = Some(sc.backslashes % 2 == 1);
                 }
-                '}' => {
-                    depth -= 1;
-                    if depth == 0 && start.is_some() {
-                        // Found complete JSON object
-                        return Ok(Some((start.unwrap(), i)));
-                    }
-                    if depth < 0 {
-                        return Err(AgentError::JsonParseError(
-                            "Mismatched braces: too many closing braces".to_string(),
-                        ));
+                if sc.is_delimiter() {
+                    sc.in_string = !sc.in_string;
+                }
+            } else if !sc.in_string {
+                match byte {
+                    b'{' => sc.depth += 1,
+                    b'}' => {
+                        sc.depth -= 1;
+                        if sc.depth == 0 {
+                            return Some((start, i));
+                        }
                     }
+                    _ => {}
                 }
-                _ => {}
             }
+            sc.backslashes = 0;
         }
 
-        // No complete JSON yet
-        Ok(None)
+        None
+    }
+
+    /// Describe an object left open at the end of the input, if any
+    fn unterminated(&self) -> Option<String> {
+        let start = self.scanner.start?;
+        let text = String::from_utf8_lossy(&self.buffer[start..]);
+        let context = if self.scanner.in_string {
+            "inside a string"
+        } else {
+            "before the closing brace"
+        };
+        Some(format!(
+            "Unterminated JSON object: the response ended {} ({} unclosed brace(s)) in `{}`",
+            context,
+            self.scanner.depth,
+            tail(&text, ERROR_CONTEXT_CHARS)
+        ))
     }
 
     /// Parse extracted JSON string into AgentMsg
@@ -191,6 +276,7 @@ impl JsonParser {
     /// Clear the buffer
     pub fn clear(&mut self) {
         self.buffer.clear();
+        self.scanner = Scanner::default();
         self.state = ParserState::Accumulating;
     }
 
@@ -199,6 +285,11 @@ impl JsonParser {
         self.buffer.is_empty()
     }
 
+    /// Whether an object has started but not yet closed
+    pub fn is_parsing(&self) -> bool {
+        self.state == ParserState::Parsing
+    }
+
     /// Force parse remaining buffer (timeout recovery)
     /// 
     /// Attempts to parse whatever is in the buffer, even if incomplete
@@ -216,8 +307,7 @@ impl JsonParser {
         let buffer_str = String::from_utf8_lossy(&self.buffer).to_string();
         
         // Clear buffer
-        self.buffer.clear();
-        self.state = ParserState::Accumulating;
+        self.clear();
 
         Ok(Some(buffer_str))
     }
@@ -229,6 +319,105 @@ impl Default for JsonParser {
     }
 }
 
+/// Extract every complete top-level JSON object from a response
+///
+/// Objects inside ``` fences come first, then the rest, each group in
+/// response order. Nested objects (e.g. tool args) are part of their parent.
+pub fn extract_json_objects(text: &str) -> Vec<ExtractedJson> {
+    extract_with_remainder(text).0
+}
+
+fn extract_with_remainder(text: &str) -> (Vec<ExtractedJson>, JsonParser) {
+    let mut parser = JsonParser::with_capacity(usize::MAX);
+    parser.buffer.extend_from_slice(text.as_bytes());
+
+    let mut objects = Vec::new();
+    while let Some(object) = parser.next_object() {
+        objects.push(object);
+    }
+    objects.sort_by_key(|object| !object.fenced);
+    (objects, parser)
+}
+
+/// Parse the action in a model response
+///
+/// Takes the first object that is a valid `AgentMsg`, so echoed examples or
+/// stray objects do not hide the real action. If none is valid, lenient
+/// repairs (trailing commas, single quotes, bare keys) are tried. On failure
+/// the error says precisely what is wrong (serde position plus the
+/// surrounding text), so it can be shown to the model for self-correction.
+pub fn parse_action(text: &str) -> Result<AgentMsg> {
+    let (objects, parser) = extract_with_remainder(text);
+
+    let repaired = objects
+        .iter()
+        .filter_map(|object| repair_json(&object.text))
+        .find_map(|fixed| serde_json::from_str::<AgentMsg>(&fixed).ok());
+    let strict = objects
+        .iter()
+        .find_map(|object| serde_json::from_str::<AgentMsg>(&object.text).ok());
+    if let Some(msg) = strict.or(repaired) {
+        return Ok(msg);
+    }
+
+    let mut first_error: Option<(bool, String)> = None;
+    for object in &objects {
+        if let Err(e) = serde_json::from_str::<AgentMsg>(&object.text) {
+            // Prefer errors from objects that look like actions
+            let looks_like_action = object.text.contains("\"type\"");
+            if first_error.as_ref().is_none_or(|(action, _)| !action && looks_like_action) {
+                first_error = Some((looks_like_action, describe_error(&object.text, &e)));
+            }
+        }
+    }
+
+    let message = match (first_error, parser.unterminated()) {
+        (Some((true, error)), _) => error,
+        (_, Some(unterminated)) => unterminated,
+        (Some((false, error)), None) => error,
+        (None, None) => "No JSON object found in the response".to_string(),
+    };
+    Err(AgentError::JsonParseError(message))
+}
+
+/// Serde error plus the text around its position
+fn describe_error(json: &str, error: &serde_json::Error) -> String {
+    if error.line() == 0 {
+        return format!("Invalid action: {}", error);
+    }
+
+    let offset: usize = json
+        .split_inclusive('\n')
+        .take(error.line() - 1)
+        .map(str::len)
+        .sum::<usize>()
+        + error.column().saturating_sub(1);
+    let mut at = offset.min(json.len());
+    while !json.is_char_boundary(at) {
+        at -= 1;
+    }
+
+    format!(
+        "Invalid action: {} near `{}`",
+        error,
+        tail(&json[..at], ERROR_CONTEXT_CHARS)
+    )
+}
+
+/// Last `max` characters of `text`
+fn tail(text: &str, max: usize) -> &str {
+    match text.char_indices().rev().nth(max) {
+        Some((i, c)) => &text[i + c.len_utf8()..],
+        None => text,
+    }
+}
+
+/// Decode an object the model emitted as an escaped JSON string body
+fn decode_escaped(raw: &str) -> Option<String> {
+    let body = raw.replace('\n', "\\n").replace('\r', "\\r").replace('\t', "\\t");
+    serde_json::from_str::<String>(&format!("\"{}\"", body)).ok()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -371,4 +560,85 @@ mod tests {
 
         assert!(matches!(msg, AgentMsg::ToolCall { .. }));
     }
+
+    #[test]
+    fn test_imbalanced_braces_in_string_content() {
+        let response = r#"I'll write it. {"type": "tool_call", "tool": "write_file", "args": {"path": "a.c", "content": "int main() {\n  if (x) {\n"}}"#;
+        match parse_action(response).unwrap() {
+            AgentMsg::ToolCall { args, .. } => {
+                assert_eq!(args["content"], "int main() {\n  if (x) {\n");
+            }
+            other => panic!("unexpected {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_prose_quotes_and_braces_are_ignored() {
+        let response = r#"The user said "list {files}} please. {"type": "final", "result": "done"}"#;
+        let objects = extract_json_objects(response);
+        assert_eq!(objects.last().unwrap().text, r#"{"type": "final", "result": "done"}"#);
+        assert!(matches!(parse_action(response).unwrap(), AgentMsg::Final { .. }));
+    }
+
+    #[test]
+    fn test_fenced_objects_come_first() {
+        let response = "Example: {\"type\": \"final\", \"result\": \"example\"}\n```json\n{\"type\": \"final\", \"result\": \"real\"}\n```";
+        let objects = extract_json_objects(response);
+        assert_eq!(objects.len(), 2);
+        assert!(objects[0].fenced);
+        assert!(objects[0].text.contains("real"));
+    }
+
+    #[test]
+    fn test_first_valid_action_wins() {
+        let response = r#"{"path": "src"} then {"type": "tool_call", "tool": "list_dir", "args": {"path": "src"}} and {"type": "final", "result": "x"}"#;
+        assert!(matches!(parse_action(response).unwrap(), AgentMsg::ToolCall { .. }));
+    }
+
+    #[test]
+    fn test_escaped_object_is_decoded() {
+        let response = r#"{\"type\": \"final\", \"result\": \"say \\\"hi\\\" {\"}"#;
+        match parse_action(response).unwrap() {
+            AgentMsg::Final { result, .. } => assert_eq!(result, r#"say "hi" {"#),
+            other => panic!("unexpected {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_tokenizer_resumes_across_chunks() {
+        let mut parser = JsonParser::new();
+        let chunks = [r#"```js"#, "on\n{\"a\": \"x\\", "\"}", "\" }\n```"];
+
+        let mut found = None;
+        for chunk in chunks {
+            if let Some(json) = parser.add_bytes(chunk.as_bytes()).unwrap() {
+                found = Some(json);
+            }
+        }
+        assert_eq!(found.unwrap(), r#"{"a": "x\"}" }"#);
+    }
+
+    #[test]
+    fn test_lenient_repairs_are_applied() {
+        let msg = parse_action("Done. {type: 'final', result: 'ok',}").unwrap();
+        assert_eq!(msg, AgentMsg::Final { result: "ok".to_string(), summary: None });
+
+        // A valid object wins over one that needs repair
+        let msg = parse_action(r#"{'type': 'final', 'result': 'a'} {"type": "final", "result": "b"}"#).unwrap();
+        assert_eq!(msg, AgentMsg::Final { result: "b".to_string(), summary: None });
+    }
+
+    #[test]
+    fn test_parse_errors_are_precise() {
+        let error = parse_action(r#"{"type": "final", "result": "done" "summary": "x"}"#).unwrap_err().to_string();
+        assert!(error.contains("line 1 column"), "{}", error);
+        assert!(error.contains("\"done\" "), "{}", error);
+
+        let error = parse_action(r#"{"type": "final", "result": "don"#).unwrap_err().to_string();
+        assert!(error.contains("Unterminated JSON object"), "{}", error);
+        assert!(error.contains("inside a string"), "{}", error);
+
+        let error = parse_action("I am not sure what to do").unwrap_err().to_string();
+        assert!(error.contains("No JSON object"), "{}", error);
+    }
 }
@@ -0,0 +1,155 @@
+//! Repair of malformed action JSON
+//!
+//! Small models often emit almost-JSON: trailing commas, single-quoted
+//! strings or unquoted keys. [`repair_json`] fixes those lexically before the
+//! model is asked to try again, and [`repair_prompt`] builds the targeted
+//! message sent when a response cannot be repaired.
+
+/// Maximum characters of the model's bad output echoed back to it
+pub const MAX_ECHOED_OUTPUT: usize = 500;
+
+/// Accepted action formats, shown to the model when it gets them wrong
+pub const ACTION_FORMATS: &str = r#"{"type": "tool_call", "tool": "<tool name>", "args": {"<arg>": <value>}}
+{"type": "tool_calls", "calls": [{"tool": "<tool name>", "args": {...}}, ...]}
+{"type": "ask", "question": "<question for the user>"}
+{"type": "final", "result": "<what was accomplished>"}"#;
+
+/// Apply lenient fixes to almost-JSON
+///
+/// Removes trailing commas, converts single-quoted strings to double-quoted
+/// ones and quotes bare object keys. Double-quoted strings are copied
+/// untouched. Returns `None` when nothing needed fixing.
+pub fn repair_json(text: &str) -> Option<String> {
+    let chars: Vec<char> = text.chars().collect();
+    let mut out = String::with_capacity(text.len() + 16);
+    let mut last_significant = None;
+    let mut i = 0;
+
+    while i < chars.len() {
+        let c = chars[i];
+        match c {
+            '"' => {
+                let end = string_end(&chars, i, '"');
+                out.extend(&chars[i..end]);
+                i = end;
+            }
+            '\'' => {
+                let end = string_end(&chars, i, '\'');
+                let closed = end > i + 1 && chars[end - 1] == '\'';
+                let content_end = if closed { end - 1 } else { end };
+                out.push('"');
+                let mut j = i + 1;
+                while j < content_end {
+                    match chars[j] {
+                        '\\' if chars.get(j + 1) == Some(&'\'') => {
+                            out.push('\'');
+                            j += 1;
+                        }
+                        '\\' => {
+                            out.push('\\');
+                            if let Some(&next) = chars.get(j + 1) {
+                                out.push(next);
+                            }
+                            j += 1;
+                        }
+                        '"' => out.push_str("\\\""),
+                        other => out.push(other),
+                    }
+                    j += 1;
+                }
+                out.push('"');
+                i = end;
+            }
+            ',' => {
+                let next = chars[i + 1..].iter().find(|c| !c.is_whitespace());
+                if !matches!(next, Some('}') | Some(']')) {
+                    out.push(c);
+                }
+                i += 1;
+            }
+            c if c.is_alphabetic() || c == '_' => {
+                let end = i + chars[i..]
+                    .iter()
+                    .take_while(|c| c.is_alphanumeric() || **c == '_' || **c == '-')
+                    .count();
+                let word: String = chars[i..end].iter().collect();
+                let next = chars[end..].iter().find(|c| !c.is_whitespace());
+                if next == Some(&':') && matches!(last_significant, Some('{') | Some(',')) {
+                    out.push('"');
+                    out.push_str(&word);
+                    out.push('"');
+                } else {
+                    out.push_str(&word);
+                }
+                i = end;
+            }
+            _ => {
+                out.push(c);
+                i += 1;
+            }
+        }
+
+        if let Some(last) = out.chars().next_back().filter(|c| !c.is_whitespace()) {
+            last_significant = Some(last);
+        }
+    }
+
+    (out != text).then_some(out)
+}
+
+/// Index just past the string literal starting at `start`
+fn string_end(chars: &[char], start: usize, quote: char) -> usize {
+    let mut j = start + 1;
+    while j < chars.len() {
+        match chars[j] {
+            '\\' => j += 2,
+            c if c == quote => return j + 1,
+            _ => j += 1,
+        }
+    }
+    chars.len()
+}
+
+/// Message asking the model to repair its last response
+pub fn repair_prompt(error: &str, output: &str) -> String {
+    let output = output.trim();
+    let echoed: String = output.chars().take(MAX_ECHOED_OUTPUT).collect();
+    let ellipsis = if echoed.len() < output.len() { " ..." } else { "" };
+
+    format!(
+        "Your last response could not be used as an action: {}\n\
+        Your output was:\n{}{}\n\
+        Respond with exactly one JSON object in one of these formats:\n{}",
+        error, echoed, ellipsis, ACTION_FORMATS
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_repairs_common_mistakes() {
+        let fixed = repair_json(r#"{type: 'final', result: 'it\'s "done"', 'tags': [1, 2,],}"#).unwrap();
+        assert_eq!(fixed, r#"{"type": "final", "result": "it's \"done\"", "tags": [1, 2]}"#);
+        assert!(serde_json::from_str::<serde_json::Value>(&fixed).is_ok());
+
+        // Valid JSON and string contents are left alone
+        assert_eq!(repair_json(r#"{"a": "x, }", "b": true}"#), None);
+        assert_eq!(
+            repair_json(r#"{"msg": "key: 'v',}"}"#),
+            None
+        );
+    }
+
+    #[test]
+    fn test_repair_prompt_echoes_output_and_formats() {
+        let prompt = repair_prompt("trailing comma at line 1 column 9", "{\"a\": 1,}");
+        assert!(prompt.contains("trailing comma"));
+        assert!(prompt.contains("{\"a\": 1,}"));
+        assert!(prompt.ends_with(ACTION_FORMATS));
+
+        let long = "x".repeat(MAX_ECHOED_OUTPUT * 2);
+        assert!(repair_prompt("bad", &long).contains(" ...\n"));
+    }
+}
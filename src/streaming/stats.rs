@@ -0,0 +1,157 @@
+//! Token counts and timings reported by Ollama
+//!
+//! The final chunk of a `/api/generate` stream (`"done": true`) carries
+//! `prompt_eval_count`, `prompt_eval_duration`, `eval_count`,
+//! `eval_duration` and `load_duration`. Durations are in nanoseconds.
+
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::ops::AddAssign;
+use std::time::Duration;
+
+/// Ollama's evaluation stats for one or more generations
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct GenerationStats {
+    /// Prompt tokens evaluated (0 when Ollama reused its prompt cache)
+    pub prompt_tokens: u64,
+
+    /// Time spent processing the prompt
+    pub prompt_eval_duration: Duration,
+
+    /// Tokens generated
+    pub eval_tokens: u64,
+
+    /// Time spent generating
+    pub eval_duration: Duration,
+
+    /// Time spent loading the model
+    pub load_duration: Duration,
+}
+
+impl GenerationStats {
+    /// Parse stats from a response chunk; `None` unless it is the final one
+    pub fn from_chunk(chunk: &serde_json::Value) -> Option<Self> {
+        if !chunk.get("done").and_then(|d| d.as_bool()).unwrap_or(false) {
+            return None;
+        }
+
+        let count = |key: &str| chunk.get(key).and_then(|v| v.as_u64()).unwrap_or(0);
+        let nanos = |key: &str| Duration::from_nanos(count(key));
+        Some(Self {
+            prompt_tokens: count("prompt_eval_count"),
+            prompt_eval_duration: nanos("prompt_eval_duration"),
+            eval_tokens: count("eval_count"),
+            eval_duration: nanos("eval_duration"),
+            load_duration: nanos("load_duration"),
+        })
+    }
+
+    /// Generation throughput in tokens per second
+    pub fn tokens_per_sec(&self) -> f64 {
+        rate(self.eval_tokens, self.eval_duration)
+    }
+
+    /// Prompt-processing throughput in tokens per second
+    pub fn prompt_tokens_per_sec(&self) -> f64 {
+        rate(self.prompt_tokens, self.prompt_eval_duration)
+    }
+
+    /// Share of a `prompt_tokens`-sized prompt that Ollama did not evaluate
+    ///
+    /// `prompt_tokens` is the whole prompt the model saw, including any
+    /// continued context; the rest was served from the KV cache.
+    pub fn cache_hit_ratio(&self, prompt_tokens: usize) -> f64 {
+        if prompt_tokens == 0 {
+            0.0
+        } else {
+            (1.0 - self.prompt_tokens as f64 / prompt_tokens as f64).clamp(0.0, 1.0)
+        }
+    }
+}
+
+fn rate(tokens: u64, duration: Duration) -> f64 {
+    if duration.is_zero() {
+        0.0
+    } else {
+        tokens as f64 / duration.as_secs_f64()
+    }
+}
+
+impl AddAssign for GenerationStats {
+    fn add_assign(&mut self, other: Self) {
+        self.prompt_tokens += other.prompt_tokens;
+        self.prompt_eval_duration += other.prompt_eval_duration;
+        self.eval_tokens += other.eval_tokens;
+        self.eval_duration += other.eval_duration;
+        self.load_duration += other.load_duration;
+    }
+}
+
+impl fmt::Display for GenerationStats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} tokens at {:.1} tok/s, prompt {} tokens in {:.2}s",
+            self.eval_tokens,
+            self.tokens_per_sec(),
+            self.prompt_tokens,
+            self.prompt_eval_duration.as_secs_f64()
+        )?;
+        // Only worth mentioning when the model was (re)loaded for this call
+        if self.load_duration >= Duration::from_millis(100) {
+            write!(f, ", model load {:.2}s", self.load_duration.as_secs_f64())?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_parse_final_chunk() {
+        assert_eq!(GenerationStats::from_chunk(&json!({"response": "a", "done": false})), None);
+
+        let stats = GenerationStats::from_chunk(&json!({
+            "response": "",
+            "done": true,
+            "prompt_eval_count": 1200,
+            "prompt_eval_duration": 800_000_000u64,
+            "eval_count": 40,
+            "eval_duration": 2_000_000_000u64,
+            "load_duration": 5_000_000u64,
+        }))
+        .unwrap();
+        assert_eq!(stats.prompt_tokens, 1200);
+        assert_eq!(stats.eval_tokens, 40);
+        assert!((stats.tokens_per_sec() - 20.0).abs() < 1e-9);
+        assert!((stats.prompt_tokens_per_sec() - 1500.0).abs() < 1e-9);
+        assert_eq!(stats.to_string(), "40 tokens at 20.0 tok/s, prompt 1200 tokens in 0.80s");
+    }
+
+    #[test]
+    fn test_accumulate() {
+        let turn = GenerationStats {
+            eval_tokens: 10,
+            eval_duration: Duration::from_secs(1),
+            load_duration: Duration::from_secs(3),
+            ..GenerationStats::default()
+        };
+        let mut total = GenerationStats::default();
+        total += turn;
+        total += turn;
+        assert_eq!(total.eval_tokens, 20);
+        assert!((total.tokens_per_sec() - 10.0).abs() < 1e-9);
+        assert!(total.to_string().ends_with("model load 6.00s"));
+
+        // Missing stats (e.g. a cached prompt) never divide by zero
+        assert_eq!(GenerationStats::default().prompt_tokens_per_sec(), 0.0);
+
+        let turn = GenerationStats { prompt_tokens: 200, ..GenerationStats::default() };
+        assert!((turn.cache_hit_ratio(1000) - 0.8).abs() < 1e-9);
+        assert_eq!(turn.cache_hit_ratio(100), 0.0);
+        assert_eq!(turn.cache_hit_ratio(0), 0.0);
+    }
+}
@@ -0,0 +1,221 @@
+//! Reasoning-model `<think>` block handling
+//!
+//! Reasoning models (deepseek-r1, qwq) stream long `<think>...</think>`
+//! sections before their answer. Thoughts often quote JSON they are
+//! considering, so they must be separated from the answer before action
+//! extraction, and rendered apart from it (dimmed or collapsed).
+//!
+//! Some chat templates open the think block themselves, so a response may
+//! contain a closing tag without an opening one; everything before it is
+//! treated as thinking. An unclosed block runs to the end of the response.
+
+/// Opening tag of a thinking block
+pub const THINK_OPEN: &str = "<think>";
+
+/// Closing tag of a thinking block
+pub const THINK_CLOSE: &str = "</think>";
+
+/// A response split into reasoning and answer
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SplitResponse {
+    /// Contents of all thinking blocks, joined by blank lines
+    pub thinking: String,
+
+    /// Everything outside thinking blocks; the only part actions are taken from
+    pub answer: String,
+}
+
+/// Split a complete response into thinking and answer text
+pub fn split_thinking(text: &str) -> SplitResponse {
+    let mut filter = ThinkFilter::new();
+    let mut segments = filter.push(text);
+    segments.extend(filter.finish());
+
+    let mut thoughts: Vec<String> = Vec::new();
+    let mut answer = String::new();
+    for segment in segments {
+        match segment {
+            Segment::ThinkingStart => thoughts.push(String::new()),
+            Segment::Thinking(t) => {
+                if let Some(current) = thoughts.last_mut() {
+                    current.push_str(&t);
+                }
+            }
+            Segment::ThinkingEnd => {}
+            Segment::Answer(t) => answer.push_str(&t),
+        }
+    }
+
+    // A stray closing tag means the whole prefix was thinking
+    if let Some(pos) = answer.find(THINK_CLOSE) {
+        let prefix = answer[..pos].trim().to_string();
+        answer = answer[pos + THINK_CLOSE.len()..].to_string();
+        if !prefix.is_empty() {
+            thoughts.insert(0, prefix);
+        }
+    }
+
+    SplitResponse {
+        thinking: thoughts
+            .iter()
+            .map(|t| t.trim())
+            .filter(|t| !t.is_empty())
+            .collect::<Vec<_>>()
+            .join("\n\n"),
+        answer,
+    }
+}
+
+/// Piece of a streamed response, as routed by [`ThinkFilter`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Segment {
+    /// A thinking block opened
+    ThinkingStart,
+
+    /// Text inside a thinking block
+    Thinking(String),
+
+    /// A thinking block closed
+    ThinkingEnd,
+
+    /// Text outside thinking blocks
+    Answer(String),
+}
+
+/// Incremental splitter for streamed tokens
+///
+/// Tags may arrive split across tokens, so a trailing fragment that could
+/// still become a tag is held back until the next token (or [`finish`]).
+///
+/// [`finish`]: ThinkFilter::finish
+#[derive(Debug, Default)]
+pub struct ThinkFilter {
+    /// Text not yet routed (a possible partial tag)
+    pending: String,
+
+    /// Whether we are inside a thinking block
+    in_thinking: bool,
+}
+
+impl ThinkFilter {
+    /// Create a filter positioned outside any thinking block
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Whether the filter is currently inside a thinking block
+    pub fn in_thinking(&self) -> bool {
+        self.in_thinking
+    }
+
+    /// Feed one token and return the segments it completes
+    pub fn push(&mut self, token: &str) -> Vec<Segment> {
+        self.pending.push_str(token);
+        let mut segments = Vec::new();
+
+        loop {
+            let tag = if self.in_thinking { THINK_CLOSE } else { THINK_OPEN };
+            match self.pending.find(tag) {
+                Some(pos) => {
+                    let before: String = self.pending.drain(..pos).collect();
+                    self.pending.drain(..tag.len());
+                    self.emit(&mut segments, before);
+                    self.in_thinking = !self.in_thinking;
+                    segments.push(if self.in_thinking {
+                        Segment::ThinkingStart
+                    } else {
+                        Segment::ThinkingEnd
+                    });
+                }
+                None => {
+                    let keep = partial_tag_len(&self.pending, tag);
+                    let ready: String = self.pending.drain(..self.pending.len() - keep).collect();
+                    self.emit(&mut segments, ready);
+                    return segments;
+                }
+            }
+        }
+    }
+
+    /// Flush held-back text at the end of the stream
+    ///
+    /// An unclosed thinking block is closed.
+    pub fn finish(&mut self) -> Vec<Segment> {
+        let mut segments = Vec::new();
+        let rest = std::mem::take(&mut self.pending);
+        self.emit(&mut segments, rest);
+        if self.in_thinking {
+            self.in_thinking = false;
+            segments.push(Segment::ThinkingEnd);
+        }
+        segments
+    }
+
+    fn emit(&self, segments: &mut Vec<Segment>, text: String) {
+        if text.is_empty() {
+            return;
+        }
+        segments.push(if self.in_thinking {
+            Segment::Thinking(text)
+        } else {
+            Segment::Answer(text)
+        });
+    }
+}
+
+/// Length of the longest suffix of `text` that is a proper prefix of `tag`
+fn partial_tag_len(text: &str, tag: &str) -> usize {
+    (1..tag.len())
+        .rev()
+        .find(|&n| text.len() >= n && text.is_char_boundary(text.len() - n) && text.ends_with(&tag[..n]))
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split_thinking() {
+        let response = r#"<think>Maybe {"type": "final", "result": "wrong"}?</think>
+{"type": "tool_call", "tool": "list_dir", "args": {"path": "."}}"#;
+        let split = split_thinking(response);
+        assert_eq!(split.thinking, r#"Maybe {"type": "final", "result": "wrong"}?"#);
+        assert!(split.answer.trim().starts_with(r#"{"type": "tool_call""#));
+
+        // Template-opened block: only the closing tag is streamed
+        let split = split_thinking("reasoning here</think>answer");
+        assert_eq!(split.thinking, "reasoning here");
+        assert_eq!(split.answer, "answer");
+
+        // Unclosed block swallows the rest
+        let split = split_thinking("before<think>still thinking {\"a\": 1}");
+        assert_eq!(split.answer, "before");
+        assert_eq!(split.thinking, "still thinking {\"a\": 1}");
+
+        let split = split_thinking("plain answer");
+        assert_eq!(split, SplitResponse { thinking: String::new(), answer: "plain answer".into() });
+    }
+
+    #[test]
+    fn test_filter_handles_tags_split_across_tokens() {
+        let mut filter = ThinkFilter::new();
+        let mut segments = Vec::new();
+        for token in ["<th", "ink>hm", "m</", "think", ">{}", "<"] {
+            segments.extend(filter.push(token));
+        }
+        segments.extend(filter.finish());
+
+        assert_eq!(
+            segments,
+            vec![
+                Segment::ThinkingStart,
+                Segment::Thinking("hm".into()),
+                Segment::Thinking("m".into()),
+                Segment::ThinkingEnd,
+                Segment::Answer("{}".into()),
+                Segment::Answer("<".into()),
+            ]
+        );
+    }
+}
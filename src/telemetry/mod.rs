@@ -2,6 +2,9 @@
 //! 
 //! Provides real-time monitoring, event collection, and terminal display.
 
+use crate::agent::ModelRole;
+use crate::streaming::GenerationStats;
+use std::collections::HashMap;
 use std::time::Instant;
 use std::sync::{Arc, Mutex};
 
@@ -44,12 +47,77 @@ pub enum TelemetryEvent {
         tool_count: usize,
         timestamp: Instant,
     },
+
+    // Model events
+    PromptEvaluated {
+        tokens: u64,
+        duration_ms: u64,
+        timestamp: Instant,
+    },
+    GenerationEvaluated {
+        tokens: u64,
+        duration_ms: u64,
+        load_duration_ms: u64,
+        timestamp: Instant,
+    },
+    PromptCache {
+        prompt_tokens: usize,
+        evaluated_tokens: u64,
+        timestamp: Instant,
+    },
+    ModelCall {
+        role: ModelRole,
+        model: String,
+        tokens: usize,
+        duration_ms: u64,
+        timestamp: Instant,
+    },
+}
+
+/// Calls, tokens and latency of the model serving one role
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ModelUsage {
+    pub model: String,
+    pub calls: usize,
+    pub tokens: usize,
+    pub duration_ms: u64,
+}
+
+impl ModelUsage {
+    /// Mean latency per call in milliseconds
+    pub fn avg_latency_ms(&self) -> u64 {
+        if self.calls == 0 {
+            0
+        } else {
+            self.duration_ms / self.calls as u64
+        }
+    }
+
+    /// Generation throughput in tokens per second
+    pub fn tokens_per_sec(&self) -> f64 {
+        if self.duration_ms == 0 {
+            0.0
+        } else {
+            self.tokens as f64 * 1000.0 / self.duration_ms as f64
+        }
+    }
 }
 
 /// Telemetry statistics
 #[derive(Debug, Clone, Default)]
 pub struct TelemetryStats {
+    /// Tokens generated, as reported by Ollama
     pub tokens_processed: usize,
+    /// Streamed response chunks
+    pub chunks_received: usize,
+    pub prompt_tokens: usize,
+    pub prompt_eval_ms: u64,
+    pub eval_ms: u64,
+    pub load_ms: u64,
+    /// Agent-turn prompt tokens the model saw, including continued context
+    pub turn_prompt_tokens: usize,
+    /// Agent-turn prompt tokens Ollama had to evaluate
+    pub turn_prompt_evaluated: usize,
     pub tools_executed: usize,
     pub tools_succeeded: usize,
     pub tools_failed: usize,
@@ -57,6 +125,31 @@ pub struct TelemetryStats {
     pub compression_events: usize,
     pub state_transitions: usize,
     pub parallel_dispatches: usize,
+    pub model_usage: HashMap<ModelRole, ModelUsage>,
+}
+
+impl TelemetryStats {
+    /// Ollama's evaluation stats summed over all calls
+    pub fn generation(&self) -> GenerationStats {
+        GenerationStats {
+            prompt_tokens: self.prompt_tokens as u64,
+            prompt_eval_duration: std::time::Duration::from_millis(self.prompt_eval_ms),
+            eval_tokens: self.tokens_processed as u64,
+            eval_duration: std::time::Duration::from_millis(self.eval_ms),
+            load_duration: std::time::Duration::from_millis(self.load_ms),
+        }
+    }
+
+    /// Share of agent-turn prompt tokens served from Ollama's cache
+    pub fn prompt_cache_hit_ratio(&self) -> Option<f64> {
+        (self.turn_prompt_tokens > 0).then(|| {
+            GenerationStats {
+                prompt_tokens: self.turn_prompt_evaluated as u64,
+                ..GenerationStats::default()
+            }
+            .cache_hit_ratio(self.turn_prompt_tokens)
+        })
+    }
 }
 
 /// Telemetry collector
@@ -87,7 +180,7 @@ impl TelemetryCollector {
                     stats.state_transitions += 1;
                 }
                 TelemetryEvent::TokenReceived { .. } => {
-                    stats.tokens_processed += 1;
+                    stats.chunks_received += 1;
                 }
                 TelemetryEvent::ContextCompression { .. } => {
                     stats.compression_events += 1;
@@ -108,6 +201,26 @@ impl TelemetryCollector {
                 TelemetryEvent::ParallelDispatch { .. } => {
                     stats.parallel_dispatches += 1;
                 }
+                TelemetryEvent::PromptEvaluated { tokens, duration_ms, .. } => {
+                    stats.prompt_tokens += *tokens as usize;
+                    stats.prompt_eval_ms += duration_ms;
+                }
+                TelemetryEvent::GenerationEvaluated { tokens, duration_ms, load_duration_ms, .. } => {
+                    stats.tokens_processed += *tokens as usize;
+                    stats.eval_ms += duration_ms;
+                    stats.load_ms += load_duration_ms;
+                }
+                TelemetryEvent::PromptCache { prompt_tokens, evaluated_tokens, .. } => {
+                    stats.turn_prompt_tokens += prompt_tokens;
+                    stats.turn_prompt_evaluated += *evaluated_tokens as usize;
+                }
+                TelemetryEvent::ModelCall { role, model, tokens, duration_ms, .. } => {
+                    let usage = stats.model_usage.entry(*role).or_default();
+                    usage.model = model.clone();
+                    usage.calls += 1;
+                    usage.tokens += tokens;
+                    usage.duration_ms += duration_ms;
+                }
             }
         }
 
@@ -116,6 +229,34 @@ impl TelemetryCollector {
         events.push(event);
     }
 
+    /// Record Ollama's evaluation stats for one model call
+    pub fn record_generation(&self, generation: &GenerationStats) {
+        let timestamp = Instant::now();
+        self.record(TelemetryEvent::PromptEvaluated {
+            tokens: generation.prompt_tokens,
+            duration_ms: generation.prompt_eval_duration.as_millis() as u64,
+            timestamp,
+        });
+        self.record(TelemetryEvent::GenerationEvaluated {
+            tokens: generation.eval_tokens,
+            duration_ms: generation.eval_duration.as_millis() as u64,
+            load_duration_ms: generation.load_duration.as_millis() as u64,
+            timestamp,
+        });
+    }
+
+    /// Record how much of an agent turn's prompt Ollama had to evaluate
+    ///
+    /// `prompt_tokens` is the size of the whole prompt, including context
+    /// continued from the previous reply.
+    pub fn record_prompt_cache(&self, prompt_tokens: usize, generation: &GenerationStats) {
+        self.record(TelemetryEvent::PromptCache {
+            prompt_tokens,
+            evaluated_tokens: generation.prompt_tokens,
+            timestamp: Instant::now(),
+        });
+    }
+
     /// Get current statistics
     pub fn get_stats(&self) -> TelemetryStats {
         self.stats.lock().unwrap().clone()
@@ -180,11 +321,38 @@ impl TelemetryDisplay {
 📊 Session Summary");
         println!("─────────────────────────────────────");
         println!("Duration:          {:?}", elapsed);
-        println!("Tokens processed:  {}", stats.tokens_processed);
+        let generation = stats.generation();
+        println!("Tokens processed:  {} ({:.1} tok/s)", stats.tokens_processed, generation.tokens_per_sec());
+        println!(
+            "Prompt tokens:     {} ({:.2}s processing)",
+            stats.prompt_tokens,
+            generation.prompt_eval_duration.as_secs_f64()
+        );
+        if let Some(ratio) = stats.prompt_cache_hit_ratio() {
+            println!(
+                "Prompt cache:      {:.1}% reused ({} of {} turn prompt tokens evaluated)",
+                ratio * 100.0,
+                stats.turn_prompt_evaluated,
+                stats.turn_prompt_tokens
+            );
+        }
         println!("Tools executed:    {}", stats.tools_executed);
         println!("Success rate:      {:.1}%", self.collector.tool_success_rate() * 100.0);
         println!("Retries:           {}", stats.retry_attempts);
         println!("Compressions:      {}", stats.compression_events);
+        for role in ModelRole::ALL {
+            if let Some(usage) = stats.model_usage.get(&role) {
+                println!(
+                    "{:<19}{} ({} calls, {} tokens, {}ms avg, {:.1} tok/s)",
+                    format!("{} model:", role),
+                    usage.model,
+                    usage.calls,
+                    usage.tokens,
+                    usage.avg_latency_ms(),
+                    usage.tokens_per_sec()
+                );
+            }
+        }
         println!();
     }
 
@@ -219,11 +387,43 @@ mod tests {
             timestamp: Instant::now(),
         });
         
+        // Chunks are not tokens; token counts come from Ollama's eval stats
         let stats = collector.get_stats();
-        assert_eq!(stats.tokens_processed, 1);
+        assert_eq!(stats.chunks_received, 1);
+        assert_eq!(stats.tokens_processed, 0);
         assert_eq!(collector.event_count(), 1);
     }
 
+    #[test]
+    fn test_record_generation() {
+        let collector = TelemetryCollector::new();
+        let turn = GenerationStats {
+            prompt_tokens: 900,
+            prompt_eval_duration: std::time::Duration::from_millis(300),
+            eval_tokens: 60,
+            eval_duration: std::time::Duration::from_secs(2),
+            load_duration: std::time::Duration::ZERO,
+        };
+        collector.record_generation(&turn);
+        collector.record_generation(&turn);
+
+        let stats = collector.get_stats();
+        assert_eq!(collector.event_count(), 4);
+        assert_eq!(stats.tokens_processed, 120);
+        assert_eq!(stats.prompt_tokens, 1800);
+        assert_eq!(stats.prompt_eval_ms, 600);
+        assert!((stats.generation().tokens_per_sec() - 30.0).abs() < 1e-9);
+        assert_eq!(stats.prompt_cache_hit_ratio(), None);
+
+        // A full first turn, then a continued turn that only evaluates the new part
+        collector.record_prompt_cache(900, &turn);
+        collector.record_prompt_cache(1100, &GenerationStats { prompt_tokens: 100, ..turn });
+        let stats = collector.get_stats();
+        assert_eq!(stats.turn_prompt_tokens, 2000);
+        assert_eq!(stats.turn_prompt_evaluated, 1000);
+        assert!((stats.prompt_cache_hit_ratio().unwrap() - 0.5).abs() < 1e-9);
+    }
+
     #[test]
     fn test_record_tool_events() {
         let collector = TelemetryCollector::new();
@@ -310,4 +510,34 @@ mod tests {
         let stats = collector.get_stats();
         assert_eq!(stats.compression_events, 1);
     }
+
+    #[test]
+    fn test_model_usage_per_role() {
+        let collector = TelemetryCollector::new();
+        for duration_ms in [200, 400] {
+            collector.record(TelemetryEvent::ModelCall {
+                role: ModelRole::Executor,
+                model: "qwen2.5:3b".to_string(),
+                tokens: 50,
+                duration_ms,
+                timestamp: Instant::now(),
+            });
+        }
+        collector.record(TelemetryEvent::ModelCall {
+            role: ModelRole::Planner,
+            model: "qwen2.5:32b".to_string(),
+            tokens: 300,
+            duration_ms: 3000,
+            timestamp: Instant::now(),
+        });
+
+        let stats = collector.get_stats();
+        let executor = &stats.model_usage[&ModelRole::Executor];
+        assert_eq!(executor.calls, 2);
+        assert_eq!(executor.tokens, 100);
+        assert_eq!(executor.avg_latency_ms(), 300);
+        assert!((executor.tokens_per_sec() - 166.6).abs() < 0.1);
+        assert_eq!(stats.model_usage[&ModelRole::Planner].model, "qwen2.5:32b");
+        assert!(!stats.model_usage.contains_key(&ModelRole::Summary));
+    }
 }
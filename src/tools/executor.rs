@@ -7,11 +7,14 @@
 //! - 2-3× speedup for read-heavy workloads
 
 use crate::errors::Result;
+use crate::tools::profile::ToolProfile;
 use crate::tools::registry::ToolRegistry;
 use crate::tools::retry::RetryManager;
+use crate::tools::schema::validate_args;
 use crate::tools::security::PathJail;
 use crate::tools::types::{ToolContext, ToolResult};
-use crate::tools::implementations;
+use crate::tools::implementations::{self, ContentEncoding};
+use futures_util::future::join_all;
 use std::sync::Arc;
 use tokio::sync::Semaphore;
 
@@ -26,6 +29,9 @@ pub struct ParallelExecutor {
     /// Tool registry
     registry: ToolRegistry,
     
+    /// Tool profile the registry was built from
+    profile: ToolProfile,
+    
     /// Retry manager
     retry_manager: RetryManager,
     
@@ -37,14 +43,24 @@ pub struct ParallelExecutor {
 }
 
 impl ParallelExecutor {
-    /// Create new parallel executor
+    /// Create new parallel executor with offline tools
     pub fn new(
         jail: PathJail,
         context: ToolContext,
+    ) -> Self {
+        Self::with_profile(jail, context, ToolProfile::new())
+    }
+
+    /// Create parallel executor exposing only tools enabled by the profile
+    pub fn with_profile(
+        jail: PathJail,
+        context: ToolContext,
+        profile: ToolProfile,
     ) -> Self {
         Self {
             semaphore: Arc::new(Semaphore::new(MAX_PARALLEL_OPERATIONS)),
-            registry: ToolRegistry::new(),
+            registry: ToolRegistry::with_profile(&profile),
+            profile,
             retry_manager: RetryManager::new(),
             jail,
             context,
@@ -69,16 +85,69 @@ impl ParallelExecutor {
             .await
     }
 
+    /// Execute a batch of tool calls from one model turn
+    /// 
+    /// Consecutive read-only calls are dispatched concurrently (bounded by
+    /// the semaphore); each write call runs alone, in order. A read that
+    /// follows a write therefore observes the write. Results are returned
+    /// in call order.
+    pub async fn execute_batch(&self, calls: &[(String, serde_json::Value)]) -> Vec<Result<ToolResult>> {
+        let mut results = Vec::with_capacity(calls.len());
+        let mut idx = 0;
+
+        while idx < calls.len() {
+            let run_end = calls[idx..]
+                .iter()
+                .position(|(tool, _)| !self.is_read_only(tool))
+                .map_or(calls.len(), |offset| idx + offset);
+
+            if run_end > idx {
+                let reads = calls[idx..run_end]
+                    .iter()
+                    .map(|(tool, args)| self.execute(tool, args));
+                results.extend(join_all(reads).await);
+                idx = run_end;
+            } else {
+                let (tool, args) = &calls[idx];
+                results.push(self.execute(tool, args).await);
+                idx += 1;
+            }
+        }
+
+        results
+    }
+
     /// Execute tool once (without retry)
     async fn execute_once(&self, tool: &str, args: &serde_json::Value) -> Result<ToolResult> {
+        // Reject tools disabled by the profile
+        if let Some(reason) = self.profile.disabled_reason(tool) {
+            return Ok(ToolResult::failure(
+                tool.to_string(),
+                reason,
+                std::time::Duration::from_millis(0),
+            ));
+        }
+
         // Validate tool exists
-        if !self.registry.contains(tool) {
+        let Some(schema) = self.registry.get(tool) else {
             return Ok(ToolResult::failure(
                 tool.to_string(),
                 format!("Unknown tool: {}", tool),
                 std::time::Duration::from_millis(0),
             ));
-        }
+        };
+
+        // Validate (and coerce) arguments before dispatch
+        let args = match validate_args(tool, &schema.parameters, args) {
+            Ok(args) => args,
+            Err(e) => {
+                return Ok(ToolResult::failure(
+                    tool.to_string(),
+                    e.to_string(),
+                    std::time::Duration::from_millis(0),
+                ))
+            }
+        };
 
         // Route to appropriate tool implementation
         match tool {
@@ -89,21 +158,25 @@ impl ParallelExecutor {
             }
             "read_file" => {
                 let path = args["path"].as_str().unwrap_or("");
-                implementations::read_file(path, &self.context, &self.jail).await
+                let start_line = args["start_line"].as_u64().map(|l| l as usize);
+                let end_line = args["end_line"].as_u64().map(|l| l as usize);
+                implementations::read_file_range(path, start_line, end_line, &self.context, &self.jail).await
             }
             "write_file" => {
                 let path = args["path"].as_str().unwrap_or("");
-                let raw_content = args["content"].as_str().unwrap_or("");
-                // Unescape common escape sequences that models might output literally
-                let content = raw_content
-                    .replace("\\n", "\n")
-                    .replace("\\t", "\t")
-                    .replace("\\r", "\r")
-                    .replace("\\\"", "\"")
-                    .replace("\\'", "'")
-                    .replace("\\/", "/");
                 let append = args["append"].as_bool().unwrap_or(false);
-                implementations::write_file(path, &content, append, &self.context, &self.jail).await
+                let content = ContentEncoding::from_arg(args["encoding"].as_str())
+                    .and_then(|encoding| encoding.decode(args["content"].as_str().unwrap_or("")));
+                match content {
+                    Ok(content) => {
+                        implementations::write_file(path, content, append, &self.context, &self.jail).await
+                    }
+                    Err(e) => Ok(ToolResult::failure(
+                        tool.to_string(),
+                        e.to_string(),
+                        std::time::Duration::from_millis(0),
+                    )),
+                }
             }
             "run_command" => {
                 let command = args["command"].as_str().unwrap_or("");
@@ -118,6 +191,13 @@ impl ParallelExecutor {
                 let timeout = args["timeout_seconds"].as_u64().unwrap_or(60);
                 implementations::run_command(command, &args_array, timeout, &self.context).await
             }
+            "run_tests" => {
+                let path = args["path"].as_str().unwrap_or(".");
+                let filter = args["filter"].as_str();
+                let framework = args["framework"].as_str();
+                let timeout = args["timeout_seconds"].as_u64().unwrap_or(300);
+                implementations::run_tests(path, filter, framework, timeout, &self.context, &self.jail).await
+            }
             "system_info" => {
                 let info_type = args["info_type"].as_str().unwrap_or("all");
                 implementations::system_info(info_type).await
@@ -128,6 +208,49 @@ impl ParallelExecutor {
                 let timeout = args["timeout_seconds"].as_u64().unwrap_or(30);
                 implementations::web_fetch(url, method, timeout, &self.context).await
             }
+            "outline" => {
+                let path = args["path"].as_str().unwrap_or(".");
+                implementations::outline(path, &self.context, &self.jail).await
+            }
+            "archive_list" => {
+                let path = args["path"].as_str().unwrap_or("");
+                implementations::archive_list(path, &self.context, &self.jail).await
+            }
+            "archive_extract" => {
+                let path = args["path"].as_str().unwrap_or("");
+                let destination = args["destination"].as_str().unwrap_or(".");
+                implementations::archive_extract(path, destination, &self.context, &self.jail).await
+            }
+            "archive_create" => {
+                let path = args["path"].as_str().unwrap_or("");
+                let sources: Vec<String> = args["sources"]
+                    .as_array()
+                    .map(|arr| {
+                        arr.iter()
+                            .filter_map(|v| v.as_str().map(String::from))
+                            .collect()
+                    })
+                    .unwrap_or_default();
+                implementations::archive_create(path, &sources, &self.context, &self.jail).await
+            }
+            "query_data" => {
+                let path = args["path"].as_str().unwrap_or("");
+                let query = args["query"].as_str().unwrap_or("");
+                let format = args["format"].as_str();
+                let columns: Vec<String> = args["columns"]
+                    .as_array()
+                    .map(|arr| {
+                        arr.iter()
+                            .filter_map(|v| v.as_str().map(String::from))
+                            .collect()
+                    })
+                    .unwrap_or_default();
+                let limit = args["limit"]
+                    .as_u64()
+                    .map(|l| l as usize)
+                    .unwrap_or(implementations::data::DEFAULT_ROW_LIMIT);
+                implementations::query_data(path, query, format, &columns, limit, &self.context, &self.jail).await
+            }
             _ => Ok(ToolResult::failure(
                 tool.to_string(),
                 format!("Tool not implemented: {}", tool),
@@ -149,6 +272,16 @@ impl ParallelExecutor {
         &self.registry
     }
 
+    /// Get tool profile reference
+    pub fn profile(&self) -> &ToolProfile {
+        &self.profile
+    }
+
+    /// Get working directory (jail root)
+    pub fn working_dir(&self) -> &std::path::Path {
+        &self.context.working_dir
+    }
+
     /// Get current parallelism limit
     pub fn max_parallel_operations(&self) -> usize {
         MAX_PARALLEL_OPERATIONS
@@ -213,7 +346,6 @@ mod tests {
         assert!(executor.is_read_only("list_dir"));
         assert!(executor.is_read_only("read_file"));
         assert!(executor.is_read_only("system_info"));
-        assert!(executor.is_read_only("web_fetch"));
 
         // Write tools
         assert!(!executor.is_read_only("write_file"));
@@ -245,6 +377,35 @@ mod tests {
         assert!(result.success);
     }
 
+    #[tokio::test]
+    async fn test_execute_batch_preserves_order() {
+        let (executor, temp) = setup_executor().await;
+        std::fs::write(temp.path().join("a.txt"), "alpha").unwrap();
+
+        let calls = vec![
+            ("read_file".to_string(), serde_json::json!({"path": "a.txt"})),
+            ("system_info".to_string(), serde_json::json!({"info_type": "os"})),
+            ("write_file".to_string(), serde_json::json!({"path": "b.txt", "content": "beta"})),
+            ("read_file".to_string(), serde_json::json!({"path": "b.txt"})),
+            ("unknown_tool".to_string(), serde_json::json!({})),
+        ];
+
+        let results: Vec<ToolResult> = executor
+            .execute_batch(&calls)
+            .await
+            .into_iter()
+            .map(|r| r.unwrap())
+            .collect();
+
+        assert_eq!(results.len(), 5);
+        assert_eq!(results[0].output, "alpha");
+        assert_eq!(results[1].tool, "system_info");
+        assert!(results[2].success);
+        // Read after write sees the written file
+        assert_eq!(results[3].output, "beta");
+        assert!(!results[4].success);
+    }
+
     #[tokio::test]
     async fn test_execute_unknown_tool() {
         let (executor, _temp) = setup_executor().await;
@@ -256,6 +417,69 @@ mod tests {
         assert!(result.error.unwrap().contains("Unknown tool"));
     }
 
+    #[tokio::test]
+    async fn test_write_file_content_encoding() {
+        let (executor, temp) = setup_executor().await;
+        let read = |name: &str| std::fs::read(temp.path().join(name)).unwrap();
+
+        // Escapes in ordinary content are written as-is
+        let c_source = "printf(\"%d\\n\", x);\n";
+        let args = serde_json::json!({"path": "a.c", "content": c_source});
+        assert!(executor.execute("write_file", &args).await.unwrap().success);
+        assert_eq!(read("a.c"), c_source.as_bytes());
+
+        // A squashed, double-encoded file is decoded once
+        let args = serde_json::json!({"path": "b.py", "content": r#"def f():\n    return \"x\""#});
+        assert!(executor.execute("write_file", &args).await.unwrap().success);
+        assert_eq!(read("b.py"), b"def f():\n    return \"x\"");
+
+        // ...unless utf8 is requested explicitly
+        let args = serde_json::json!({"path": "c.txt", "content": r"a\nb", "encoding": "utf8"});
+        assert!(executor.execute("write_file", &args).await.unwrap().success);
+        assert_eq!(read("c.txt"), br"a\nb");
+
+        let args = serde_json::json!({"path": "d.bin", "content": "AAEC/w==", "encoding": "base64"});
+        assert!(executor.execute("write_file", &args).await.unwrap().success);
+        assert_eq!(read("d.bin"), vec![0u8, 1, 2, 255]);
+
+        let args = serde_json::json!({"path": "e.bin", "content": "%%%", "encoding": "base64"});
+        let result = executor.execute("write_file", &args).await.unwrap();
+        assert!(result.error.unwrap().contains("not valid base64"));
+    }
+
+    #[tokio::test]
+    async fn test_execute_validates_args() {
+        let (executor, temp) = setup_executor().await;
+
+        // A missing path is reported, not written to an empty file name
+        let args = serde_json::json!({"content": "hello"});
+        let result = executor.execute("write_file", &args).await.unwrap();
+        assert!(!result.success);
+        assert!(result.error.unwrap().contains("missing required argument 'path'"));
+
+        // Coerced arguments still run
+        std::fs::write(temp.path().join("a.txt"), "one\ntwo\nthree\n").unwrap();
+        let args = serde_json::json!({"path": "a.txt", "start_line": "2", "end_line": 2.0});
+        let result = executor.execute("read_file", &args).await.unwrap();
+        assert!(result.success, "{:?}", result.error);
+        assert!(result.output.contains("two"));
+        assert!(!result.output.contains("three"));
+    }
+
+    #[tokio::test]
+    async fn test_execute_disabled_tool_rejected() {
+        // The default executor is offline
+        let (executor, _temp) = setup_executor().await;
+
+        assert!(!executor.registry().contains("web_fetch"));
+
+        let args = serde_json::json!({"url": "http://example.com"});
+        let result = executor.execute("web_fetch", &args).await.unwrap();
+
+        assert!(!result.success);
+        assert!(result.error.unwrap().contains("--online"));
+    }
+
     #[tokio::test]
     async fn test_parallel_execution() {
         let (executor, temp) = setup_executor().await;
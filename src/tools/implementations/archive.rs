@@ -0,0 +1,815 @@
+//! Archive tool implementations
+//!
+//! Implements tar, tar.gz and zip handling inside the jail:
+//! - archive_list: List archive entries with sizes
+//! - archive_extract: Extract entries with zip-slip and size checks
+//! - archive_create: Bundle files and directories into an archive
+//!
+//! Extraction validates every entry before writing anything, so a
+//! malicious or oversized archive is rejected without partial output.
+//! If extraction still fails part-way (e.g. an entry is larger than its
+//! header declared), the files and directories it created are removed again.
+
+use crate::errors::{AgentError, Result};
+use crate::tools::security::PathJail;
+use crate::tools::types::{ToolContext, ToolResult};
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use std::fs::{self, File};
+use std::io::{self, Read, Write};
+use std::path::{Component, Path, PathBuf};
+use std::time::Instant;
+
+/// Maximum entries listed in tool output
+const MAX_LISTED_ENTRIES: usize = 100;
+
+/// What an extraction has put on disk so far
+#[derive(Debug, Default)]
+struct Extracted {
+    /// Files written, relative to the destination, with their sizes
+    files: Vec<(String, u64)>,
+    /// Directories created, parents before children
+    dirs: Vec<PathBuf>,
+}
+
+impl Extracted {
+    /// Remove everything again so no half-extracted tree is left behind
+    ///
+    /// Directories are only removed if empty, so pre-existing content
+    /// that ended up inside them is never touched.
+    fn discard(&self, dest_dir: &Path) {
+        for (relative, _) in &self.files {
+            let _ = fs::remove_file(dest_dir.join(relative));
+        }
+        for dir in self.dirs.iter().rev() {
+            let _ = fs::remove_dir(dir);
+        }
+    }
+}
+
+/// Supported archive formats
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ArchiveFormat {
+    Tar,
+    TarGz,
+    Zip,
+}
+
+impl ArchiveFormat {
+    /// Detect format from file name
+    pub fn from_path(path: &Path) -> Option<Self> {
+        let name = path.file_name()?.to_str()?.to_lowercase();
+        if name.ends_with(".tar.gz") || name.ends_with(".tgz") {
+            Some(Self::TarGz)
+        } else if name.ends_with(".tar") {
+            Some(Self::Tar)
+        } else if name.ends_with(".zip") {
+            Some(Self::Zip)
+        } else {
+            None
+        }
+    }
+}
+
+/// Archive entry metadata
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ArchiveEntry {
+    /// Path inside the archive
+    pub path: String,
+    /// Uncompressed size in bytes
+    pub size: u64,
+    /// Entry kind
+    pub kind: EntryKind,
+}
+
+/// Kind of archive entry
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EntryKind {
+    File,
+    Directory,
+    /// Symlinks, hard links and devices (never extracted)
+    Other,
+}
+
+/// List archive contents
+///
+/// # Security
+/// - Path validated through jail
+/// - Read-only operation (safe for parallelization)
+pub async fn archive_list(
+    path: &str,
+    context: &ToolContext,
+    jail: &PathJail,
+) -> Result<ToolResult> {
+    let start = Instant::now();
+
+    let archive_path = jail.verify_and_canonicalize(path)?;
+    let format = match check_archive(&archive_path, path) {
+        Ok(format) => format,
+        Err(e) => return Ok(ToolResult::failure("archive_list".to_string(), e, start.elapsed())),
+    };
+
+    let entries = match read_entries(&archive_path, format) {
+        Ok(entries) => entries,
+        Err(e) => {
+            return Ok(ToolResult::failure(
+                "archive_list".to_string(),
+                format!("Failed to read archive {}: {}", path, e),
+                start.elapsed(),
+            ));
+        }
+    };
+
+    let total: u64 = entries.iter().map(|e| e.size).sum();
+    let mut output = format!(
+        "{} entries, {} bytes uncompressed\n",
+        entries.len(),
+        total
+    );
+    for entry in entries.iter().take(MAX_LISTED_ENTRIES) {
+        let line = match entry.kind {
+            EntryKind::Directory => format!("  {:>10}  {}/\n", "-", entry.path.trim_end_matches('/')),
+            EntryKind::File => format!("  {:>10}  {}\n", entry.size, entry.path),
+            EntryKind::Other => format!("  {:>10}  {} (link/special)\n", "-", entry.path),
+        };
+        if output.len() + line.len() > context.max_output_size {
+            break;
+        }
+        output.push_str(&line);
+    }
+    if entries.len() > MAX_LISTED_ENTRIES {
+        output.push_str(&format!("  ... {} more\n", entries.len() - MAX_LISTED_ENTRIES));
+    }
+
+    Ok(ToolResult::success(
+        "archive_list".to_string(),
+        output,
+        start.elapsed(),
+    ))
+}
+
+/// Extract archive into a directory
+///
+/// # Security
+/// - Archive and destination validated through jail
+/// - Entry paths must be relative without `..` (zip-slip)
+/// - Each written path re-verified through jail (symlinked directories)
+/// - Directories only created beneath a verified existing ancestor
+/// - Links and special files skipped
+/// - Total size capped by max_extract_size
+/// - Not read-only (sequential execution required)
+pub async fn archive_extract(
+    path: &str,
+    destination: &str,
+    context: &ToolContext,
+    jail: &PathJail,
+) -> Result<ToolResult> {
+    let start = Instant::now();
+
+    let archive_path = jail.verify_and_canonicalize(path)?;
+    let format = match check_archive(&archive_path, path) {
+        Ok(format) => format,
+        Err(e) => return Ok(ToolResult::failure("archive_extract".to_string(), e, start.elapsed())),
+    };
+
+    // Validate every entry before touching the filesystem
+    let entries = match read_entries(&archive_path, format) {
+        Ok(entries) => entries,
+        Err(e) => {
+            return Ok(ToolResult::failure(
+                "archive_extract".to_string(),
+                format!("Failed to read archive {}: {}", path, e),
+                start.elapsed(),
+            ));
+        }
+    };
+
+    if let Some(bad) = entries.iter().find(|e| safe_entry_path(&e.path).is_none()) {
+        return Ok(ToolResult::failure(
+            "archive_extract".to_string(),
+            format!("Refusing to extract: entry escapes destination: {}", bad.path),
+            start.elapsed(),
+        ));
+    }
+
+    let total: u64 = entries
+        .iter()
+        .filter(|e| e.kind == EntryKind::File)
+        .map(|e| e.size)
+        .sum();
+    if total > context.max_extract_size as u64 {
+        return Ok(ToolResult::failure(
+            "archive_extract".to_string(),
+            format!(
+                "Archive too large: {} bytes uncompressed (max: {} bytes)",
+                total, context.max_extract_size
+            ),
+            start.elapsed(),
+        ));
+    }
+
+    let mut extracted = Extracted::default();
+    let dest_dir = match prepare_directory(destination, jail, &mut extracted.dirs) {
+        Ok(dest_dir) => dest_dir,
+        Err(e) => {
+            extracted.discard(jail.jail_root());
+            return Err(e);
+        }
+    };
+
+    let result = match File::open(&archive_path) {
+        Ok(file) => match format {
+            ArchiveFormat::Tar => extract_tar(file, &dest_dir, context, jail, &mut extracted),
+            ArchiveFormat::TarGz => extract_tar(GzDecoder::new(file), &dest_dir, context, jail, &mut extracted),
+            ArchiveFormat::Zip => extract_zip(file, &dest_dir, context, jail, &mut extracted),
+        },
+        Err(e) => Err(e),
+    };
+
+    if let Err(e) = result {
+        extracted.discard(&dest_dir);
+        return Ok(ToolResult::failure(
+            "archive_extract".to_string(),
+            format!("Extraction failed: {}", e),
+            start.elapsed(),
+        ));
+    }
+
+    let skipped: Vec<&str> = entries
+        .iter()
+        .filter(|e| e.kind == EntryKind::Other)
+        .map(|e| e.path.as_str())
+        .collect();
+
+    let written = extracted.files;
+    let bytes: u64 = written.iter().map(|(_, size)| size).sum();
+    let mut output = format!(
+        "Extracted {} files ({} bytes) to {}\n",
+        written.len(),
+        bytes,
+        dest_dir.display()
+    );
+    for (file, size) in written.iter().take(MAX_LISTED_ENTRIES) {
+        output.push_str(&format!("  {} ({} bytes)\n", file, size));
+    }
+    if written.len() > MAX_LISTED_ENTRIES {
+        output.push_str(&format!("  ... {} more\n", written.len() - MAX_LISTED_ENTRIES));
+    }
+    if !skipped.is_empty() {
+        output.push_str(&format!("Skipped links/special files: {}\n", skipped.join(", ")));
+    }
+
+    Ok(ToolResult::success(
+        "archive_extract".to_string(),
+        output,
+        start.elapsed(),
+    ))
+}
+
+/// Create archive from files and directories
+///
+/// # Security
+/// - Output and every source validated through jail
+/// - Entry names are paths relative to the jail root
+/// - Total input size capped by max_extract_size
+/// - Not read-only (sequential execution required)
+pub async fn archive_create(
+    path: &str,
+    sources: &[String],
+    context: &ToolContext,
+    jail: &PathJail,
+) -> Result<ToolResult> {
+    let start = Instant::now();
+
+    let Some(format) = ArchiveFormat::from_path(Path::new(path)) else {
+        return Ok(ToolResult::failure(
+            "archive_create".to_string(),
+            format!("Unsupported archive type: {} (use .tar, .tar.gz, .tgz or .zip)", path),
+            start.elapsed(),
+        ));
+    };
+
+    if sources.is_empty() {
+        return Ok(ToolResult::failure(
+            "archive_create".to_string(),
+            "No sources given".to_string(),
+            start.elapsed(),
+        ));
+    }
+
+    let output_path = jail.verify_and_canonicalize(path)?;
+
+    // Resolve sources to (absolute path, entry name) pairs
+    let mut files = Vec::new();
+    for source in sources {
+        let source_path = jail.verify_and_canonicalize(source)?;
+        if !source_path.exists() {
+            return Ok(ToolResult::failure(
+                "archive_create".to_string(),
+                format!("Source does not exist: {}", source),
+                start.elapsed(),
+            ));
+        }
+        collect_files(&source_path, &output_path, &mut files)?;
+    }
+
+    let mut total: u64 = 0;
+    for file in &files {
+        total += fs::metadata(file)?.len();
+    }
+    if total > context.max_extract_size as u64 {
+        return Ok(ToolResult::failure(
+            "archive_create".to_string(),
+            format!(
+                "Sources too large: {} bytes (max: {} bytes)",
+                total, context.max_extract_size
+            ),
+            start.elapsed(),
+        ));
+    }
+
+    let entries: Vec<(PathBuf, String)> = files
+        .into_iter()
+        .map(|file| {
+            let name = file
+                .strip_prefix(jail.jail_root())
+                .unwrap_or(&file)
+                .to_string_lossy()
+                .replace('\\', "/");
+            (file, name)
+        })
+        .collect();
+
+    let result = match format {
+        ArchiveFormat::Tar => write_tar(File::create(&output_path)?, &entries).map(|_| ()),
+        ArchiveFormat::TarGz => write_tar(GzEncoder::new(File::create(&output_path)?, Compression::default()), &entries)
+            .and_then(|encoder| encoder.finish().map(|_| ())),
+        ArchiveFormat::Zip => write_zip(File::create(&output_path)?, &entries),
+    };
+
+    if let Err(e) = result {
+        let _ = fs::remove_file(&output_path);
+        return Ok(ToolResult::failure(
+            "archive_create".to_string(),
+            format!("Failed to create archive: {}", e),
+            start.elapsed(),
+        ));
+    }
+
+    let archive_size = fs::metadata(&output_path)?.len();
+    let mut output = format!(
+        "Created {} with {} files ({} bytes in, {} bytes archive)\n",
+        path,
+        entries.len(),
+        total,
+        archive_size
+    );
+    for (_, name) in entries.iter().take(MAX_LISTED_ENTRIES) {
+        output.push_str(&format!("  {}\n", name));
+    }
+    if entries.len() > MAX_LISTED_ENTRIES {
+        output.push_str(&format!("  ... {} more\n", entries.len() - MAX_LISTED_ENTRIES));
+    }
+
+    Ok(ToolResult::success(
+        "archive_create".to_string(),
+        output,
+        start.elapsed(),
+    ))
+}
+
+/// Check archive exists and has a supported format
+fn check_archive(archive_path: &Path, display: &str) -> std::result::Result<ArchiveFormat, String> {
+    if !archive_path.is_file() {
+        return Err(format!("Archive does not exist: {}", display));
+    }
+    ArchiveFormat::from_path(archive_path).ok_or_else(|| {
+        format!("Unsupported archive type: {} (use .tar, .tar.gz, .tgz or .zip)", display)
+    })
+}
+
+/// Validate an entry path: relative, no `..`, no root or drive prefix
+pub fn safe_entry_path(name: &str) -> Option<PathBuf> {
+    let path = Path::new(name);
+    let mut safe = PathBuf::new();
+
+    for component in path.components() {
+        match component {
+            Component::Normal(part) => safe.push(part),
+            Component::CurDir => {}
+            Component::ParentDir | Component::RootDir | Component::Prefix(_) => return None,
+        }
+    }
+
+    if safe.as_os_str().is_empty() {
+        None
+    } else {
+        Some(safe)
+    }
+}
+
+/// Create destination directory inside the jail
+fn prepare_directory(destination: &str, jail: &PathJail, created: &mut Vec<PathBuf>) -> Result<PathBuf> {
+    let path = Path::new(destination);
+
+    if path.is_relative() {
+        let relative = safe_entry_path(destination).unwrap_or_default();
+        if relative.as_os_str().is_empty() && !matches!(destination, "" | ".") {
+            return Err(AgentError::Generic(format!(
+                "Path escapes jail: {}",
+                destination
+            )));
+        }
+        return Ok(create_dir_in_jail(&jail.jail_root().join(relative), jail, created)?);
+    }
+
+    Ok(create_dir_in_jail(path, jail, created)?)
+}
+
+/// Create `dir` and any missing parents without leaving the jail
+///
+/// The deepest existing ancestor is canonicalized and verified before
+/// anything is created, and the missing components are then created one
+/// by one beneath it. A symlinked directory inside the jail can therefore
+/// not be used to create directories outside it. Each new directory is
+/// appended to `created`.
+fn create_dir_in_jail(dir: &Path, jail: &PathJail, created: &mut Vec<PathBuf>) -> io::Result<PathBuf> {
+    let escapes = || io::Error::new(io::ErrorKind::PermissionDenied, format!("Path escapes jail: {}", dir.display()));
+
+    let mut existing = dir;
+    let mut missing = Vec::new();
+    while fs::symlink_metadata(existing).is_err() {
+        match (existing.parent(), existing.file_name()) {
+            (Some(parent), Some(name)) => {
+                missing.push(name);
+                existing = parent;
+            }
+            _ => return Err(escapes()),
+        }
+    }
+
+    let mut current = existing.canonicalize()?;
+    if !current.starts_with(jail.jail_root()) {
+        return Err(escapes());
+    }
+
+    for name in missing.into_iter().rev() {
+        current.push(name);
+        fs::create_dir(&current)?;
+        created.push(current.clone());
+    }
+
+    if !current.is_dir() {
+        return Err(io::Error::new(
+            io::ErrorKind::AlreadyExists,
+            format!("Not a directory: {}", dir.display()),
+        ));
+    }
+    Ok(current)
+}
+
+/// Read entry metadata without extracting
+fn read_entries(archive_path: &Path, format: ArchiveFormat) -> io::Result<Vec<ArchiveEntry>> {
+    match format {
+        ArchiveFormat::Tar => tar_entries(File::open(archive_path)?),
+        ArchiveFormat::TarGz => tar_entries(GzDecoder::new(File::open(archive_path)?)),
+        ArchiveFormat::Zip => {
+            let mut archive = zip::ZipArchive::new(File::open(archive_path)?).map_err(zip_error)?;
+            let mut entries = Vec::with_capacity(archive.len());
+            for i in 0..archive.len() {
+                let file = archive.by_index(i).map_err(zip_error)?;
+                let kind = if file.is_dir() {
+                    EntryKind::Directory
+                } else if file.is_symlink() {
+                    EntryKind::Other
+                } else {
+                    EntryKind::File
+                };
+                entries.push(ArchiveEntry {
+                    path: file.name().to_string(),
+                    size: file.size(),
+                    kind,
+                });
+            }
+            Ok(entries)
+        }
+    }
+}
+
+fn tar_entries<R: Read>(reader: R) -> io::Result<Vec<ArchiveEntry>> {
+    let mut archive = tar::Archive::new(reader);
+    let mut entries = Vec::new();
+    for entry in archive.entries()? {
+        let entry = entry?;
+        entries.push(ArchiveEntry {
+            path: entry.path()?.to_string_lossy().to_string(),
+            size: entry.size(),
+            kind: tar_kind(entry.header().entry_type()),
+        });
+    }
+    Ok(entries)
+}
+
+fn tar_kind(entry_type: tar::EntryType) -> EntryKind {
+    if entry_type.is_dir() {
+        EntryKind::Directory
+    } else if entry_type.is_file() {
+        EntryKind::File
+    } else {
+        EntryKind::Other
+    }
+}
+
+/// Resolve and verify the output path for an entry, creating its parents
+fn entry_target(
+    name: &str,
+    dest_dir: &Path,
+    jail: &PathJail,
+    created: &mut Vec<PathBuf>,
+) -> io::Result<(PathBuf, PathBuf)> {
+    let relative = safe_entry_path(name).ok_or_else(|| {
+        io::Error::new(io::ErrorKind::InvalidData, format!("entry escapes destination: {}", name))
+    })?;
+    let target = dest_dir.join(&relative);
+    if let Some(parent) = target.parent() {
+        create_dir_in_jail(parent, jail, created)?;
+    }
+    // Re-verify so pre-existing symlinked directories cannot redirect writes
+    let verified = jail
+        .verify_and_canonicalize(&target)
+        .map_err(|e| io::Error::new(io::ErrorKind::PermissionDenied, e.to_string()))?;
+    Ok((relative, verified))
+}
+
+/// Copy at most `remaining` bytes, failing if the entry is larger than declared
+///
+/// The target file is removed again on failure.
+fn copy_capped<R: Read>(reader: R, target: &Path, remaining: &mut u64) -> io::Result<u64> {
+    let mut file = File::create(target)?;
+    let copied = io::copy(&mut reader.take(*remaining + 1), &mut file).and_then(|written| {
+        if written > *remaining {
+            Err(io::Error::new(io::ErrorKind::InvalidData, "extracted size exceeds limit"))
+        } else {
+            Ok(written)
+        }
+    });
+    drop(file);
+
+    match copied {
+        Ok(written) => {
+            *remaining -= written;
+            Ok(written)
+        }
+        Err(e) => {
+            let _ = fs::remove_file(target);
+            Err(e)
+        }
+    }
+}
+
+fn extract_tar<R: Read>(
+    reader: R,
+    dest_dir: &Path,
+    context: &ToolContext,
+    jail: &PathJail,
+    extracted: &mut Extracted,
+) -> io::Result<()> {
+    let mut archive = tar::Archive::new(reader);
+    let mut remaining = context.max_extract_size as u64;
+
+    for entry in archive.entries()? {
+        let entry = entry?;
+        let name = entry.path()?.to_string_lossy().to_string();
+        match tar_kind(entry.header().entry_type()) {
+            EntryKind::Directory => {
+                let (_, target) = entry_target(&name, dest_dir, jail, &mut extracted.dirs)?;
+                create_dir_in_jail(&target, jail, &mut extracted.dirs)?;
+            }
+            EntryKind::File => {
+                let (relative, target) = entry_target(&name, dest_dir, jail, &mut extracted.dirs)?;
+                let size = copy_capped(entry, &target, &mut remaining)?;
+                extracted.files.push((relative.display().to_string(), size));
+            }
+            EntryKind::Other => {}
+        }
+    }
+
+    Ok(())
+}
+
+fn extract_zip(
+    file: File,
+    dest_dir: &Path,
+    context: &ToolContext,
+    jail: &PathJail,
+    extracted: &mut Extracted,
+) -> io::Result<()> {
+    let mut archive = zip::ZipArchive::new(file).map_err(zip_error)?;
+    let mut remaining = context.max_extract_size as u64;
+
+    for i in 0..archive.len() {
+        let file = archive.by_index(i).map_err(zip_error)?;
+        let name = file.name().to_string();
+        if file.is_dir() {
+            let (_, target) = entry_target(&name, dest_dir, jail, &mut extracted.dirs)?;
+            create_dir_in_jail(&target, jail, &mut extracted.dirs)?;
+        } else if !file.is_symlink() {
+            let (relative, target) = entry_target(&name, dest_dir, jail, &mut extracted.dirs)?;
+            let size = copy_capped(file, &target, &mut remaining)?;
+            extracted.files.push((relative.display().to_string(), size));
+        }
+    }
+
+    Ok(())
+}
+
+/// Recursively collect regular files, skipping the archive being written
+fn collect_files(path: &Path, exclude: &Path, files: &mut Vec<PathBuf>) -> Result<()> {
+    if path == exclude {
+        return Ok(());
+    }
+
+    let file_type = fs::symlink_metadata(path)?.file_type();
+    if file_type.is_dir() {
+        let mut children: Vec<PathBuf> = fs::read_dir(path)?
+            .filter_map(|e| e.ok().map(|e| e.path()))
+            .collect();
+        children.sort();
+        for child in children {
+            collect_files(&child, exclude, files)?;
+        }
+    } else if file_type.is_file() {
+        files.push(path.to_path_buf());
+    }
+
+    Ok(())
+}
+
+fn write_tar<W: Write>(writer: W, entries: &[(PathBuf, String)]) -> io::Result<W> {
+    let mut builder = tar::Builder::new(writer);
+    for (file, name) in entries {
+        builder.append_path_with_name(file, name)?;
+    }
+    builder.into_inner()
+}
+
+fn write_zip(writer: File, entries: &[(PathBuf, String)]) -> io::Result<()> {
+    let mut zip = zip::ZipWriter::new(writer);
+    let options = zip::write::SimpleFileOptions::default()
+        .compression_method(zip::CompressionMethod::Deflated);
+
+    for (file, name) in entries {
+        zip.start_file(name.as_str(), options).map_err(zip_error)?;
+        io::copy(&mut File::open(file)?, &mut zip)?;
+    }
+
+    zip.finish().map_err(zip_error)?;
+    Ok(())
+}
+
+fn zip_error(e: zip::result::ZipError) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    async fn setup_test_env() -> (TempDir, PathJail, ToolContext) {
+        let temp_dir = TempDir::new().unwrap();
+        let jail = PathJail::new(temp_dir.path()).unwrap();
+        let context = ToolContext::new(temp_dir.path().to_path_buf());
+        (temp_dir, jail, context)
+    }
+
+    fn write_logs(root: &Path) {
+        fs::create_dir_all(root.join("logs/app")).unwrap();
+        fs::write(root.join("logs/app/server.log"), "started\nready\n").unwrap();
+        fs::write(root.join("logs/error.log"), "boom\n").unwrap();
+    }
+
+    #[test]
+    fn test_safe_entry_path() {
+        assert_eq!(safe_entry_path("logs/a.log"), Some(PathBuf::from("logs/a.log")));
+        assert_eq!(safe_entry_path("./logs/"), Some(PathBuf::from("logs")));
+        assert_eq!(safe_entry_path("../evil"), None);
+        assert_eq!(safe_entry_path("logs/../../evil"), None);
+        assert_eq!(safe_entry_path("/etc/passwd"), None);
+        assert_eq!(safe_entry_path(""), None);
+    }
+
+    #[tokio::test]
+    async fn test_round_trip_all_formats() {
+        for name in ["bundle.tar", "bundle.tar.gz", "bundle.zip"] {
+            let (temp, jail, context) = setup_test_env().await;
+            write_logs(temp.path());
+
+            let result = archive_create(name, &["logs".to_string()], &context, &jail).await.unwrap();
+            assert!(result.success, "{}: {:?}", name, result.error);
+            assert!(result.output.contains("2 files"));
+
+            let result = archive_list(name, &context, &jail).await.unwrap();
+            assert!(result.success);
+            assert!(result.output.contains("logs/app/server.log"));
+
+            let result = archive_extract(name, "out/restored", &context, &jail).await.unwrap();
+            assert!(result.success, "{}: {:?}", name, result.error);
+            assert!(result.output.starts_with("Extracted 2 files"));
+            assert_eq!(
+                fs::read_to_string(temp.path().join("out/restored/logs/error.log")).unwrap(),
+                "boom\n"
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn test_extract_rejects_zip_slip() {
+        let (temp, jail, context) = setup_test_env().await;
+
+        let mut zip = zip::ZipWriter::new(File::create(temp.path().join("evil.zip")).unwrap());
+        zip.start_file("../escaped.txt", zip::write::SimpleFileOptions::default()).unwrap();
+        zip.write_all(b"pwned").unwrap();
+        zip.finish().unwrap();
+
+        let result = archive_extract("evil.zip", ".", &context, &jail).await.unwrap();
+        assert!(!result.success);
+        assert!(result.error.unwrap().contains("escapes destination"));
+        assert!(!temp.path().parent().unwrap().join("escaped.txt").exists());
+    }
+
+    #[tokio::test]
+    async fn test_extract_size_cap() {
+        let (temp, jail, _) = setup_test_env().await;
+        let context = ToolContext::new(temp.path().to_path_buf()).with_max_extract_size(4);
+        write_logs(temp.path());
+
+        let result = archive_create("bundle.tar", &["logs".to_string()], &ToolContext::new(temp.path().to_path_buf()), &jail)
+            .await
+            .unwrap();
+        assert!(result.success);
+
+        let result = archive_extract("bundle.tar", "out", &context, &jail).await.unwrap();
+        assert!(!result.success);
+        assert!(result.error.unwrap().contains("too large"));
+        assert!(!temp.path().join("out").exists());
+    }
+
+    #[tokio::test]
+    async fn test_failed_extract_removes_written_files() {
+        let (temp, jail, context) = setup_test_env().await;
+        write_logs(temp.path());
+        let result = archive_create("bundle.tar", &["logs".to_string()], &context, &jail).await.unwrap();
+        assert!(result.success);
+
+        // logs/app/server.log is written first, then logs/error.log cannot be
+        fs::create_dir_all(temp.path().join("out/logs/error.log")).unwrap();
+
+        let result = archive_extract("bundle.tar", "out", &context, &jail).await.unwrap();
+        assert!(!result.success);
+        assert!(result.error.unwrap().contains("Extraction failed"));
+        assert!(!temp.path().join("out/logs/app/server.log").exists());
+        assert!(!temp.path().join("out/logs/app").exists());
+        assert!(temp.path().join("out/logs/error.log").is_dir());
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_extract_does_not_create_dirs_through_symlinks() {
+        let (temp, jail, context) = setup_test_env().await;
+        let outside = TempDir::new().unwrap();
+        write_logs(temp.path());
+        let result = archive_create("bundle.tar", &["logs".to_string()], &context, &jail).await.unwrap();
+        assert!(result.success);
+
+        std::os::unix::fs::symlink(outside.path(), temp.path().join("link")).unwrap();
+        assert!(archive_extract("bundle.tar", "link/nested", &context, &jail).await.is_err());
+        assert!(!outside.path().join("nested").exists());
+
+        // A symlinked directory below the destination is rejected per entry
+        fs::create_dir(temp.path().join("out")).unwrap();
+        std::os::unix::fs::symlink(outside.path(), temp.path().join("out/logs")).unwrap();
+        let result = archive_extract("bundle.tar", "out", &context, &jail).await.unwrap();
+        assert!(!result.success);
+        assert!(!outside.path().join("app").exists());
+        assert_eq!(fs::read_dir(outside.path()).unwrap().count(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_unsupported_and_jail() {
+        let (temp, jail, context) = setup_test_env().await;
+        fs::write(temp.path().join("notes.txt"), "x").unwrap();
+        write_logs(temp.path());
+
+        let result = archive_list("notes.txt", &context, &jail).await.unwrap();
+        assert!(!result.success);
+
+        let result = archive_create("bundle.zip", &["logs".to_string()], &context, &jail).await.unwrap();
+        assert!(result.success);
+
+        assert!(archive_list("../../../etc/passwd", &context, &jail).await.is_err());
+        assert!(archive_extract("bundle.zip", "../outside", &context, &jail).await.is_err());
+        assert!(archive_create("bundle.tar", &["/etc".to_string()], &context, &jail).await.is_err());
+    }
+}
@@ -0,0 +1,669 @@
+//! Structured data tool implementations
+//!
+//! Implements read-only queries over structured files:
+//! - query_data: Evaluate a path expression against JSON, YAML or TOML,
+//!   or column filters against CSV/TSV, returning only the matches
+
+use crate::errors::{AgentError, Result};
+use crate::tools::security::PathJail;
+use crate::tools::types::{ToolContext, ToolResult};
+use serde_json::Value;
+use std::fs;
+use std::path::Path;
+use std::time::Instant;
+
+/// Default maximum number of CSV rows returned
+pub const DEFAULT_ROW_LIMIT: usize = 100;
+
+/// Supported structured data formats
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DataFormat {
+    Json,
+    Yaml,
+    Toml,
+    Csv,
+    Tsv,
+}
+
+impl DataFormat {
+    /// Parse explicit format name
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_lowercase().as_str() {
+            "json" => Some(Self::Json),
+            "yaml" | "yml" => Some(Self::Yaml),
+            "toml" => Some(Self::Toml),
+            "csv" => Some(Self::Csv),
+            "tsv" => Some(Self::Tsv),
+            _ => None,
+        }
+    }
+
+    /// Detect format from file extension
+    pub fn from_path(path: &Path) -> Option<Self> {
+        path.extension()
+            .and_then(|ext| ext.to_str())
+            .and_then(Self::from_name)
+    }
+}
+
+/// One step of a JSONPath-like expression
+#[derive(Debug, Clone, PartialEq)]
+pub enum PathSegment {
+    /// Object member: `.name` or `['name']`
+    Key(String),
+    /// Array element: `[0]`, negative counts from the end
+    Index(i64),
+    /// All children: `.*` or `[*]`
+    Wildcard,
+    /// Member at any depth: `..name`
+    RecursiveKey(String),
+    /// All descendants: `..*`
+    RecursiveWildcard,
+}
+
+/// Query structured data file
+///
+/// # Security
+/// - Path validated through jail
+/// - Size limit enforced (max_output_size)
+/// - Read-only operation (safe for parallelization)
+pub async fn query_data(
+    path: &str,
+    query: &str,
+    format: Option<&str>,
+    columns: &[String],
+    limit: usize,
+    context: &ToolContext,
+    jail: &PathJail,
+) -> Result<ToolResult> {
+    let start = Instant::now();
+
+    let verified_path = jail.verify_and_canonicalize(path)?;
+
+    if !verified_path.is_file() {
+        return Ok(ToolResult::failure(
+            "query_data".to_string(),
+            format!("File does not exist: {}", path),
+            start.elapsed(),
+        ));
+    }
+
+    let format = match format {
+        Some(name) => DataFormat::from_name(name),
+        None => DataFormat::from_path(&verified_path),
+    };
+
+    let Some(format) = format else {
+        return Ok(ToolResult::failure(
+            "query_data".to_string(),
+            format!(
+                "Cannot determine data format for {} (use format: json, yaml, toml, csv or tsv)",
+                path
+            ),
+            start.elapsed(),
+        ));
+    };
+
+    let metadata = fs::metadata(&verified_path).map_err(|e| {
+        AgentError::Generic(format!("Failed to read metadata: {}", e))
+    })?;
+
+    if metadata.len() > context.max_output_size as u64 {
+        return Ok(ToolResult::failure(
+            "query_data".to_string(),
+            format!(
+                "File too large: {} bytes (max: {} bytes)",
+                metadata.len(),
+                context.max_output_size
+            ),
+            start.elapsed(),
+        ));
+    }
+
+    let content = fs::read_to_string(&verified_path).map_err(|e| {
+        AgentError::Generic(format!("Failed to read file: {}", e))
+    })?;
+
+    let matches = match format {
+        DataFormat::Csv | DataFormat::Tsv => {
+            let delimiter = if format == DataFormat::Tsv { b'\t' } else { b',' };
+            query_delimited(&content, delimiter, query, columns, limit)
+        }
+        _ => parse_document(&content, format)
+            .and_then(|doc| evaluate_path(&doc, query).map(|found| found.into_iter().cloned().collect())),
+    };
+
+    let matches = match matches {
+        Ok(m) => m,
+        Err(e) => {
+            return Ok(ToolResult::failure(
+                "query_data".to_string(),
+                e,
+                start.elapsed(),
+            ));
+        }
+    };
+
+    let output = serde_json::to_string_pretty(&matches)?;
+
+    if output.len() > context.max_output_size {
+        return Ok(ToolResult::failure(
+            "query_data".to_string(),
+            format!(
+                "Query result too large: {} bytes (max: {} bytes), narrow the query",
+                output.len(),
+                context.max_output_size
+            ),
+            start.elapsed(),
+        ));
+    }
+
+    Ok(ToolResult::success(
+        "query_data".to_string(),
+        format!("{} match(es)\n{}", matches.len(), output),
+        start.elapsed(),
+    ))
+}
+
+/// Parse JSON, YAML or TOML document into a JSON value
+fn parse_document(content: &str, format: DataFormat) -> std::result::Result<Value, String> {
+    match format {
+        DataFormat::Json => serde_json::from_str(content)
+            .map_err(|e| format!("Invalid JSON: {}", e)),
+        DataFormat::Yaml => serde_yaml::from_str(content)
+            .map_err(|e| format!("Invalid YAML: {}", e)),
+        DataFormat::Toml => toml::from_str(content)
+            .map_err(|e| format!("Invalid TOML: {}", e)),
+        DataFormat::Csv | DataFormat::Tsv => {
+            Err("Delimited files are queried with column filters".to_string())
+        }
+    }
+}
+
+/// Parse a JSONPath-like expression
+///
+/// Supported syntax: `$`, `.key`, `['key']`, `[0]`, `[-1]`, `[*]`, `.*`,
+/// `..key`, `..*`. The leading `$` is optional (`package.name` works).
+pub fn parse_path(expr: &str) -> std::result::Result<Vec<PathSegment>, String> {
+    let expr = expr.trim();
+    let mut rest = expr.strip_prefix('$').unwrap_or(expr);
+    let mut segments = Vec::new();
+
+    // Allow a bare leading key ("package.name")
+    if !rest.is_empty() && !rest.starts_with('.') && !rest.starts_with('[') {
+        let (name, tail) = split_name(rest);
+        segments.push(PathSegment::Key(name.to_string()));
+        rest = tail;
+    }
+
+    while !rest.is_empty() {
+        if let Some(tail) = rest.strip_prefix("..") {
+            if let Some(tail) = tail.strip_prefix('*') {
+                segments.push(PathSegment::RecursiveWildcard);
+                rest = tail;
+            } else {
+                let (name, tail) = split_name(tail);
+                if name.is_empty() {
+                    return Err(format!("Expected key after '..' in '{}'", expr));
+                }
+                segments.push(PathSegment::RecursiveKey(name.to_string()));
+                rest = tail;
+            }
+        } else if let Some(tail) = rest.strip_prefix('.') {
+            if let Some(tail) = tail.strip_prefix('*') {
+                segments.push(PathSegment::Wildcard);
+                rest = tail;
+            } else {
+                let (name, tail) = split_name(tail);
+                if name.is_empty() {
+                    return Err(format!("Expected key after '.' in '{}'", expr));
+                }
+                segments.push(PathSegment::Key(name.to_string()));
+                rest = tail;
+            }
+        } else if let Some(tail) = rest.strip_prefix('[') {
+            let end = tail
+                .find(']')
+                .ok_or_else(|| format!("Unclosed '[' in '{}'", expr))?;
+            let inner = tail[..end].trim();
+            rest = &tail[end + 1..];
+
+            let segment = if inner == "*" {
+                PathSegment::Wildcard
+            } else if let Some(key) = strip_quotes(inner) {
+                PathSegment::Key(key.to_string())
+            } else {
+                let index = inner
+                    .parse::<i64>()
+                    .map_err(|_| format!("Invalid index '[{}]' in '{}'", inner, expr))?;
+                PathSegment::Index(index)
+            };
+            segments.push(segment);
+        } else {
+            return Err(format!("Unexpected '{}' in '{}'", rest, expr));
+        }
+    }
+
+    Ok(segments)
+}
+
+/// Split a member name from the front of an expression
+fn split_name(s: &str) -> (&str, &str) {
+    let end = s.find(['.', '[']).unwrap_or(s.len());
+    (&s[..end], &s[end..])
+}
+
+/// Strip matching single or double quotes
+fn strip_quotes(s: &str) -> Option<&str> {
+    s.strip_prefix('\'')
+        .and_then(|s| s.strip_suffix('\''))
+        .or_else(|| s.strip_prefix('"').and_then(|s| s.strip_suffix('"')))
+}
+
+/// Evaluate a JSONPath-like expression against a document
+pub fn evaluate_path<'a>(doc: &'a Value, expr: &str) -> std::result::Result<Vec<&'a Value>, String> {
+    let segments = parse_path(expr)?;
+    let mut current = vec![doc];
+
+    for segment in &segments {
+        let mut next = Vec::new();
+
+        for value in current {
+            match segment {
+                PathSegment::Key(key) => {
+                    if let Some(child) = value.get(key.as_str()) {
+                        next.push(child);
+                    }
+                }
+                PathSegment::Index(index) => {
+                    if let Some(arr) = value.as_array() {
+                        let idx = if *index < 0 {
+                            arr.len() as i64 + index
+                        } else {
+                            *index
+                        };
+                        if idx >= 0 {
+                            if let Some(child) = arr.get(idx as usize) {
+                                next.push(child);
+                            }
+                        }
+                    }
+                }
+                PathSegment::Wildcard => next.extend(children(value)),
+                PathSegment::RecursiveKey(key) => {
+                    for node in descendants(value, true) {
+                        if let Some(child) = node.get(key.as_str()) {
+                            next.push(child);
+                        }
+                    }
+                }
+                PathSegment::RecursiveWildcard => next.extend(descendants(value, false)),
+            }
+        }
+
+        current = next;
+    }
+
+    Ok(current)
+}
+
+/// Direct children of an object or array
+fn children(value: &Value) -> Vec<&Value> {
+    match value {
+        Value::Object(map) => map.values().collect(),
+        Value::Array(arr) => arr.iter().collect(),
+        _ => Vec::new(),
+    }
+}
+
+/// All descendants in pre-order, optionally including the value itself
+fn descendants(value: &Value, include_self: bool) -> Vec<&Value> {
+    let mut out = Vec::new();
+    if include_self {
+        out.push(value);
+    }
+    for child in children(value) {
+        out.extend(descendants(child, true));
+    }
+    out
+}
+
+/// Comparison operators for column filters
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum FilterOp {
+    Eq,
+    Ne,
+    Gt,
+    Ge,
+    Lt,
+    Le,
+    Contains,
+}
+
+/// Column filter: `column<op>value`
+#[derive(Debug, Clone, PartialEq)]
+struct ColumnFilter {
+    column: String,
+    op: FilterOp,
+    value: String,
+}
+
+impl ColumnFilter {
+    /// Parse a single condition such as `status=failed` or `size>=1024`
+    fn parse(condition: &str) -> std::result::Result<Self, String> {
+        // Two-character operators first so ">=" is not read as ">"
+        const OPS: [(&str, FilterOp); 7] = [
+            (">=", FilterOp::Ge),
+            ("<=", FilterOp::Le),
+            ("!=", FilterOp::Ne),
+            ("=", FilterOp::Eq),
+            (">", FilterOp::Gt),
+            ("<", FilterOp::Lt),
+            ("~", FilterOp::Contains),
+        ];
+
+        for (token, op) in OPS {
+            if let Some(pos) = condition.find(token) {
+                let column = condition[..pos].trim();
+                let value = condition[pos + token.len()..].trim();
+                if column.is_empty() {
+                    break;
+                }
+                return Ok(Self {
+                    column: column.to_string(),
+                    op,
+                    value: strip_quotes(value).unwrap_or(value).to_string(),
+                });
+            }
+        }
+
+        Err(format!(
+            "Invalid filter '{}' (expected column=value, !=, >, >=, <, <= or ~ for contains)",
+            condition
+        ))
+    }
+
+    /// Check a cell against this filter (numeric when both sides parse)
+    fn matches(&self, cell: &str) -> bool {
+        if self.op == FilterOp::Contains {
+            return cell.contains(&self.value);
+        }
+
+        let ordering = match (cell.trim().parse::<f64>(), self.value.parse::<f64>()) {
+            (Ok(a), Ok(b)) => a.partial_cmp(&b),
+            _ => Some(cell.cmp(self.value.as_str())),
+        };
+
+        let Some(ordering) = ordering else {
+            return false;
+        };
+
+        match self.op {
+            FilterOp::Eq => ordering.is_eq(),
+            FilterOp::Ne => ordering.is_ne(),
+            FilterOp::Gt => ordering.is_gt(),
+            FilterOp::Ge => ordering.is_ge(),
+            FilterOp::Lt => ordering.is_lt(),
+            FilterOp::Le => ordering.is_le(),
+            FilterOp::Contains => unreachable!(),
+        }
+    }
+}
+
+/// Filter and project rows of a delimited file
+///
+/// `query` is a list of conditions joined by `&&` (empty matches all rows).
+/// Rows are returned as objects keyed by header, limited to `columns` if given.
+fn query_delimited(
+    content: &str,
+    delimiter: u8,
+    query: &str,
+    columns: &[String],
+    limit: usize,
+) -> std::result::Result<Vec<Value>, String> {
+    let filters = query
+        .split("&&")
+        .map(str::trim)
+        .filter(|c| !c.is_empty())
+        .map(ColumnFilter::parse)
+        .collect::<std::result::Result<Vec<_>, _>>()?;
+
+    let mut reader = csv::ReaderBuilder::new()
+        .delimiter(delimiter)
+        .flexible(true)
+        .from_reader(content.as_bytes());
+
+    let headers: Vec<String> = reader
+        .headers()
+        .map_err(|e| format!("Invalid CSV header: {}", e))?
+        .iter()
+        .map(String::from)
+        .collect();
+
+    let column_index = |name: &str| {
+        headers
+            .iter()
+            .position(|h| h == name)
+            .ok_or_else(|| format!("Unknown column '{}' (columns: {})", name, headers.join(", ")))
+    };
+
+    let filter_indices = filters
+        .iter()
+        .map(|f| column_index(&f.column))
+        .collect::<std::result::Result<Vec<_>, _>>()?;
+
+    let selected: Vec<usize> = if columns.is_empty() {
+        (0..headers.len()).collect()
+    } else {
+        columns
+            .iter()
+            .map(|c| column_index(c))
+            .collect::<std::result::Result<Vec<_>, _>>()?
+    };
+
+    let mut rows = Vec::new();
+    for record in reader.records() {
+        let record = record.map_err(|e| format!("Invalid CSV row: {}", e))?;
+
+        let matched = filters
+            .iter()
+            .zip(&filter_indices)
+            .all(|(filter, &idx)| filter.matches(record.get(idx).unwrap_or("")));
+
+        if !matched {
+            continue;
+        }
+
+        let row: serde_json::Map<String, Value> = selected
+            .iter()
+            .map(|&idx| {
+                (
+                    headers[idx].clone(),
+                    Value::String(record.get(idx).unwrap_or("").to_string()),
+                )
+            })
+            .collect();
+        rows.push(Value::Object(row));
+
+        if rows.len() >= limit {
+            break;
+        }
+    }
+
+    Ok(rows)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use tempfile::TempDir;
+
+    async fn setup_test_env() -> (TempDir, PathJail, ToolContext) {
+        let temp_dir = TempDir::new().unwrap();
+        let jail = PathJail::new(temp_dir.path()).unwrap();
+        let context = ToolContext::new(temp_dir.path().to_path_buf());
+        (temp_dir, jail, context)
+    }
+
+    #[test]
+    fn test_parse_path() {
+        assert_eq!(
+            parse_path("$.package.name").unwrap(),
+            vec![
+                PathSegment::Key("package".to_string()),
+                PathSegment::Key("name".to_string())
+            ]
+        );
+        assert_eq!(
+            parse_path("items[-1]['first name']").unwrap(),
+            vec![
+                PathSegment::Key("items".to_string()),
+                PathSegment::Index(-1),
+                PathSegment::Key("first name".to_string())
+            ]
+        );
+        assert_eq!(
+            parse_path("$..version").unwrap(),
+            vec![PathSegment::RecursiveKey("version".to_string())]
+        );
+        assert!(parse_path("$.items[abc]").is_err());
+        assert!(parse_path("$.items[0").is_err());
+    }
+
+    #[test]
+    fn test_evaluate_path() {
+        let doc = json!({
+            "items": [
+                {"name": "a", "tags": {"version": 1}},
+                {"name": "b", "tags": {"version": 2}}
+            ]
+        });
+
+        let names = evaluate_path(&doc, "$.items[*].name").unwrap();
+        assert_eq!(names, vec![&json!("a"), &json!("b")]);
+
+        let last = evaluate_path(&doc, "items[-1].name").unwrap();
+        assert_eq!(last, vec![&json!("b")]);
+
+        let versions = evaluate_path(&doc, "$..version").unwrap();
+        assert_eq!(versions, vec![&json!(1), &json!(2)]);
+
+        assert!(evaluate_path(&doc, "$.missing").unwrap().is_empty());
+        assert_eq!(evaluate_path(&doc, "$").unwrap(), vec![&doc]);
+    }
+
+    #[test]
+    fn test_column_filter() {
+        let filter = ColumnFilter::parse("size>=10").unwrap();
+        assert!(filter.matches("10"));
+        assert!(filter.matches("100"));
+        assert!(!filter.matches("9"));
+
+        let filter = ColumnFilter::parse("status != 'ok'").unwrap();
+        assert!(filter.matches("failed"));
+        assert!(!filter.matches("ok"));
+
+        assert!(ColumnFilter::parse("status").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_query_toml() {
+        let (temp, jail, context) = setup_test_env().await;
+        fs::write(
+            temp.path().join("Cargo.toml"),
+            "[package]\nname = \"demo\"\nversion = \"0.1.0\"\n\n[dependencies]\nserde = \"1.0\"\n",
+        )
+        .unwrap();
+
+        let result = query_data("Cargo.toml", "$.package.version", None, &[], DEFAULT_ROW_LIMIT, &context, &jail)
+            .await
+            .unwrap();
+
+        assert!(result.success);
+        assert!(result.output.starts_with("1 match(es)"));
+        assert!(result.output.contains("\"0.1.0\""));
+        assert!(!result.output.contains("serde"));
+    }
+
+    #[tokio::test]
+    async fn test_query_json_and_yaml() {
+        let (temp, jail, context) = setup_test_env().await;
+        fs::write(
+            temp.path().join("package.json"),
+            r#"{"name": "app", "scripts": {"test": "jest", "build": "tsc"}}"#,
+        )
+        .unwrap();
+        fs::write(temp.path().join("ci.yml"), "jobs:\n  test:\n    runs-on: ubuntu\n").unwrap();
+
+        let result = query_data("package.json", "scripts.test", None, &[], DEFAULT_ROW_LIMIT, &context, &jail)
+            .await
+            .unwrap();
+        assert!(result.success);
+        assert!(result.output.contains("\"jest\""));
+
+        let result = query_data("ci.yml", "$.jobs.test['runs-on']", None, &[], DEFAULT_ROW_LIMIT, &context, &jail)
+            .await
+            .unwrap();
+        assert!(result.success);
+        assert!(result.output.contains("\"ubuntu\""));
+    }
+
+    #[tokio::test]
+    async fn test_query_csv() {
+        let (temp, jail, context) = setup_test_env().await;
+        fs::write(
+            temp.path().join("results.csv"),
+            "name,status,duration\nalpha,ok,12\nbeta,failed,40\ngamma,failed,3\n",
+        )
+        .unwrap();
+
+        let columns = vec!["name".to_string()];
+        let result = query_data(
+            "results.csv",
+            "status=failed && duration>10",
+            None,
+            &columns,
+            DEFAULT_ROW_LIMIT,
+            &context,
+            &jail,
+        )
+        .await
+        .unwrap();
+
+        assert!(result.success);
+        assert!(result.output.starts_with("1 match(es)"));
+        assert!(result.output.contains("beta"));
+        assert!(!result.output.contains("duration"));
+
+        let result = query_data("results.csv", "owner=me", None, &[], DEFAULT_ROW_LIMIT, &context, &jail)
+            .await
+            .unwrap();
+        assert!(!result.success);
+        assert!(result.error.unwrap().contains("Unknown column"));
+    }
+
+    #[tokio::test]
+    async fn test_query_unknown_format() {
+        let (temp, jail, context) = setup_test_env().await;
+        fs::write(temp.path().join("data.bin"), "{}").unwrap();
+
+        let result = query_data("data.bin", "$", None, &[], DEFAULT_ROW_LIMIT, &context, &jail)
+            .await
+            .unwrap();
+        assert!(!result.success);
+
+        let result = query_data("data.bin", "$", Some("json"), &[], DEFAULT_ROW_LIMIT, &context, &jail)
+            .await
+            .unwrap();
+        assert!(result.success);
+    }
+
+    #[tokio::test]
+    async fn test_query_path_jail_security() {
+        let (_temp, jail, context) = setup_test_env().await;
+
+        let result = query_data("../../../etc/passwd", "$", Some("csv"), &[], DEFAULT_ROW_LIMIT, &context, &jail).await;
+        assert!(result.is_err());
+    }
+}
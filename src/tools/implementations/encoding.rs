@@ -0,0 +1,110 @@
+//! Content encoding for write_file
+//!
+//! `write_file` content is written exactly as given unless the model asks
+//! otherwise via the `encoding` arg:
+//! - `utf8`: write the text verbatim (escape-heavy files round-trip exactly)
+//! - `base64`: decode to bytes first (binary files)
+//!
+//! Without an explicit encoding, content that is detectably double-encoded
+//! (the whole file squashed onto one line as a JSON string body, e.g.
+//! `fn main() {\n    println!(\"hi\");\n}`) is decoded once. Anything else,
+//! such as a C format string or regex containing `\n`, is left alone.
+
+use crate::errors::{AgentError, Result};
+use base64::Engine;
+
+/// How `write_file` content is encoded
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ContentEncoding {
+    /// No encoding given: UTF-8, unless detectably double-encoded
+    Auto,
+
+    /// UTF-8 text, written verbatim
+    Utf8,
+
+    /// Base64-encoded bytes
+    Base64,
+}
+
+impl ContentEncoding {
+    /// Parse the `encoding` tool argument
+    pub fn from_arg(arg: Option<&str>) -> Result<Self> {
+        match arg.map(str::to_ascii_lowercase).as_deref() {
+            None => Ok(Self::Auto),
+            Some("utf8") | Some("utf-8") => Ok(Self::Utf8),
+            Some("base64") => Ok(Self::Base64),
+            Some(other) => Err(AgentError::Generic(format!(
+                "Unsupported encoding '{}' (expected utf8 or base64)",
+                other
+            ))),
+        }
+    }
+
+    /// Decode content to the bytes that should be written
+    pub fn decode(self, content: &str) -> Result<Vec<u8>> {
+        match self {
+            Self::Utf8 => Ok(content.as_bytes().to_vec()),
+            Self::Auto => Ok(unescape_if_double_encoded(content)
+                .unwrap_or_else(|| content.to_string())
+                .into_bytes()),
+            Self::Base64 => {
+                let compact: String = content.chars().filter(|c| !c.is_whitespace()).collect();
+                base64::engine::general_purpose::STANDARD
+                    .decode(compact)
+                    .map_err(|e| AgentError::Generic(format!("Content is not valid base64: {}", e)))
+            }
+        }
+    }
+}
+
+/// Decode content that was escaped twice, if it detectably was
+///
+/// Double-encoded content has no real line breaks (a trailing one aside) but
+/// contains `\n` escapes, and is a valid JSON string body: every quote is
+/// escaped and every backslash starts a JSON escape. Returns `None` for
+/// anything else.
+pub fn unescape_if_double_encoded(content: &str) -> Option<String> {
+    let body = content.strip_suffix('\n').unwrap_or(content);
+    if body.contains('\n') || !body.contains("\\n") {
+        return None;
+    }
+
+    let decoded: String = serde_json::from_str(&format!("\"{}\"", body)).ok()?;
+    let trailing = if body.len() < content.len() { "\n" } else { "" };
+    Some(format!("{}{}", decoded, trailing))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_only_double_encoded_content_is_unescaped() {
+        let squashed = r#"fn main() {\n    println!(\"hi\\n\");\n}\n"#;
+        assert_eq!(
+            unescape_if_double_encoded(squashed).unwrap(),
+            "fn main() {\n    println!(\"hi\\n\");\n}\n"
+        );
+
+        // Legitimate backslash sequences are kept
+        assert_eq!(unescape_if_double_encoded(r#"printf("%d\n", x);"#), None);
+        assert_eq!(unescape_if_double_encoded(r"^\d+\n$"), None);
+        assert_eq!(unescape_if_double_encoded("line one\nsplit(\"\\n\")\n"), None);
+        assert_eq!(unescape_if_double_encoded(r#"{"fixture": "a\nb"}"#), None);
+    }
+
+    #[test]
+    fn test_decode_by_encoding() {
+        assert_eq!(ContentEncoding::from_arg(None).unwrap(), ContentEncoding::Auto);
+        assert_eq!(ContentEncoding::from_arg(Some("UTF-8")).unwrap(), ContentEncoding::Utf8);
+        assert!(ContentEncoding::from_arg(Some("latin1")).is_err());
+
+        assert_eq!(ContentEncoding::Utf8.decode(r"a\nb").unwrap(), br"a\nb");
+        assert_eq!(ContentEncoding::Auto.decode(r"a\nb").unwrap(), b"a\nb");
+        assert_eq!(
+            ContentEncoding::Base64.decode("AAEC\n/w==").unwrap(),
+            vec![0u8, 1, 2, 255]
+        );
+        assert!(ContentEncoding::Base64.decode("not base64!").is_err());
+    }
+}
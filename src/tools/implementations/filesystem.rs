@@ -2,7 +2,7 @@
 //! 
 //! Implements secure filesystem operations:
 //! - list_dir: List directory contents
-//! - read_file: Read file contents with size limits
+//! - read_file: Read file contents with size limits (optionally a line range)
 //! - write_file: Write content with path validation
 
 use crate::errors::{AgentError, Result};
@@ -154,6 +154,26 @@ pub async fn read_file(
     path: &str,
     context: &ToolContext,
     jail: &PathJail,
+) -> Result<ToolResult> {
+    read_file_range(path, None, None, context, jail).await
+}
+
+/// Read file contents, limited to an inclusive 1-based line range
+///
+/// With no bounds the whole file is returned unchanged. With either bound
+/// set, output starts with a `[lines a-b of n]` header so the model can
+/// page through large files using line numbers from `outline`.
+///
+/// # Security
+/// - Path validated through jail
+/// - Size limit enforced (max 2MB by default)
+/// - Read-only operation (safe for parallelization)
+pub async fn read_file_range(
+    path: &str,
+    start_line: Option<usize>,
+    end_line: Option<usize>,
+    context: &ToolContext,
+    jail: &PathJail,
 ) -> Result<ToolResult> {
     let start = Instant::now();
 
@@ -212,9 +232,42 @@ pub async fn read_file(
         AgentError::Generic(format!("Failed to read file: {}", e))
     })?;
 
+    if start_line.is_none() && end_line.is_none() {
+        return Ok(ToolResult::success(
+            "read_file".to_string(),
+            content,
+            start.elapsed(),
+        ));
+    }
+
+    let lines: Vec<&str> = content.lines().collect();
+    let first = start_line.unwrap_or(1).max(1);
+    let last = end_line.unwrap_or(lines.len()).min(lines.len());
+
+    if first > last {
+        return Ok(ToolResult::failure(
+            "read_file".to_string(),
+            format!(
+                "Invalid line range {}-{} (file has {} lines)",
+                first,
+                end_line.unwrap_or(lines.len()),
+                lines.len()
+            ),
+            start.elapsed(),
+        ));
+    }
+
+    let output = format!(
+        "[lines {}-{} of {}]\n{}",
+        first,
+        last,
+        lines.len(),
+        lines[first - 1..last].join("\n")
+    );
+
     Ok(ToolResult::success(
         "read_file".to_string(),
-        content,
+        output,
         start.elapsed(),
     ))
 }
@@ -227,12 +280,13 @@ pub async fn read_file(
 /// - Not read-only (sequential execution required)
 pub async fn write_file(
     path: &str,
-    content: &str,
+    content: impl AsRef<[u8]>,
     append: bool,
     context: &ToolContext,
     jail: &PathJail,
 ) -> Result<ToolResult> {
     let start = Instant::now();
+    let content = content.as_ref();
 
     // Check content size
     if content.len() > context.max_output_size {
@@ -281,7 +335,11 @@ pub async fn write_file(
 
     // Write or append to file
     let result = if append {
-        fs::write(&verified_path, content)
+        fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&verified_path)
+            .and_then(|mut file| std::io::Write::write_all(&mut file, content))
     } else {
         fs::write(&verified_path, content)
     };
@@ -372,6 +430,23 @@ mod tests {
         assert_eq!(result.output, test_content);
     }
 
+    #[tokio::test]
+    async fn test_read_file_range() {
+        let (temp, jail, context) = setup_test_env().await;
+
+        fs::write(temp.path().join("lines.txt"), "one\ntwo\nthree\nfour\n").unwrap();
+
+        let result = read_file_range("lines.txt", Some(2), Some(3), &context, &jail).await.unwrap();
+        assert!(result.success);
+        assert_eq!(result.output, "[lines 2-3 of 4]\ntwo\nthree");
+
+        let result = read_file_range("lines.txt", Some(3), Some(100), &context, &jail).await.unwrap();
+        assert_eq!(result.output, "[lines 3-4 of 4]\nthree\nfour");
+
+        let result = read_file_range("lines.txt", Some(10), None, &context, &jail).await.unwrap();
+        assert!(!result.success);
+    }
+
     #[tokio::test]
     async fn test_read_file_nonexistent() {
         let (_temp, jail, context) = setup_test_env().await;
@@ -410,6 +485,17 @@ mod tests {
         assert_eq!(written, content);
     }
 
+    #[tokio::test]
+    async fn test_write_file_append() {
+        let (temp, jail, context) = setup_test_env().await;
+
+        write_file("log.txt", "one\n", false, &context, &jail).await.unwrap();
+        let result = write_file("log.txt", b"two\n", true, &context, &jail).await.unwrap();
+
+        assert!(result.success);
+        assert_eq!(fs::read_to_string(temp.path().join("log.txt")).unwrap(), "one\ntwo\n");
+    }
+
     #[tokio::test]
     async fn test_write_file_creates_parent_dirs() {
         let (_temp, jail, context) = setup_test_env().await;
@@ -1,8 +1,18 @@
 //! Tool implementations module
 
+pub mod archive;
+pub mod data;
+pub mod encoding;
 pub mod filesystem;
+pub mod outline;
 pub mod process;
+pub mod testing;
 
 // Re-export for convenience
-pub use filesystem::{list_dir, read_file, write_file};
+pub use archive::{archive_create, archive_extract, archive_list};
+pub use data::query_data;
+pub use encoding::ContentEncoding;
+pub use filesystem::{list_dir, read_file, read_file_range, write_file};
+pub use outline::outline;
 pub use process::{run_command, system_info, web_fetch};
+pub use testing::run_tests;
@@ -0,0 +1,580 @@
+//! Code outline tool implementation
+//!
+//! Implements a lightweight symbol map for source files:
+//! - outline: List functions, types, impls and classes with line numbers
+//!   for Rust, Python, TypeScript/JavaScript and Go
+//!
+//! Parsing is line-based (no full grammar), which is enough to give the
+//! model a map it can follow up with ranged `read_file` calls.
+
+use crate::errors::{AgentError, Result};
+use crate::tools::security::PathJail;
+use crate::tools::types::{ToolContext, ToolResult};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+
+/// Maximum files outlined when given a directory
+pub const MAX_OUTLINE_FILES: usize = 200;
+
+/// Directories skipped when walking a tree
+const SKIPPED_DIRS: &[&str] = &["target", "node_modules", "vendor", "dist", "build", "__pycache__"];
+
+/// Supported source languages
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Language {
+    Rust,
+    Python,
+    TypeScript,
+    Go,
+}
+
+impl Language {
+    /// Detect language from file extension
+    pub fn from_path(path: &Path) -> Option<Self> {
+        match path.extension()?.to_str()? {
+            "rs" => Some(Self::Rust),
+            "py" => Some(Self::Python),
+            "ts" | "tsx" | "js" | "jsx" | "mjs" => Some(Self::TypeScript),
+            "go" => Some(Self::Go),
+            _ => None,
+        }
+    }
+
+    /// Display name
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Rust => "rust",
+            Self::Python => "python",
+            Self::TypeScript => "typescript",
+            Self::Go => "go",
+        }
+    }
+}
+
+/// Symbol found in a source file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Symbol {
+    /// Symbol kind (fn, struct, class, impl, ...)
+    pub kind: &'static str,
+    /// Symbol name (for impls, the implemented type/trait)
+    pub name: String,
+    /// 1-based line number
+    pub line: usize,
+    /// Nesting level derived from indentation
+    pub depth: usize,
+}
+
+/// Outline a source file or directory
+///
+/// # Security
+/// - Path validated through jail
+/// - Output bounded by max_output_size
+/// - Read-only operation (safe for parallelization)
+pub async fn outline(
+    path: &str,
+    context: &ToolContext,
+    jail: &PathJail,
+) -> Result<ToolResult> {
+    let start = Instant::now();
+
+    let verified_path = jail.verify_and_canonicalize(path)?;
+
+    if !verified_path.exists() {
+        return Ok(ToolResult::failure(
+            "outline".to_string(),
+            format!("Path does not exist: {}", path),
+            start.elapsed(),
+        ));
+    }
+
+    let files = if verified_path.is_dir() {
+        let mut files = Vec::new();
+        collect_source_files(&verified_path, &mut files)?;
+        files.sort();
+        files
+    } else if Language::from_path(&verified_path).is_some() {
+        vec![verified_path.clone()]
+    } else {
+        return Ok(ToolResult::failure(
+            "outline".to_string(),
+            format!(
+                "Unsupported file type: {} (supported: .rs, .py, .ts, .tsx, .js, .jsx, .go)",
+                path
+            ),
+            start.elapsed(),
+        ));
+    };
+
+    if files.is_empty() {
+        return Ok(ToolResult::failure(
+            "outline".to_string(),
+            format!("No supported source files found in: {}", path),
+            start.elapsed(),
+        ));
+    }
+
+    let base = if verified_path.is_dir() {
+        verified_path.as_path()
+    } else {
+        verified_path.parent().unwrap_or(&verified_path)
+    };
+
+    let mut output = String::new();
+    let mut truncated = false;
+
+    for file in files.iter().take(MAX_OUTLINE_FILES) {
+        let Some(language) = Language::from_path(file) else {
+            continue;
+        };
+
+        let metadata = fs::metadata(file).map_err(|e| {
+            AgentError::Generic(format!("Failed to read metadata: {}", e))
+        })?;
+        if metadata.len() > context.max_output_size as u64 {
+            continue;
+        }
+
+        // Skip files that are not valid UTF-8 instead of failing the whole outline
+        let Ok(content) = fs::read_to_string(file) else {
+            continue;
+        };
+
+        let display = file.strip_prefix(base).unwrap_or(file).display();
+        let mut section = format!("{} ({})\n", display, language.name());
+        for symbol in outline_source(&content, language) {
+            section.push_str(&format!(
+                "{}{:>5}: {} {}\n",
+                "  ".repeat(symbol.depth + 1),
+                symbol.line,
+                symbol.kind,
+                symbol.name
+            ));
+        }
+
+        if output.len() + section.len() > context.max_output_size {
+            truncated = true;
+            break;
+        }
+        output.push_str(&section);
+    }
+
+    if truncated || files.len() > MAX_OUTLINE_FILES {
+        output.push_str("... (outline truncated, narrow the path)\n");
+    }
+
+    Ok(ToolResult::success(
+        "outline".to_string(),
+        output,
+        start.elapsed(),
+    ))
+}
+
+/// Recursively collect supported source files, skipping hidden and build dirs
+fn collect_source_files(dir: &Path, files: &mut Vec<PathBuf>) -> Result<()> {
+    let entries = fs::read_dir(dir).map_err(|e| {
+        AgentError::Generic(format!("Failed to read directory: {}", e))
+    })?;
+
+    for entry in entries.flatten() {
+        // Stop walking once enough files are collected to report truncation
+        if files.len() > MAX_OUTLINE_FILES {
+            return Ok(());
+        }
+
+        let path = entry.path();
+        let name = entry.file_name().to_string_lossy().to_string();
+        let Ok(file_type) = entry.file_type() else {
+            continue;
+        };
+
+        if file_type.is_dir() {
+            if !name.starts_with('.') && !SKIPPED_DIRS.contains(&name.as_str()) {
+                collect_source_files(&path, files)?;
+            }
+        } else if file_type.is_file() && Language::from_path(&path).is_some() {
+            files.push(path);
+        }
+    }
+
+    Ok(())
+}
+
+/// Extract symbols from source text
+pub fn outline_source(content: &str, language: Language) -> Vec<Symbol> {
+    let mut symbols = Vec::new();
+    let mut in_block_comment = false;
+
+    for (idx, raw_line) in content.lines().enumerate() {
+        let trimmed = raw_line.trim_start();
+
+        // Skip C-style block comments (Python has none)
+        if language != Language::Python {
+            if in_block_comment {
+                if trimmed.contains("*/") {
+                    in_block_comment = false;
+                }
+                continue;
+            }
+            if trimmed.starts_with("/*") {
+                in_block_comment = !trimmed.contains("*/");
+                continue;
+            }
+        }
+
+        if trimmed.is_empty()
+            || trimmed.starts_with("//")
+            || (language == Language::Python && trimmed.starts_with('#'))
+        {
+            continue;
+        }
+
+        let symbol = match language {
+            Language::Rust => parse_rust_line(trimmed),
+            Language::Python => parse_python_line(trimmed),
+            Language::TypeScript => parse_typescript_line(trimmed),
+            Language::Go => parse_go_line(trimmed),
+        };
+
+        if let Some((kind, name)) = symbol {
+            symbols.push(Symbol {
+                kind,
+                name,
+                line: idx + 1,
+                depth: indent_width(raw_line) / 4,
+            });
+        }
+    }
+
+    symbols
+}
+
+/// Leading whitespace width (tabs count as 4)
+fn indent_width(line: &str) -> usize {
+    line.chars()
+        .take_while(|c| c.is_whitespace())
+        .map(|c| if c == '\t' { 4 } else { 1 })
+        .sum()
+}
+
+/// Leading identifier of a string
+fn ident(s: &str) -> &str {
+    let end = s
+        .find(|c: char| !(c.is_alphanumeric() || c == '_' || c == '$'))
+        .unwrap_or(s.len());
+    &s[..end]
+}
+
+/// Strip any of the given prefix words (each followed by whitespace)
+fn strip_words<'a>(mut s: &'a str, words: &[&str]) -> &'a str {
+    loop {
+        let before = s;
+        for word in words {
+            if let Some(rest) = s.strip_prefix(word) {
+                if rest.starts_with(char::is_whitespace) {
+                    s = rest.trim_start();
+                }
+            }
+        }
+        if s == before {
+            return s;
+        }
+    }
+}
+
+/// Named symbol after a keyword, if the identifier is non-empty
+fn named(kind: &'static str, rest: &str) -> Option<(&'static str, String)> {
+    let name = ident(rest.trim_start());
+    if name.is_empty() {
+        None
+    } else {
+        Some((kind, name.to_string()))
+    }
+}
+
+fn parse_rust_line(line: &str) -> Option<(&'static str, String)> {
+    // Drop visibility: pub, pub(crate), pub(super), pub(in path)
+    let mut line = line;
+    if let Some(rest) = line.strip_prefix("pub(") {
+        line = rest.split_once(')').map(|(_, r)| r.trim_start())?;
+    }
+    let line = strip_words(line, &["pub", "default", "async", "const", "unsafe", "extern \"C\"", "extern"]);
+
+    if let Some(rest) = line.strip_prefix("macro_rules!") {
+        return named("macro", rest);
+    }
+
+    // impl blocks: keep the header up to the body/where clause ("Trait for Type<T>")
+    if let Some(rest) = line.strip_prefix("impl") {
+        if !rest.starts_with([' ', '<']) {
+            return None;
+        }
+        let header = rest.split(['{', ';']).next().unwrap_or(rest);
+        let header = header.split(" where").next().unwrap_or(header);
+        let target = skip_generics(header.trim()).trim();
+        return (!target.is_empty()).then(|| ("impl", target.to_string()));
+    }
+
+    let (keyword, rest) = line.split_once(char::is_whitespace)?;
+    match keyword {
+        "fn" => named("fn", rest),
+        "struct" => named("struct", rest),
+        "enum" => named("enum", rest),
+        "union" => named("union", rest),
+        "trait" => named("trait", rest),
+        "mod" => named("mod", rest),
+        "type" => named("type", rest),
+        _ => None,
+    }
+}
+
+/// Skip a leading `<...>` generic list
+fn skip_generics(s: &str) -> &str {
+    if !s.starts_with('<') {
+        return s;
+    }
+    let mut depth = 0;
+    for (i, c) in s.char_indices() {
+        match c {
+            '<' => depth += 1,
+            '>' => {
+                depth -= 1;
+                if depth == 0 {
+                    return &s[i + 1..];
+                }
+            }
+            _ => {}
+        }
+    }
+    s
+}
+
+fn parse_python_line(line: &str) -> Option<(&'static str, String)> {
+    let line = strip_words(line, &["async"]);
+    if let Some(rest) = line.strip_prefix("def ") {
+        return named("def", rest);
+    }
+    if let Some(rest) = line.strip_prefix("class ") {
+        return named("class", rest);
+    }
+    None
+}
+
+fn parse_typescript_line(line: &str) -> Option<(&'static str, String)> {
+    let line = strip_words(line, &["export", "default", "declare", "abstract", "async"]);
+
+    if let Some(rest) = line.strip_prefix("function") {
+        let rest = rest.strip_prefix('*').unwrap_or(rest);
+        return named("function", rest);
+    }
+    if let Some(rest) = line.strip_prefix("class ") {
+        return named("class", rest);
+    }
+    if let Some(rest) = line.strip_prefix("interface ") {
+        return named("interface", rest);
+    }
+    if let Some(rest) = line.strip_prefix("enum ") {
+        return named("enum", rest);
+    }
+    if let Some(rest) = line.strip_prefix("type ") {
+        if rest.contains('=') {
+            return named("type", rest);
+        }
+    }
+
+    // Arrow functions: const name = (...) => / const name = async (...) =>
+    for decl in ["const ", "let ", "var "] {
+        if let Some(rest) = line.strip_prefix(decl) {
+            let name = ident(rest);
+            let value = rest[name.len()..].split_once('=').map(|(_, v)| v.trim_start());
+            if !name.is_empty() && value.is_some_and(|v| v.contains("=>") || v.starts_with("function")) {
+                return Some(("function", name.to_string()));
+            }
+            return None;
+        }
+    }
+
+    // Class methods: [modifiers] name(args) {  (indented only)
+    let method = strip_words(line, &["public", "private", "protected", "static", "readonly", "override", "async", "get", "set"]);
+    let name = ident(method);
+    const NOT_METHODS: &[&str] = &["if", "for", "while", "switch", "catch", "return", "function", "constructor", "super", "new"];
+    if !name.is_empty()
+        && !NOT_METHODS.contains(&name)
+        && method[name.len()..].trim_start().starts_with(['(', '<'])
+        && line.trim_end().ends_with('{')
+    {
+        return Some(("method", name.to_string()));
+    }
+    if name == "constructor" && line.trim_end().ends_with('{') {
+        return Some(("method", name.to_string()));
+    }
+
+    None
+}
+
+fn parse_go_line(line: &str) -> Option<(&'static str, String)> {
+    if let Some(rest) = line.strip_prefix("func ") {
+        // Method receiver: func (r *Type) Name(
+        if let Some(receiver) = rest.strip_prefix('(') {
+            let (recv, after) = receiver.split_once(')')?;
+            let recv_type = recv.split_whitespace().last()?.trim_start_matches('*');
+            let name = ident(after.trim_start());
+            if name.is_empty() {
+                return None;
+            }
+            return Some(("method", format!("{}.{}", recv_type, name)));
+        }
+        return named("func", rest);
+    }
+
+    if let Some(rest) = line.strip_prefix("type ") {
+        let name = ident(rest);
+        if name.is_empty() {
+            return None;
+        }
+        let kind = match rest[name.len()..].split_whitespace().next() {
+            Some(t) if t.starts_with("struct") => "struct",
+            Some(t) if t.starts_with("interface") => "interface",
+            _ => "type",
+        };
+        return Some((kind, name.to_string()));
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn names(symbols: &[Symbol]) -> Vec<(&'static str, &str, usize)> {
+        symbols
+            .iter()
+            .map(|s| (s.kind, s.name.as_str(), s.line))
+            .collect()
+    }
+
+    #[test]
+    fn test_outline_rust() {
+        let src = "\
+use std::fmt;
+
+/// Doc
+pub struct Point {
+    x: i32,
+}
+
+impl<T: Clone> fmt::Display for Wrapper<T> where T: Send {
+    fn fmt(&self) {}
+}
+
+pub(crate) async fn run() {}
+// fn commented() {}
+/* fn hidden() {}
+*/
+macro_rules! square {}
+";
+        let symbols = outline_source(src, Language::Rust);
+        assert_eq!(
+            names(&symbols),
+            vec![
+                ("struct", "Point", 4),
+                ("impl", "fmt::Display for Wrapper<T>", 8),
+                ("fn", "fmt", 9),
+                ("fn", "run", 12),
+                ("macro", "square", 16),
+            ]
+        );
+        assert_eq!(symbols[2].depth, 1);
+    }
+
+    #[test]
+    fn test_outline_python() {
+        let src = "class Parser:\n    def parse(self):\n        pass\n\nasync def main():\n    # def not_this\n    pass\n";
+        let symbols = outline_source(src, Language::Python);
+        assert_eq!(
+            names(&symbols),
+            vec![("class", "Parser", 1), ("def", "parse", 2), ("def", "main", 5)]
+        );
+    }
+
+    #[test]
+    fn test_outline_typescript() {
+        let src = "\
+export interface Options {}
+export default class Server {
+  private async start(port: number): Promise<void> {
+    if (port) {
+    }
+  }
+}
+export const handler = async (req) => {
+};
+const LIMIT = 10;
+function helper() {}
+";
+        let symbols = outline_source(src, Language::TypeScript);
+        assert_eq!(
+            names(&symbols),
+            vec![
+                ("interface", "Options", 1),
+                ("class", "Server", 2),
+                ("method", "start", 3),
+                ("function", "handler", 8),
+                ("function", "helper", 11),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_outline_go() {
+        let src = "package main\n\ntype Server struct {\n}\n\nfunc (s *Server) Start() error {\n}\n\nfunc main() {\n}\n";
+        let symbols = outline_source(src, Language::Go);
+        assert_eq!(
+            names(&symbols),
+            vec![("struct", "Server", 3), ("method", "Server.Start", 6), ("func", "main", 9)]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_outline_directory() {
+        let temp = TempDir::new().unwrap();
+        let jail = PathJail::new(temp.path()).unwrap();
+        let context = ToolContext::new(temp.path().to_path_buf());
+
+        fs::create_dir_all(temp.path().join("src")).unwrap();
+        fs::create_dir_all(temp.path().join("target")).unwrap();
+        fs::write(temp.path().join("src/lib.rs"), "pub fn add() {}\n").unwrap();
+        fs::write(temp.path().join("target/gen.rs"), "fn generated() {}\n").unwrap();
+        fs::write(temp.path().join("README.md"), "# fn readme").unwrap();
+
+        let result = outline(".", &context, &jail).await.unwrap();
+        assert!(result.success);
+        assert!(result.output.contains("lib.rs (rust)"));
+        assert!(result.output.contains("1: fn add"));
+        assert!(!result.output.contains("generated"));
+        assert!(!result.output.contains("readme"));
+    }
+
+    #[tokio::test]
+    async fn test_outline_unsupported_file() {
+        let temp = TempDir::new().unwrap();
+        let jail = PathJail::new(temp.path()).unwrap();
+        let context = ToolContext::new(temp.path().to_path_buf());
+        fs::write(temp.path().join("notes.txt"), "fn nope").unwrap();
+
+        let result = outline("notes.txt", &context, &jail).await.unwrap();
+        assert!(!result.success);
+        assert!(result.error.unwrap().contains("Unsupported file type"));
+    }
+
+    #[tokio::test]
+    async fn test_outline_path_jail_security() {
+        let temp = TempDir::new().unwrap();
+        let jail = PathJail::new(temp.path()).unwrap();
+        let context = ToolContext::new(temp.path().to_path_buf());
+
+        assert!(outline("../../../etc", &context, &jail).await.is_err());
+    }
+}
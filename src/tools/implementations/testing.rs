@@ -0,0 +1,582 @@
+//! Test runner tool implementation
+//!
+//! Implements project-aware test execution:
+//! - run_tests: Detect the project type (Cargo, pytest, npm, go test),
+//!   run the suite or a filtered subset, and return a structured report
+//!
+//! The report is returned as JSON so both the model and `TaskValidator`
+//! can read pass/fail counts and failure messages without raw test logs.
+
+use crate::errors::Result;
+use crate::tools::security::PathJail;
+use crate::tools::types::{ToolContext, ToolResult};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use std::time::{Duration, Instant};
+use tokio::process::Command;
+use tokio::time::timeout;
+
+/// Maximum failures included in a report
+pub const MAX_REPORTED_FAILURES: usize = 20;
+
+/// Maximum lines kept per failure message
+const MAX_MESSAGE_LINES: usize = 12;
+
+/// Supported test frameworks
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TestFramework {
+    Cargo,
+    Pytest,
+    Npm,
+    Go,
+}
+
+impl TestFramework {
+    /// Detect framework from project marker files
+    pub fn detect(dir: &Path) -> Option<Self> {
+        if dir.join("Cargo.toml").is_file() {
+            Some(Self::Cargo)
+        } else if dir.join("go.mod").is_file() {
+            Some(Self::Go)
+        } else if dir.join("package.json").is_file() {
+            Some(Self::Npm)
+        } else if ["pyproject.toml", "setup.py", "setup.cfg", "pytest.ini", "tox.ini"]
+            .iter()
+            .any(|marker| dir.join(marker).is_file())
+        {
+            Some(Self::Pytest)
+        } else {
+            None
+        }
+    }
+
+    /// Parse explicit framework name
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_lowercase().as_str() {
+            "cargo" | "rust" => Some(Self::Cargo),
+            "pytest" | "python" => Some(Self::Pytest),
+            "npm" | "node" | "jest" => Some(Self::Npm),
+            "go" => Some(Self::Go),
+            _ => None,
+        }
+    }
+
+    /// Program and arguments for running tests with an optional filter
+    pub fn command(&self, filter: Option<&str>) -> (&'static str, Vec<String>) {
+        let mut args: Vec<String> = Vec::new();
+        let program = match self {
+            Self::Cargo => {
+                args.extend(["test", "--color", "never"].map(String::from));
+                args.extend(filter.map(String::from));
+                "cargo"
+            }
+            Self::Pytest => {
+                args.extend(["-q", "-rfE", "--color=no"].map(String::from));
+                if let Some(f) = filter {
+                    args.extend(["-k".to_string(), f.to_string()]);
+                }
+                "pytest"
+            }
+            Self::Npm => {
+                args.extend(["test", "--silent"].map(String::from));
+                if let Some(f) = filter {
+                    args.extend(["--".to_string(), f.to_string()]);
+                }
+                "npm"
+            }
+            Self::Go => {
+                args.extend(["test", "-v", "./..."].map(String::from));
+                if let Some(f) = filter {
+                    args.extend(["-run".to_string(), f.to_string()]);
+                }
+                "go"
+            }
+        };
+        (program, args)
+    }
+
+    /// Parse test output into a report
+    pub fn parse(&self, output: &str) -> TestReport {
+        let mut report = TestReport::new(*self);
+        match self {
+            Self::Cargo => parse_cargo(output, &mut report),
+            Self::Pytest => parse_pytest(output, &mut report),
+            Self::Npm => parse_npm(output, &mut report),
+            Self::Go => parse_go(output, &mut report),
+        }
+        report.failures.truncate(MAX_REPORTED_FAILURES);
+        report
+    }
+}
+
+/// Single failing test
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TestFailure {
+    /// Test name as reported by the framework
+    pub name: String,
+    /// Failure message (truncated)
+    pub message: String,
+}
+
+/// Structured test run result
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TestReport {
+    /// Framework that produced the report
+    pub framework: TestFramework,
+    /// Number of passing tests
+    pub passed: usize,
+    /// Number of failing tests
+    pub failed: usize,
+    /// Number of skipped/ignored tests
+    pub ignored: usize,
+    /// Failing tests with messages
+    pub failures: Vec<TestFailure>,
+    /// Process exit code
+    pub exit_code: i32,
+}
+
+impl TestReport {
+    /// Create empty report
+    pub fn new(framework: TestFramework) -> Self {
+        Self {
+            framework,
+            passed: 0,
+            failed: 0,
+            ignored: 0,
+            failures: Vec::new(),
+            exit_code: 0,
+        }
+    }
+
+    /// Whether the run succeeded with no failures
+    pub fn all_passed(&self) -> bool {
+        self.exit_code == 0 && self.failed == 0
+    }
+
+    /// Parse report from `run_tests` tool output
+    pub fn from_output(output: &str) -> Option<Self> {
+        serde_json::from_str(output).ok()
+    }
+}
+
+/// Run project tests
+///
+/// # Security
+/// - Project path validated through jail
+/// - Argv execution (no shell)
+/// - Timeout enforcement
+/// - Not read-only (tests may build artifacts or touch files)
+pub async fn run_tests(
+    path: &str,
+    filter: Option<&str>,
+    framework: Option<&str>,
+    timeout_seconds: u64,
+    _context: &ToolContext,
+    jail: &PathJail,
+) -> Result<ToolResult> {
+    let start = Instant::now();
+
+    let project_dir = jail.verify_and_canonicalize(path)?;
+
+    if !project_dir.is_dir() {
+        return Ok(ToolResult::failure(
+            "run_tests".to_string(),
+            format!("Not a directory: {}", path),
+            start.elapsed(),
+        ));
+    }
+
+    let detected = match framework {
+        Some(name) => TestFramework::from_name(name),
+        None => TestFramework::detect(&project_dir),
+    };
+
+    let Some(framework) = detected else {
+        return Ok(ToolResult::failure(
+            "run_tests".to_string(),
+            format!(
+                "Could not detect test framework in {} (expected Cargo.toml, go.mod, package.json or a Python project file)",
+                path
+            ),
+            start.elapsed(),
+        ));
+    };
+
+    let filter = filter.filter(|f| !f.trim().is_empty());
+    let (program, args) = framework.command(filter);
+
+    let mut cmd = Command::new(program);
+    cmd.args(&args).current_dir(&project_dir).kill_on_drop(true);
+
+    match timeout(Duration::from_secs(timeout_seconds), cmd.output()).await {
+        Ok(Ok(output)) => {
+            let combined = format!(
+                "{}\n{}",
+                String::from_utf8_lossy(&output.stdout),
+                String::from_utf8_lossy(&output.stderr)
+            );
+            let exit_code = output.status.code().unwrap_or(-1);
+
+            let mut report = framework.parse(&combined);
+            report.exit_code = exit_code;
+
+            // Non-zero exit with nothing parsed means the suite never ran (build error, etc.)
+            if exit_code != 0 && report.failed == 0 && report.failures.is_empty() {
+                report.failures.push(TestFailure {
+                    name: "<build>".to_string(),
+                    message: error_excerpt(&combined),
+                });
+            }
+
+            Ok(ToolResult::with_exit_code(
+                "run_tests".to_string(),
+                serde_json::to_string_pretty(&report)?,
+                exit_code,
+                start.elapsed(),
+            ))
+        }
+        Ok(Err(e)) => Ok(ToolResult::failure(
+            "run_tests".to_string(),
+            format!("Failed to run {}: {}", program, e),
+            start.elapsed(),
+        )),
+        Err(_) => Ok(ToolResult::failure(
+            "run_tests".to_string(),
+            format!("Tests timed out after {}s", timeout_seconds),
+            start.elapsed(),
+        )),
+    }
+}
+
+/// Error lines from output, falling back to the tail
+fn error_excerpt(output: &str) -> String {
+    let errors: Vec<&str> = output
+        .lines()
+        .filter(|l| {
+            let l = l.trim_start().to_lowercase();
+            l.starts_with("error") || l.contains("error:")
+        })
+        .take(MAX_MESSAGE_LINES)
+        .collect();
+
+    if !errors.is_empty() {
+        return errors.join("\n");
+    }
+
+    let lines: Vec<&str> = output.lines().filter(|l| !l.trim().is_empty()).collect();
+    lines[lines.len().saturating_sub(MAX_MESSAGE_LINES)..].join("\n")
+}
+
+/// Join message lines, trimmed and truncated
+fn message_from(lines: &[&str]) -> String {
+    lines
+        .iter()
+        .map(|l| l.trim())
+        .filter(|l| !l.is_empty())
+        .take(MAX_MESSAGE_LINES)
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Extract the number before a word ("3 passed" -> 3)
+fn count_before(text: &str, words: &[&str]) -> usize {
+    let tokens: Vec<&str> = text
+        .split(|c: char| c.is_whitespace() || c == ',' || c == ';')
+        .filter(|t| !t.is_empty())
+        .collect();
+
+    tokens
+        .windows(2)
+        .filter(|w| words.contains(&w[1].trim_end_matches('.')))
+        .filter_map(|w| w[0].parse::<usize>().ok())
+        .sum()
+}
+
+/// Parse `cargo test` output
+fn parse_cargo(output: &str, report: &mut TestReport) {
+    let lines: Vec<&str> = output.lines().collect();
+
+    for line in &lines {
+        if let Some(summary) = line.trim().strip_prefix("test result:") {
+            report.passed += count_before(summary, &["passed"]);
+            report.failed += count_before(summary, &["failed"]);
+            report.ignored += count_before(summary, &["ignored"]);
+        }
+    }
+
+    // Messages come from "---- name stdout ----" sections
+    let mut i = 0;
+    while i < lines.len() {
+        let line = lines[i].trim();
+        if let Some(name) = line
+            .strip_prefix("---- ")
+            .and_then(|l| l.strip_suffix(" stdout ----"))
+        {
+            let body_start = i + 1;
+            let mut end = body_start;
+            while end < lines.len() {
+                let next = lines[end].trim();
+                if next.starts_with("---- ") || next == "failures:" || next.starts_with("test result:") {
+                    break;
+                }
+                end += 1;
+            }
+            report.failures.push(TestFailure {
+                name: name.to_string(),
+                message: message_from(&lines[body_start..end]),
+            });
+            i = end;
+        } else {
+            i += 1;
+        }
+    }
+
+    // Failed tests without captured output
+    for line in &lines {
+        if let Some(name) = line
+            .trim()
+            .strip_prefix("test ")
+            .and_then(|l| l.strip_suffix(" ... FAILED"))
+        {
+            if !report.failures.iter().any(|f| f.name == name) {
+                report.failures.push(TestFailure {
+                    name: name.to_string(),
+                    message: String::new(),
+                });
+            }
+        }
+    }
+}
+
+/// Parse pytest output (`-q -rfE` short summary)
+fn parse_pytest(output: &str, report: &mut TestReport) {
+    for line in output.lines() {
+        let trimmed = line.trim().trim_matches('=').trim();
+
+        if let Some(rest) = trimmed
+            .strip_prefix("FAILED ")
+            .or_else(|| trimmed.strip_prefix("ERROR "))
+        {
+            let (name, message) = rest.split_once(" - ").unwrap_or((rest, ""));
+            report.failures.push(TestFailure {
+                name: name.trim().to_string(),
+                message: message.trim().to_string(),
+            });
+            continue;
+        }
+
+        // Summary: "1 failed, 2 passed, 1 skipped in 0.12s"
+        let is_summary = trimmed.contains(" in ")
+            && (trimmed.contains(" passed") || trimmed.contains(" failed") || trimmed.contains(" error"));
+        if is_summary {
+            report.passed = count_before(trimmed, &["passed"]);
+            report.failed = count_before(trimmed, &["failed", "error", "errors"]);
+            report.ignored = count_before(trimmed, &["skipped", "xfailed", "deselected"]);
+        }
+    }
+}
+
+/// Parse npm test output (Jest or Mocha)
+fn parse_npm(output: &str, report: &mut TestReport) {
+    let lines: Vec<&str> = output.lines().collect();
+
+    for (idx, line) in lines.iter().enumerate() {
+        let trimmed = line.trim();
+
+        // Jest: "Tests:       1 failed, 2 passed, 3 total"
+        if let Some(summary) = trimmed.strip_prefix("Tests:") {
+            report.passed = count_before(summary, &["passed"]);
+            report.failed = count_before(summary, &["failed"]);
+            report.ignored = count_before(summary, &["skipped", "todo"]);
+        }
+
+        // Mocha: "3 passing", "1 failing", "2 pending"
+        if trimmed.ends_with("passing") || trimmed.contains("passing (") {
+            report.passed = count_before(trimmed, &["passing"]);
+        } else if trimmed.ends_with("failing") {
+            report.failed = count_before(trimmed, &["failing"]);
+        } else if trimmed.ends_with("pending") {
+            report.ignored = count_before(trimmed, &["pending"]);
+        }
+
+        // Jest failure headers: "● Suite › test name"
+        if let Some(name) = trimmed.strip_prefix("● ") {
+            if name.starts_with("Test suite failed") {
+                continue;
+            }
+            let body: Vec<&str> = lines[idx + 1..]
+                .iter()
+                .take_while(|l| !l.trim().starts_with("● "))
+                .copied()
+                .collect();
+            report.failures.push(TestFailure {
+                name: name.to_string(),
+                message: message_from(&body),
+            });
+        }
+    }
+}
+
+/// Parse `go test -v` output
+fn parse_go(output: &str, report: &mut TestReport) {
+    let lines: Vec<&str> = output.lines().collect();
+
+    for (idx, line) in lines.iter().enumerate() {
+        let trimmed = line.trim();
+
+        if trimmed.starts_with("--- PASS:") {
+            report.passed += 1;
+        } else if trimmed.starts_with("--- SKIP:") {
+            report.ignored += 1;
+        } else if let Some(rest) = trimmed.strip_prefix("--- FAIL:") {
+            report.failed += 1;
+            let name = rest.split_whitespace().next().unwrap_or(rest).to_string();
+
+            // With -v, t.Log output streams before the FAIL marker; without it, after
+            let is_message = |l: &&&str| l.starts_with("    ") && !l.trim().starts_with("---");
+            let before = lines[..idx].iter().rev().take_while(is_message).count();
+            let body: Vec<&str> = lines[idx - before..idx]
+                .iter()
+                .chain(lines[idx + 1..].iter().take_while(is_message))
+                .copied()
+                .collect();
+            report.failures.push(TestFailure {
+                name,
+                message: message_from(&body),
+            });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_detect_framework() {
+        let temp = TempDir::new().unwrap();
+        assert_eq!(TestFramework::detect(temp.path()), None);
+
+        fs::write(temp.path().join("pyproject.toml"), "").unwrap();
+        assert_eq!(TestFramework::detect(temp.path()), Some(TestFramework::Pytest));
+
+        fs::write(temp.path().join("Cargo.toml"), "").unwrap();
+        assert_eq!(TestFramework::detect(temp.path()), Some(TestFramework::Cargo));
+    }
+
+    #[test]
+    fn test_command_with_filter() {
+        let (program, args) = TestFramework::Cargo.command(Some("parser"));
+        assert_eq!(program, "cargo");
+        assert_eq!(args, vec!["test", "--color", "never", "parser"]);
+
+        let (program, args) = TestFramework::Go.command(Some("TestParse"));
+        assert_eq!(program, "go");
+        assert!(args.ends_with(&["-run".to_string(), "TestParse".to_string()]));
+    }
+
+    #[test]
+    fn test_parse_cargo() {
+        let output = "\
+running 3 tests
+test parser::tests::ok ... ok
+test parser::tests::bad ... FAILED
+test parser::tests::skip ... ignored
+
+failures:
+
+---- parser::tests::bad stdout ----
+thread 'parser::tests::bad' panicked at src/parser.rs:10:5:
+assertion `left == right` failed
+  left: 1
+ right: 2
+
+failures:
+    parser::tests::bad
+
+test result: FAILED. 1 passed; 1 failed; 1 ignored; 0 measured; 0 filtered out
+";
+        let report = TestFramework::Cargo.parse(output);
+        assert_eq!((report.passed, report.failed, report.ignored), (1, 1, 1));
+        assert_eq!(report.failures.len(), 1);
+        assert_eq!(report.failures[0].name, "parser::tests::bad");
+        assert!(report.failures[0].message.contains("left: 1"));
+    }
+
+    #[test]
+    fn test_parse_pytest() {
+        let output = "\
+..F.s
+=========================== short test summary info ============================
+FAILED tests/test_math.py::test_div - ZeroDivisionError: division by zero
+1 failed, 3 passed, 1 skipped in 0.12s
+";
+        let report = TestFramework::Pytest.parse(output);
+        assert_eq!((report.passed, report.failed, report.ignored), (3, 1, 1));
+        assert_eq!(report.failures[0].name, "tests/test_math.py::test_div");
+        assert_eq!(report.failures[0].message, "ZeroDivisionError: division by zero");
+    }
+
+    #[test]
+    fn test_parse_jest() {
+        let output = "\
+  ● math › divides
+
+    expect(received).toBe(expected)
+
+    Expected: 2
+    Received: 3
+
+Tests:       1 failed, 4 passed, 5 total
+";
+        let report = TestFramework::Npm.parse(output);
+        assert_eq!((report.passed, report.failed), (4, 1));
+        assert_eq!(report.failures[0].name, "math › divides");
+        assert!(report.failures[0].message.contains("Expected: 2"));
+    }
+
+    #[test]
+    fn test_parse_go() {
+        let output = "\
+=== RUN   TestAdd
+--- PASS: TestAdd (0.00s)
+=== RUN   TestSub
+    math_test.go:14: expected 1, got 2
+--- FAIL: TestSub (0.00s)
+    math_test.go:20: cleanup failed
+FAIL
+";
+        let report = TestFramework::Go.parse(output);
+        assert_eq!((report.passed, report.failed), (1, 1));
+        assert_eq!(report.failures[0].name, "TestSub");
+        assert_eq!(
+            report.failures[0].message,
+            "math_test.go:14: expected 1, got 2\nmath_test.go:20: cleanup failed"
+        );
+    }
+
+    #[test]
+    fn test_report_round_trip() {
+        let mut report = TestReport::new(TestFramework::Cargo);
+        report.passed = 2;
+        assert!(report.all_passed());
+
+        let json = serde_json::to_string_pretty(&report).unwrap();
+        assert_eq!(TestReport::from_output(&json), Some(report));
+        assert_eq!(TestReport::from_output("not json"), None);
+    }
+
+    #[tokio::test]
+    async fn test_run_tests_undetected_project() {
+        let temp = TempDir::new().unwrap();
+        let jail = PathJail::new(temp.path()).unwrap();
+        let context = ToolContext::new(temp.path().to_path_buf());
+
+        let result = run_tests(".", None, None, 60, &context, &jail).await.unwrap();
+        assert!(!result.success);
+        assert!(result.error.unwrap().contains("Could not detect"));
+    }
+}
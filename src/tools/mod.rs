@@ -6,19 +6,25 @@
 //! - Parallel executor (4 concurrent operations)
 //! - Retry manager (exponential backoff)
 //! - Tool runtime coordinator
+//! - Tool profiles (online gating, per-project disabling)
+//! - Argument validation against each tool's JSON Schema
 
 pub mod types;
 pub mod registry;
+pub mod schema;
 pub mod security;
 pub mod retry;
 pub mod executor;
 pub mod runtime;
+pub mod profile;
 pub mod implementations;
 
 // Re-export commonly used types
 pub use types::{ToolResult, ToolContext, ToolSchema, ToolStats};
 pub use registry::ToolRegistry;
+pub use schema::validate_args;
 pub use security::PathJail;
 pub use retry::RetryManager;
 pub use executor::ParallelExecutor;
 pub use runtime::ToolRuntime;
+pub use profile::ToolProfile;
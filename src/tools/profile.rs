@@ -0,0 +1,195 @@
+//! Tool profile for registry construction
+//!
+//! Decides which tools are exposed to the model for a given run:
+//! - Network tools gated behind online mode (--online / online_enabled)
+//! - Tools disabled globally in config ([tools] disabled = [...])
+//! - Tools disabled per project (.ollamabuddy/tools.toml)
+
+use crate::cli::config::ToolsConfig;
+use crate::errors::{AgentError, Result};
+use serde::Deserialize;
+use std::collections::BTreeSet;
+use std::path::Path;
+
+/// Tools that require network access
+pub const NETWORK_TOOLS: &[&str] = &["web_fetch"];
+
+/// Project-level tool settings file (relative to working directory)
+pub const PROJECT_TOOLS_FILE: &str = ".ollamabuddy/tools.toml";
+
+/// Project-level tool settings
+#[derive(Debug, Clone, Default, Deserialize)]
+struct ProjectToolsFile {
+    /// Tools disabled for this project
+    #[serde(default)]
+    disabled: Vec<String>,
+}
+
+/// Tool profile controlling which tools are registered
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ToolProfile {
+    /// Whether network tools are allowed
+    online: bool,
+
+    /// Explicitly disabled tool names
+    disabled: BTreeSet<String>,
+}
+
+impl ToolProfile {
+    /// Create offline profile with no disabled tools
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create profile with every tool enabled (including network tools)
+    pub fn all_tools() -> Self {
+        Self {
+            online: true,
+            disabled: BTreeSet::new(),
+        }
+    }
+
+    /// Build profile from tools configuration
+    pub fn from_config(config: &ToolsConfig) -> Self {
+        Self {
+            online: config.online_enabled,
+            disabled: config.disabled.iter().cloned().collect(),
+        }
+    }
+
+    /// Enable or disable network tools
+    pub fn with_online(mut self, online: bool) -> Self {
+        self.online = online;
+        self
+    }
+
+    /// Disable a tool by name
+    pub fn disable(mut self, tool: impl Into<String>) -> Self {
+        self.disabled.insert(tool.into());
+        self
+    }
+
+    /// Merge project-level settings from `<dir>/.ollamabuddy/tools.toml`
+    ///
+    /// Missing file is not an error; a malformed file is.
+    pub fn with_project_overrides(mut self, project_dir: impl AsRef<Path>) -> Result<Self> {
+        let path = project_dir.as_ref().join(PROJECT_TOOLS_FILE);
+        if !path.exists() {
+            return Ok(self);
+        }
+
+        let contents = std::fs::read_to_string(&path).map_err(|e| {
+            AgentError::ConfigError(format!("Failed to read {}: {}", path.display(), e))
+        })?;
+
+        let project: ProjectToolsFile = toml::from_str(&contents).map_err(|e| {
+            AgentError::ConfigError(format!("Failed to parse {}: {}", path.display(), e))
+        })?;
+
+        self.disabled.extend(project.disabled);
+        Ok(self)
+    }
+
+    /// Check whether network tools are allowed
+    pub fn is_online(&self) -> bool {
+        self.online
+    }
+
+    /// Check whether a tool is enabled under this profile
+    pub fn is_enabled(&self, tool: &str) -> bool {
+        self.disabled_reason(tool).is_none()
+    }
+
+    /// Explain why a tool is disabled, or None if it is enabled
+    pub fn disabled_reason(&self, tool: &str) -> Option<String> {
+        if self.disabled.contains(tool) {
+            return Some(format!("Tool disabled by configuration: {}", tool));
+        }
+
+        if !self.online && NETWORK_TOOLS.contains(&tool) {
+            return Some(format!(
+                "Tool requires online mode: {} (run with --online to enable)",
+                tool
+            ));
+        }
+
+        None
+    }
+
+    /// Get explicitly disabled tool names
+    pub fn disabled_tools(&self) -> Vec<String> {
+        self.disabled.iter().cloned().collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_default_profile_is_offline() {
+        let profile = ToolProfile::new();
+        assert!(!profile.is_online());
+        assert!(!profile.is_enabled("web_fetch"));
+        assert!(profile.is_enabled("read_file"));
+    }
+
+    #[test]
+    fn test_online_enables_network_tools() {
+        let profile = ToolProfile::new().with_online(true);
+        assert!(profile.is_enabled("web_fetch"));
+    }
+
+    #[test]
+    fn test_disable_tool() {
+        let profile = ToolProfile::all_tools().disable("run_command");
+        assert!(!profile.is_enabled("run_command"));
+        assert!(profile.disabled_reason("run_command").unwrap().contains("configuration"));
+        assert!(profile.is_enabled("web_fetch"));
+    }
+
+    #[test]
+    fn test_from_config() {
+        let config = ToolsConfig {
+            online_enabled: true,
+            disabled: vec!["write_file".to_string()],
+            ..Default::default()
+        };
+
+        let profile = ToolProfile::from_config(&config);
+        assert!(profile.is_online());
+        assert!(!profile.is_enabled("write_file"));
+    }
+
+    #[test]
+    fn test_project_overrides() {
+        let temp = TempDir::new().unwrap();
+        std::fs::create_dir_all(temp.path().join(".ollamabuddy")).unwrap();
+        std::fs::write(
+            temp.path().join(PROJECT_TOOLS_FILE),
+            "disabled = [\"run_command\"]\n",
+        )
+        .unwrap();
+
+        let profile = ToolProfile::new().with_project_overrides(temp.path()).unwrap();
+        assert!(!profile.is_enabled("run_command"));
+        assert_eq!(profile.disabled_tools(), vec!["run_command".to_string()]);
+    }
+
+    #[test]
+    fn test_project_overrides_missing_file() {
+        let temp = TempDir::new().unwrap();
+        let profile = ToolProfile::new().with_project_overrides(temp.path()).unwrap();
+        assert_eq!(profile, ToolProfile::new());
+    }
+
+    #[test]
+    fn test_project_overrides_malformed_file() {
+        let temp = TempDir::new().unwrap();
+        std::fs::create_dir_all(temp.path().join(".ollamabuddy")).unwrap();
+        std::fs::write(temp.path().join(PROJECT_TOOLS_FILE), "disabled = 3").unwrap();
+
+        assert!(ToolProfile::new().with_project_overrides(temp.path()).is_err());
+    }
+}
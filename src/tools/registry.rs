@@ -10,6 +10,7 @@
 //! - system_info: Get system information
 //! - web_fetch: Fetch web content
 
+use crate::tools::profile::ToolProfile;
 use crate::tools::types::ToolSchema;
 use serde_json::json;
 use std::collections::HashMap;
@@ -22,8 +23,16 @@ pub struct ToolRegistry {
 }
 
 impl ToolRegistry {
-    /// Create new tool registry with all tools
+    /// Create new tool registry with offline tools
+    ///
+    /// Network tools are only registered through `with_profile` with an
+    /// online profile.
     pub fn new() -> Self {
+        Self::with_profile(&ToolProfile::new())
+    }
+
+    /// Create tool registry containing only tools enabled by the profile
+    pub fn with_profile(profile: &ToolProfile) -> Self {
         let mut registry = Self {
             tools: HashMap::new(),
         };
@@ -35,6 +44,12 @@ impl ToolRegistry {
         registry.register_run_command();
         registry.register_system_info();
         registry.register_web_fetch();
+        registry.register_query_data();
+        registry.register_outline();
+        registry.register_run_tests();
+        registry.register_archive_tools();
+
+        registry.tools.retain(|name, _| profile.is_enabled(name));
 
         registry
     }
@@ -43,7 +58,7 @@ impl ToolRegistry {
     fn register_list_dir(&mut self) {
         let schema = ToolSchema::new(
             "list_dir",
-            "List contents of a directory",
+            "List files and directories",
             json!({
                 "type": "object",
                 "properties": {
@@ -68,13 +83,23 @@ impl ToolRegistry {
     fn register_read_file(&mut self) {
         let schema = ToolSchema::new(
             "read_file",
-            "Read contents of a file",
+            "Read contents of a text file",
             json!({
                 "type": "object",
                 "properties": {
                     "path": {
                         "type": "string",
                         "description": "File path to read (relative to working directory)"
+                    },
+                    "start_line": {
+                        "type": "integer",
+                        "description": "First line to read (1-based, inclusive)",
+                        "minimum": 1
+                    },
+                    "end_line": {
+                        "type": "integer",
+                        "description": "Last line to read (inclusive)",
+                        "minimum": 1
                     }
                 },
                 "required": ["path"]
@@ -88,7 +113,7 @@ impl ToolRegistry {
     fn register_write_file(&mut self) {
         let schema = ToolSchema::new(
             "write_file",
-            "Write content to a file",
+            "Write or append content to a file",
             json!({
                 "type": "object",
                 "properties": {
@@ -104,6 +129,11 @@ impl ToolRegistry {
                         "type": "boolean",
                         "description": "Whether to append to file (default: overwrite)",
                         "default": false
+                    },
+                    "encoding": {
+                        "type": "string",
+                        "description": "utf8 writes content exactly; base64 decodes it first (binary files)",
+                        "enum": ["utf8", "base64"]
                     }
                 },
                 "required": ["path", "content"]
@@ -117,7 +147,7 @@ impl ToolRegistry {
     fn register_run_command(&mut self) {
         let schema = ToolSchema::new(
             "run_command",
-            "Execute a system command",
+            "Execute a system command (supports shell pipes/redirects)",
             json!({
                 "type": "object",
                 "properties": {
@@ -201,6 +231,166 @@ impl ToolRegistry {
         self.tools.insert("web_fetch".to_string(), schema);
     }
 
+    /// Register query_data tool
+    fn register_query_data(&mut self) {
+        let schema = ToolSchema::new(
+            "query_data",
+            "Query a JSON, YAML, TOML or CSV file and return only the matching values",
+            json!({
+                "type": "object",
+                "properties": {
+                    "path": {
+                        "type": "string",
+                        "description": "Path to the data file"
+                    },
+                    "query": {
+                        "type": "string",
+                        "description": "Path expression like $.package.version or $.items[*].name; for CSV, column filters like status=failed && duration>10 (empty matches all rows)"
+                    },
+                    "format": {
+                        "type": "string",
+                        "enum": ["json", "yaml", "toml", "csv", "tsv"],
+                        "description": "Data format (detected from extension if omitted)"
+                    },
+                    "columns": {
+                        "type": "array",
+                        "items": {"type": "string"},
+                        "description": "CSV columns to return (all if omitted)"
+                    },
+                    "limit": {
+                        "type": "integer",
+                        "description": "Maximum CSV rows to return",
+                        "default": 100,
+                        "minimum": 1
+                    }
+                },
+                "required": ["path", "query"]
+            }),
+            true, // Read-only
+        );
+        self.tools.insert("query_data".to_string(), schema);
+    }
+
+    /// Register outline tool
+    fn register_outline(&mut self) {
+        let schema = ToolSchema::new(
+            "outline",
+            "List functions, types, impls and classes with line numbers in Rust, Python, TypeScript/JavaScript or Go sources",
+            json!({
+                "type": "object",
+                "properties": {
+                    "path": {
+                        "type": "string",
+                        "description": "Source file or directory to outline",
+                        "default": "."
+                    }
+                }
+            }),
+            true, // Read-only
+        );
+        self.tools.insert("outline".to_string(), schema);
+    }
+
+    /// Register run_tests tool
+    fn register_run_tests(&mut self) {
+        let schema = ToolSchema::new(
+            "run_tests",
+            "Run the project's tests (Cargo, pytest, npm or go test) and return pass/fail counts with failure messages",
+            json!({
+                "type": "object",
+                "properties": {
+                    "path": {
+                        "type": "string",
+                        "description": "Project directory",
+                        "default": "."
+                    },
+                    "filter": {
+                        "type": "string",
+                        "description": "Only run tests matching this name/pattern"
+                    },
+                    "framework": {
+                        "type": "string",
+                        "enum": ["cargo", "pytest", "npm", "go"],
+                        "description": "Test framework (detected from project files if omitted)"
+                    },
+                    "timeout_seconds": {
+                        "type": "integer",
+                        "description": "Timeout in seconds",
+                        "default": 300,
+                        "minimum": 1,
+                        "maximum": 1800
+                    }
+                }
+            }),
+            false, // Not read-only (tests may build or modify files)
+        );
+        self.tools.insert("run_tests".to_string(), schema);
+    }
+
+    /// Register archive_list, archive_extract and archive_create tools
+    fn register_archive_tools(&mut self) {
+        let list = ToolSchema::new(
+            "archive_list",
+            "List entries of a .tar, .tar.gz/.tgz or .zip archive",
+            json!({
+                "type": "object",
+                "properties": {
+                    "path": {
+                        "type": "string",
+                        "description": "Archive path"
+                    }
+                },
+                "required": ["path"]
+            }),
+            true, // Read-only
+        );
+        self.tools.insert("archive_list".to_string(), list);
+
+        let extract = ToolSchema::new(
+            "archive_extract",
+            "Extract a .tar, .tar.gz/.tgz or .zip archive into a directory",
+            json!({
+                "type": "object",
+                "properties": {
+                    "path": {
+                        "type": "string",
+                        "description": "Archive path"
+                    },
+                    "destination": {
+                        "type": "string",
+                        "description": "Directory to extract into (created if missing)",
+                        "default": "."
+                    }
+                },
+                "required": ["path"]
+            }),
+            false, // Not read-only (writes files)
+        );
+        self.tools.insert("archive_extract".to_string(), extract);
+
+        let create = ToolSchema::new(
+            "archive_create",
+            "Create a .tar, .tar.gz/.tgz or .zip archive from files and directories",
+            json!({
+                "type": "object",
+                "properties": {
+                    "path": {
+                        "type": "string",
+                        "description": "Archive to create (format from extension)"
+                    },
+                    "sources": {
+                        "type": "array",
+                        "items": {"type": "string"},
+                        "description": "Files and directories to include"
+                    }
+                },
+                "required": ["path", "sources"]
+            }),
+            false, // Not read-only (writes files)
+        );
+        self.tools.insert("archive_create".to_string(), create);
+    }
+
     /// Get tool schema by name
     pub fn get(&self, name: &str) -> Option<&ToolSchema> {
         self.tools.get(name)
@@ -221,6 +411,27 @@ impl ToolRegistry {
         self.tools.values().collect()
     }
 
+    /// Render one line per tool for the system prompt
+    ///
+    /// Format: "name: description. Args: arg (type, required), ..."
+    pub fn prompt_descriptions(&self) -> Vec<String> {
+        let mut names = self.tool_names();
+        names.sort();
+
+        names
+            .iter()
+            .filter_map(|name| self.tools.get(name))
+            .map(|schema| {
+                let args = describe_parameters(&schema.parameters);
+                if args.is_empty() {
+                    format!("{}: {}", schema.name, schema.description)
+                } else {
+                    format!("{}: {}. Args: {}", schema.name, schema.description, args.join(", "))
+                }
+            })
+            .collect()
+    }
+
     /// Get read-only tool names
     pub fn read_only_tools(&self) -> Vec<String> {
         self.tools
@@ -250,6 +461,48 @@ impl ToolRegistry {
     }
 }
 
+/// Describe JSON Schema properties as "name (type, required|optional[, default X])"
+fn describe_parameters(parameters: &serde_json::Value) -> Vec<String> {
+    let required: Vec<&str> = parameters["required"]
+        .as_array()
+        .map(|arr| arr.iter().filter_map(|v| v.as_str()).collect())
+        .unwrap_or_default();
+
+    let Some(properties) = parameters["properties"].as_object() else {
+        return Vec::new();
+    };
+
+    // Required arguments first, then optional
+    let mut names: Vec<&String> = properties.keys().collect();
+    names.sort_by_key(|name| !required.contains(&name.as_str()));
+
+    names
+        .into_iter()
+        .map(|name| {
+            let prop = &properties[name];
+            let ty = prop["type"].as_str().unwrap_or("any");
+            let mut parts = vec![ty.to_string()];
+
+            if required.contains(&name.as_str()) {
+                parts.push("required".to_string());
+            } else {
+                parts.push("optional".to_string());
+            }
+
+            if let Some(values) = prop["enum"].as_array() {
+                let values: Vec<String> = values.iter().map(|v| v.to_string()).collect();
+                parts.push(format!("one of {}", values.join("|")));
+            }
+
+            if let Some(default) = prop.get("default") {
+                parts.push(format!("default {}", default));
+            }
+
+            format!("{} ({})", name, parts.join(", "))
+        })
+        .collect()
+}
+
 impl Default for ToolRegistry {
     fn default() -> Self {
         Self::new()
@@ -262,14 +515,14 @@ mod tests {
 
     #[test]
     fn test_registry_creation() {
-        let registry = ToolRegistry::new();
-        assert_eq!(registry.len(), 6);
+        let registry = ToolRegistry::with_profile(&ToolProfile::all_tools());
+        assert_eq!(registry.len(), 12);
         assert!(!registry.is_empty());
     }
 
     #[test]
     fn test_all_tools_registered() {
-        let registry = ToolRegistry::new();
+        let registry = ToolRegistry::with_profile(&ToolProfile::all_tools());
         
         assert!(registry.contains("list_dir"));
         assert!(registry.contains("read_file"));
@@ -277,6 +530,12 @@ mod tests {
         assert!(registry.contains("run_command"));
         assert!(registry.contains("system_info"));
         assert!(registry.contains("web_fetch"));
+        assert!(registry.contains("query_data"));
+        assert!(registry.contains("outline"));
+        assert!(registry.contains("run_tests"));
+        assert!(registry.contains("archive_list"));
+        assert!(registry.contains("archive_extract"));
+        assert!(registry.contains("archive_create"));
     }
 
     #[test]
@@ -290,14 +549,16 @@ mod tests {
 
     #[test]
     fn test_read_only_tools() {
-        let registry = ToolRegistry::new();
+        let registry = ToolRegistry::with_profile(&ToolProfile::all_tools());
         let read_only = registry.read_only_tools();
         
-        assert_eq!(read_only.len(), 4);
+        assert_eq!(read_only.len(), 7);
         assert!(read_only.contains(&"list_dir".to_string()));
         assert!(read_only.contains(&"read_file".to_string()));
         assert!(read_only.contains(&"system_info".to_string()));
         assert!(read_only.contains(&"web_fetch".to_string()));
+        assert!(read_only.contains(&"query_data".to_string()));
+        assert!(read_only.contains(&"outline".to_string()));
     }
 
     #[test]
@@ -305,25 +566,26 @@ mod tests {
         let registry = ToolRegistry::new();
         let write_tools = registry.write_tools();
         
-        assert_eq!(write_tools.len(), 2);
+        assert_eq!(write_tools.len(), 5);
         assert!(write_tools.contains(&"write_file".to_string()));
         assert!(write_tools.contains(&"run_command".to_string()));
+        assert!(write_tools.contains(&"run_tests".to_string()));
     }
 
     #[test]
     fn test_tool_names() {
-        let registry = ToolRegistry::new();
+        let registry = ToolRegistry::with_profile(&ToolProfile::all_tools());
         let names = registry.tool_names();
         
-        assert_eq!(names.len(), 6);
+        assert_eq!(names.len(), 12);
     }
 
     #[test]
     fn test_schemas() {
-        let registry = ToolRegistry::new();
+        let registry = ToolRegistry::with_profile(&ToolProfile::all_tools());
         let schemas = registry.schemas();
         
-        assert_eq!(schemas.len(), 6);
+        assert_eq!(schemas.len(), 12);
         
         for schema in schemas {
             assert!(!schema.name.is_empty());
@@ -331,6 +593,43 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_offline_profile_omits_web_fetch() {
+        let registry = ToolRegistry::new();
+
+        assert_eq!(registry.len(), 11);
+        assert!(!registry.contains("web_fetch"));
+        assert!(registry
+            .prompt_descriptions()
+            .iter()
+            .all(|line| !line.starts_with("web_fetch")));
+    }
+
+    #[test]
+    fn test_profile_disables_tool() {
+        let profile = ToolProfile::all_tools().disable("run_command");
+        let registry = ToolRegistry::with_profile(&profile);
+
+        assert!(!registry.contains("run_command"));
+        assert!(registry.contains("web_fetch"));
+        let write_tools = registry.write_tools();
+        assert_eq!(write_tools.len(), 4);
+        assert!(!write_tools.contains(&"run_command".to_string()));
+    }
+
+    #[test]
+    fn test_prompt_descriptions() {
+        let registry = ToolRegistry::with_profile(&ToolProfile::all_tools());
+        let lines = registry.prompt_descriptions();
+
+        assert_eq!(lines.len(), 12);
+        let read_file = lines.iter().find(|l| l.starts_with("read_file:")).unwrap();
+        assert!(read_file.contains("path (string, required)"));
+
+        let list_dir = lines.iter().find(|l| l.starts_with("list_dir:")).unwrap();
+        assert!(list_dir.contains("recursive (boolean, optional, default false)"));
+    }
+
     #[test]
     fn test_nonexistent_tool() {
         let registry = ToolRegistry::new();
@@ -5,6 +5,7 @@
 
 use crate::errors::Result;
 use crate::tools::executor::ParallelExecutor;
+use crate::tools::profile::ToolProfile;
 use crate::tools::registry::ToolRegistry;
 use crate::tools::security::PathJail;
 use crate::tools::types::{ToolContext, ToolResult};
@@ -28,6 +29,24 @@ impl ToolRuntime {
         })
     }
 
+    /// Create tool runtime exposing only tools enabled by the profile
+    pub fn with_profile(
+        working_dir: impl AsRef<std::path::Path>,
+        profile: ToolProfile,
+    ) -> Result<Self> {
+        Self::with_context_and_profile(ToolContext::new(working_dir.as_ref().to_path_buf()), profile)
+    }
+
+    /// Create tool runtime with custom limits, jailed to the context's working directory
+    pub fn with_context_and_profile(context: ToolContext, profile: ToolProfile) -> Result<Self> {
+        let jail = PathJail::new(&context.working_dir)?;
+        let executor = ParallelExecutor::with_profile(jail, context, profile);
+
+        Ok(Self {
+            executor: Arc::new(executor),
+        })
+    }
+
     /// Create tool runtime with custom context
     pub fn with_context(jail: PathJail, context: ToolContext) -> Self {
         let executor = ParallelExecutor::new(jail, context);
@@ -54,11 +73,34 @@ impl ToolRuntime {
         self.executor.execute(tool, args).await
     }
 
+    /// Execute a batch of tool calls (parallel reads, ordered writes)
+    pub async fn execute_batch(
+        &self,
+        calls: &[(String, serde_json::Value)],
+    ) -> Vec<Result<ToolResult>> {
+        self.executor.execute_batch(calls).await
+    }
+
+    /// Get working directory (jail root)
+    pub fn working_dir(&self) -> &std::path::Path {
+        self.executor.working_dir()
+    }
+
+    /// Check if tool is read-only (safe for parallel execution)
+    pub fn is_read_only(&self, tool: &str) -> bool {
+        self.executor.is_read_only(tool)
+    }
+
     /// Get tool registry
     pub fn get_registry(&self) -> &ToolRegistry {
         self.executor.registry()
     }
 
+    /// Get tool profile
+    pub fn profile(&self) -> &ToolProfile {
+        self.executor.profile()
+    }
+
     /// Get all tool names
     pub fn tool_names(&self) -> Vec<String> {
         self.get_registry().tool_names()
@@ -94,7 +136,9 @@ mod tests {
     #[test]
     fn test_runtime_creation() {
         let (runtime, _temp) = setup_runtime();
-        assert_eq!(runtime.tool_names().len(), 6);
+        // Network tools are opt-in
+        assert_eq!(runtime.tool_names().len(), 11);
+        assert!(!runtime.has_tool("web_fetch"));
     }
 
     #[test]
@@ -102,7 +146,7 @@ mod tests {
         let (runtime, _temp) = setup_runtime();
         
         let registry = runtime.get_registry();
-        assert_eq!(registry.len(), 6);
+        assert_eq!(registry.len(), 11);
     }
 
     #[test]
@@ -121,13 +165,23 @@ mod tests {
         let read_only = runtime.read_only_tools();
         let write = runtime.write_tools();
         
-        assert_eq!(read_only.len(), 4);
-        assert_eq!(write.len(), 2);
+        assert_eq!(read_only.len(), 6);
+        assert_eq!(write.len(), 5);
         
         assert!(read_only.contains(&"list_dir".to_string()));
         assert!(write.contains(&"write_file".to_string()));
     }
 
+    #[test]
+    fn test_runtime_with_offline_profile() {
+        let temp_dir = TempDir::new().unwrap();
+        let runtime = ToolRuntime::with_profile(temp_dir.path(), ToolProfile::new()).unwrap();
+
+        assert_eq!(runtime.tool_names().len(), 11);
+        assert!(!runtime.has_tool("web_fetch"));
+        assert!(!runtime.profile().is_online());
+    }
+
     #[tokio::test]
     async fn test_execute_list_dir() {
         let (runtime, _temp) = setup_runtime();
@@ -0,0 +1,259 @@
+//! Tool argument validation against the registered JSON Schemas
+//!
+//! Checks the subset of JSON Schema the registry uses (`type`, `required`,
+//! `enum`, `minimum`/`maximum`, array `items`) before a tool is dispatched,
+//! so a missing argument is reported to the model instead of silently
+//! becoming an empty string. Common model mistakes are coerced rather than
+//! rejected:
+//! - numbers and booleans sent as strings (`"30"`, `"true"`)
+//! - whole floats for integers (`30.0`)
+//! - a single value where an array is expected
+//! - enum values in the wrong case (`"get"` for `"GET"`)
+//! - `null` for optional arguments (treated as absent)
+
+use crate::errors::{AgentError, Result};
+use serde_json::{Map, Value};
+
+/// Validate `args` against a tool's parameter schema
+///
+/// Returns the arguments with coercions applied. All problems are reported
+/// at once; unknown arguments are only mentioned alongside other errors
+/// (they usually explain them, e.g. `file` instead of `path`).
+pub fn validate_args(tool: &str, parameters: &Value, args: &Value) -> Result<Value> {
+    let mut args = match args {
+        Value::Object(map) => map.clone(),
+        Value::Null => Map::new(),
+        other => {
+            return Err(AgentError::InvalidArguments {
+                tool: tool.to_string(),
+                errors: vec![format!("arguments must be an object, got {}", describe(other))],
+            })
+        }
+    };
+    args.retain(|_, value| !value.is_null());
+
+    let empty = Map::new();
+    let properties = parameters["properties"].as_object().unwrap_or(&empty);
+    let mut errors = Vec::new();
+
+    if let Some(required) = parameters["required"].as_array() {
+        for name in required.iter().filter_map(Value::as_str) {
+            if !args.contains_key(name) {
+                let hint = properties
+                    .get(name)
+                    .map(|p| format!(" ({})", type_name(p)))
+                    .unwrap_or_default();
+                errors.push(format!("missing required argument '{}'{}", name, hint));
+            }
+        }
+    }
+
+    for (name, value) in args.iter_mut() {
+        if let Some(schema) = properties.get(name) {
+            check_value(name, schema, value, &mut errors);
+        }
+    }
+
+    if errors.is_empty() {
+        return Ok(Value::Object(args));
+    }
+
+    let unknown: Vec<&str> = args
+        .keys()
+        .filter(|name| !properties.contains_key(*name))
+        .map(String::as_str)
+        .collect();
+    if !unknown.is_empty() {
+        let mut known: Vec<&str> = properties.keys().map(String::as_str).collect();
+        known.sort();
+        errors.push(format!(
+            "unknown argument(s) {}; expected {}",
+            unknown.join(", "),
+            known.join(", ")
+        ));
+    }
+
+    Err(AgentError::InvalidArguments {
+        tool: tool.to_string(),
+        errors,
+    })
+}
+
+/// Check (and coerce) one value against its property schema
+fn check_value(name: &str, schema: &Value, value: &mut Value, errors: &mut Vec<String>) {
+    if let Some(expected) = schema["type"].as_str() {
+        match coerce(expected, value) {
+            Some(coerced) => *value = coerced,
+            None => {
+                errors.push(format!(
+                    "argument '{}' must be {}, got {}",
+                    name,
+                    type_name(schema),
+                    describe(value)
+                ));
+                return;
+            }
+        }
+    }
+
+    if let Some(allowed) = schema["enum"].as_array() {
+        if !allowed.contains(value) {
+            let matched = value.as_str().and_then(|s| {
+                allowed
+                    .iter()
+                    .find(|a| a.as_str().is_some_and(|a| a.eq_ignore_ascii_case(s)))
+            });
+            match matched {
+                Some(allowed_value) => *value = allowed_value.clone(),
+                None => errors.push(format!(
+                    "argument '{}' must be one of {}, got {}",
+                    name,
+                    allowed.iter().map(Value::to_string).collect::<Vec<_>>().join(", "),
+                    value
+                )),
+            }
+        }
+    }
+
+    if let Some(n) = value.as_f64() {
+        if let Some(min) = schema["minimum"].as_f64().filter(|min| n < *min) {
+            errors.push(format!("argument '{}' must be >= {}, got {}", name, min, value));
+        }
+        if let Some(max) = schema["maximum"].as_f64().filter(|max| n > *max) {
+            errors.push(format!("argument '{}' must be <= {}, got {}", name, max, value));
+        }
+    }
+
+    if let (Some(items), Value::Array(elements)) = (schema.get("items"), value) {
+        for (i, element) in elements.iter_mut().enumerate() {
+            check_value(&format!("{}[{}]", name, i), items, element, errors);
+        }
+    }
+}
+
+/// Convert `value` to the JSON Schema type `expected`, if it is (or
+/// plausibly means) one
+fn coerce(expected: &str, value: &Value) -> Option<Value> {
+    match (expected, value) {
+        ("string", Value::String(_))
+        | ("boolean", Value::Bool(_))
+        | ("object", Value::Object(_))
+        | ("array", Value::Array(_)) => Some(value.clone()),
+        ("number", Value::Number(_)) => Some(value.clone()),
+        ("integer", Value::Number(n)) => {
+            if n.is_i64() || n.is_u64() {
+                Some(value.clone())
+            } else {
+                n.as_f64()
+                    .filter(|f| f.fract() == 0.0)
+                    .map(|f| Value::from(f as i64))
+            }
+        }
+        ("integer", Value::String(s)) => s.trim().parse::<i64>().ok().map(Value::from),
+        ("number", Value::String(s)) => s.trim().parse::<f64>().ok().map(Value::from),
+        ("boolean", Value::String(s)) => match s.trim().to_ascii_lowercase().as_str() {
+            "true" => Some(Value::Bool(true)),
+            "false" => Some(Value::Bool(false)),
+            _ => None,
+        },
+        ("string", Value::Number(_) | Value::Bool(_)) => Some(Value::String(value.to_string())),
+        ("array", Value::String(s)) => match serde_json::from_str::<Value>(s) {
+            Ok(parsed @ Value::Array(_)) => Some(parsed),
+            _ => Some(Value::Array(vec![value.clone()])),
+        },
+        ("array", Value::Number(_) | Value::Bool(_)) => Some(Value::Array(vec![value.clone()])),
+        _ => None,
+    }
+}
+
+/// Schema type for error messages, e.g. "an array of string"
+fn type_name(schema: &Value) -> String {
+    match schema["type"].as_str() {
+        Some("array") => match schema["items"]["type"].as_str() {
+            Some(item) => format!("an array of {}", item),
+            None => "an array".to_string(),
+        },
+        Some(t @ ("integer" | "object")) => format!("an {}", t),
+        Some(t) => format!("a {}", t),
+        None => "a value".to_string(),
+    }
+}
+
+/// Short description of a JSON value for error messages
+fn describe(value: &Value) -> String {
+    let kind = match value {
+        Value::Null => return "null".to_string(),
+        Value::Bool(_) => "boolean",
+        Value::Number(_) => "number",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    };
+    let text = value.to_string();
+    if text.chars().count() > 40 {
+        format!("{} {}...", kind, text.chars().take(40).collect::<String>())
+    } else {
+        format!("{} {}", kind, text)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tools::{ToolProfile, ToolRegistry};
+    use serde_json::json;
+
+    fn validate(tool: &str, args: Value) -> Result<Value> {
+        let registry = ToolRegistry::with_profile(&ToolProfile::all_tools());
+        validate_args(tool, &registry.get(tool).unwrap().parameters, &args)
+    }
+
+    #[test]
+    fn test_coerces_common_mistakes() {
+        let args = validate(
+            "run_command",
+            json!({"command": "ls", "args": "-la", "timeout_seconds": "30"}),
+        )
+        .unwrap();
+        assert_eq!(args, json!({"command": "ls", "args": ["-la"], "timeout_seconds": 30}));
+
+        let args = validate("web_fetch", json!({"url": "http://x", "method": "post", "timeout_seconds": 5.0})).unwrap();
+        assert_eq!(args["method"], "POST");
+        assert_eq!(args["timeout_seconds"], 5);
+
+        let args = validate("list_dir", json!({"path": ".", "recursive": "TRUE"})).unwrap();
+        assert_eq!(args["recursive"], true);
+
+        // null optional args are dropped
+        let args = validate("read_file", json!({"path": "a.txt", "start_line": null})).unwrap();
+        assert_eq!(args, json!({"path": "a.txt"}));
+    }
+
+    #[test]
+    fn test_reports_all_errors_precisely() {
+        let err = validate(
+            "web_fetch",
+            json!({"uri": "http://x", "method": "PUT", "timeout_seconds": 500}),
+        )
+        .unwrap_err()
+        .to_string();
+
+        assert!(err.contains("'web_fetch'"), "{}", err);
+        assert!(err.contains("missing required argument 'url' (a string)"), "{}", err);
+        assert!(err.contains("'method' must be one of \"GET\", \"POST\", got \"PUT\""), "{}", err);
+        assert!(err.contains("'timeout_seconds' must be <= 120, got 500"), "{}", err);
+        assert!(err.contains("unknown argument(s) uri"), "{}", err);
+
+        let err = validate("read_file", json!({"path": "a", "start_line": "first"})).unwrap_err();
+        assert!(err.to_string().contains("'start_line' must be an integer, got string \"first\""));
+
+        let err = validate("archive_create", json!({"path": "a.zip", "sources": [1, {"x": 1}]})).unwrap_err();
+        assert!(err.to_string().contains("'sources[1]' must be a string, got object"));
+    }
+
+    #[test]
+    fn test_non_object_args_are_rejected() {
+        let err = validate("list_dir", json!(["."])).unwrap_err();
+        assert!(err.to_string().contains("arguments must be an object"));
+    }
+}
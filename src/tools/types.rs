@@ -72,6 +72,16 @@ impl ToolResult {
             exit_code: Some(exit_code),
         }
     }
+
+    /// Text shown to the model: the output, prefixed with the error on failure
+    pub fn feedback_text(&self) -> String {
+        if self.success {
+            self.output.clone()
+        } else {
+            let error = self.error.as_deref().unwrap_or_default();
+            format!("ERROR: {}\n{}", error, self.output).trim_end().to_string()
+        }
+    }
 }
 
 /// Tool execution context with security and resource bounds
@@ -86,6 +96,9 @@ pub struct ToolContext {
     /// Maximum output size (bytes)
     pub max_output_size: usize,
     
+    /// Maximum total size of files extracted from or packed into archives (bytes)
+    pub max_extract_size: usize,
+    
     /// Enable verbose logging
     pub verbose: bool,
 }
@@ -96,6 +109,7 @@ impl Default for ToolContext {
             working_dir: std::env::current_dir().unwrap_or_else(|_| "/tmp".into()),
             timeout: Duration::from_secs(60),
             max_output_size: 2_097_152, // 2MB
+            max_extract_size: 104_857_600, // 100MB
             verbose: false,
         }
     }
@@ -122,6 +136,12 @@ impl ToolContext {
         self
     }
 
+    /// Set max archive extraction size
+    pub fn with_max_extract_size(mut self, size: usize) -> Self {
+        self.max_extract_size = size;
+        self
+    }
+
     /// Enable verbose mode
     pub fn with_verbose(mut self, verbose: bool) -> Self {
         self.verbose = verbose;
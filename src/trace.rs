@@ -0,0 +1,394 @@
+//! Record/replay traces of agent runs
+//!
+//! A trace is a JSONL file with one `TraceEvent` per line: the initial
+//! memory, every model request/response and every tool call/result of an
+//! `execute_agent_task` run. Replaying a trace re-drives the orchestrator
+//! from the recorded model outputs, so parser, compression and validation
+//! bugs can be reproduced without a model server. Tools are either mocked
+//! from the recorded results or re-executed live.
+
+use crate::errors::{AgentError, Result};
+use crate::tools::runtime::ToolRuntime;
+use crate::tools::types::ToolResult;
+use crate::types::MemoryEntry;
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::fs::File;
+use std::io::{BufRead, BufReader, BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+/// One line of a trace file
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum TraceEvent {
+    /// Run configuration and memory before the first iteration
+    RunStarted {
+        task: String,
+        model: String,
+        max_iterations: usize,
+        working_dir: PathBuf,
+        memory: Vec<MemoryEntry>,
+        timestamp: u64,
+        /// Sub-goals were delegated to sub-agents (`--delegate`)
+        #[serde(default)]
+        delegate: bool,
+    },
+
+    /// Prompt sent to the model
+    ModelRequest { iteration: usize, prompt: String },
+
+    /// Full (accumulated) model response
+    ModelResponse { iteration: usize, response: String },
+
+    /// Tool invocation
+    ToolCall { tool: String, args: serde_json::Value },
+
+    /// Tool outcome; `error` is set when the executor itself failed
+    ToolResult {
+        tool: String,
+        result: Option<ToolResult>,
+        error: Option<String>,
+    },
+
+    /// Run outcome
+    RunFinished {
+        success: bool,
+        iterations: usize,
+        output: String,
+    },
+}
+
+/// Appends trace events to a JSONL file
+///
+/// Each event is flushed immediately so a crashed run still leaves a usable
+/// trace behind.
+pub struct TraceRecorder {
+    writer: Mutex<BufWriter<File>>,
+    path: PathBuf,
+}
+
+impl TraceRecorder {
+    /// Create (or truncate) a trace file
+    pub fn create(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref().to_path_buf();
+        if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            std::fs::create_dir_all(parent)?;
+        }
+        let file = File::create(&path)?;
+        Ok(Self {
+            writer: Mutex::new(BufWriter::new(file)),
+            path,
+        })
+    }
+
+    /// Trace file path
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Append one event
+    pub fn record(&self, event: &TraceEvent) -> Result<()> {
+        let line = serde_json::to_string(event)?;
+        let mut writer = self.writer.lock().unwrap();
+        writeln!(writer, "{}", line)?;
+        writer.flush()?;
+        Ok(())
+    }
+}
+
+/// Recorded tool outcome: tool name, result, executor error
+type RecordedToolResult = (String, Option<ToolResult>, Option<String>);
+
+/// A loaded trace, consumed in order during replay
+pub struct TraceReplayer {
+    start: Option<TraceEvent>,
+    responses: Mutex<VecDeque<(usize, String)>>,
+    prompts: Vec<(usize, String)>,
+    tool_results: Mutex<Vec<Option<RecordedToolResult>>>,
+    mock_tools: bool,
+}
+
+impl TraceReplayer {
+    /// Load a trace file
+    ///
+    /// With `mock_tools`, tool calls return the recorded results instead of
+    /// executing.
+    pub fn load(path: impl AsRef<Path>, mock_tools: bool) -> Result<Self> {
+        let file = File::open(path.as_ref())?;
+        let mut events = Vec::new();
+        for (index, line) in BufReader::new(file).lines().enumerate() {
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            let event: TraceEvent = serde_json::from_str(&line).map_err(|e| {
+                AgentError::Generic(format!("Invalid trace event on line {}: {}", index + 1, e))
+            })?;
+            events.push(event);
+        }
+        Ok(Self::from_events(events, mock_tools))
+    }
+
+    /// Build a replayer from already parsed events
+    pub fn from_events(events: Vec<TraceEvent>, mock_tools: bool) -> Self {
+        let mut start = None;
+        let mut responses = VecDeque::new();
+        let mut prompts = Vec::new();
+        let mut tool_results = Vec::new();
+
+        for event in events {
+            match event {
+                TraceEvent::RunStarted { .. } => {
+                    if start.is_none() {
+                        start = Some(event);
+                    }
+                }
+                TraceEvent::ModelRequest { iteration, prompt } => prompts.push((iteration, prompt)),
+                TraceEvent::ModelResponse { iteration, response } => {
+                    responses.push_back((iteration, response))
+                }
+                TraceEvent::ToolResult { tool, result, error } => {
+                    tool_results.push(Some((tool, result, error)))
+                }
+                TraceEvent::ToolCall { .. } | TraceEvent::RunFinished { .. } => {}
+            }
+        }
+
+        Self {
+            start,
+            responses: Mutex::new(responses),
+            prompts,
+            tool_results: Mutex::new(tool_results),
+            mock_tools,
+        }
+    }
+
+    /// The `RunStarted` event, if the trace has one
+    pub fn run_started(&self) -> Option<&TraceEvent> {
+        self.start.as_ref()
+    }
+
+    /// Whether tools are mocked from the trace
+    pub fn mocks_tools(&self) -> bool {
+        self.mock_tools
+    }
+
+    /// Next recorded model response, or `None` once the trace is exhausted
+    pub fn next_response(&self) -> Option<String> {
+        self.responses.lock().unwrap().pop_front().map(|(_, response)| response)
+    }
+
+    /// Whether `prompt` matches the prompt recorded for `iteration`
+    ///
+    /// Returns `true` when nothing was recorded for that iteration.
+    pub fn prompt_matches(&self, iteration: usize, prompt: &str) -> bool {
+        self.prompts
+            .iter()
+            .find(|(i, _)| *i == iteration)
+            .is_none_or(|(_, recorded)| recorded == prompt)
+    }
+
+    /// Take the earliest unused recorded result for `tool`
+    fn take_tool_result(&self, tool: &str) -> Result<ToolResult> {
+        let mut results = self.tool_results.lock().unwrap();
+        let slot = results
+            .iter_mut()
+            .find(|slot| matches!(slot, Some((name, _, _)) if name == tool));
+
+        match slot.and_then(Option::take) {
+            Some((_, Some(result), _)) => Ok(result),
+            Some((_, None, error)) => Err(AgentError::Generic(
+                error.unwrap_or_else(|| "Recorded tool error".to_string()),
+            )),
+            None => Err(AgentError::Generic(format!(
+                "No recorded result for tool '{}' left in trace",
+                tool
+            ))),
+        }
+    }
+}
+
+/// How an agent run interacts with traces
+#[derive(Default)]
+pub enum TraceMode {
+    /// No tracing
+    #[default]
+    Off,
+
+    /// Record events to a trace file
+    Record(TraceRecorder),
+
+    /// Replay model outputs (and optionally tool results) from a trace
+    Replay(TraceReplayer),
+}
+
+impl TraceMode {
+    /// Record an event (no-op unless recording)
+    ///
+    /// Write failures are reported on stderr rather than failing the run.
+    pub fn record(&self, event: TraceEvent) {
+        if let Self::Record(recorder) = self {
+            if let Err(e) = recorder.record(&event) {
+                eprintln!("Warning: failed to write trace event: {}", e);
+            }
+        }
+    }
+
+    /// Replayer, when replaying
+    pub fn replayer(&self) -> Option<&TraceReplayer> {
+        match self {
+            Self::Replay(replayer) => Some(replayer),
+            _ => None,
+        }
+    }
+
+    /// Execute a tool, recording or mocking it as configured
+    pub async fn execute_tool(
+        &self,
+        runtime: &ToolRuntime,
+        tool: &str,
+        args: &serde_json::Value,
+    ) -> Result<ToolResult> {
+        if let Self::Replay(replayer) = self {
+            if replayer.mock_tools {
+                return replayer.take_tool_result(tool);
+            }
+        }
+
+        self.record(TraceEvent::ToolCall {
+            tool: tool.to_string(),
+            args: args.clone(),
+        });
+        let result = runtime.execute(tool, args).await;
+        self.record_result(tool, &result);
+        result
+    }
+
+    /// Execute a batch of tool calls, recording or mocking them as configured
+    pub async fn execute_batch(
+        &self,
+        runtime: &ToolRuntime,
+        calls: &[(String, serde_json::Value)],
+    ) -> Vec<Result<ToolResult>> {
+        if let Self::Replay(replayer) = self {
+            if replayer.mock_tools {
+                return calls
+                    .iter()
+                    .map(|(tool, _)| replayer.take_tool_result(tool))
+                    .collect();
+            }
+        }
+
+        for (tool, args) in calls {
+            self.record(TraceEvent::ToolCall {
+                tool: tool.clone(),
+                args: args.clone(),
+            });
+        }
+        let results = runtime.execute_batch(calls).await;
+        for ((tool, _), result) in calls.iter().zip(&results) {
+            self.record_result(tool, result);
+        }
+        results
+    }
+
+    fn record_result(&self, tool: &str, result: &Result<ToolResult>) {
+        if !matches!(self, Self::Record(_)) {
+            return;
+        }
+        let (result, error) = match result {
+            Ok(output) => (Some(output.clone()), None),
+            Err(e) => (None, Some(e.to_string())),
+        };
+        self.record(TraceEvent::ToolResult {
+            tool: tool.to_string(),
+            result,
+            error,
+        });
+    }
+}
+
+/// Seconds since the Unix epoch, for trace timestamps
+pub fn now_secs() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap()
+        .as_secs()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_record_and_load_roundtrip() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("run.jsonl");
+
+        let recorder = TraceRecorder::create(&path).unwrap();
+        recorder
+            .record(&TraceEvent::ModelRequest { iteration: 1, prompt: "p1".to_string() })
+            .unwrap();
+        recorder
+            .record(&TraceEvent::ModelResponse { iteration: 1, response: "r1".to_string() })
+            .unwrap();
+        recorder
+            .record(&TraceEvent::ModelResponse { iteration: 2, response: "r2".to_string() })
+            .unwrap();
+
+        let replayer = TraceReplayer::load(&path, true).unwrap();
+        assert!(replayer.prompt_matches(1, "p1"));
+        assert!(!replayer.prompt_matches(1, "changed"));
+        assert!(replayer.prompt_matches(5, "anything"));
+        assert_eq!(replayer.next_response().as_deref(), Some("r1"));
+        assert_eq!(replayer.next_response().as_deref(), Some("r2"));
+        assert_eq!(replayer.next_response(), None);
+    }
+
+    #[test]
+    fn test_mocked_tool_results_are_consumed_per_tool() {
+        let ok = |tool: &str, output: &str| TraceEvent::ToolResult {
+            tool: tool.to_string(),
+            result: Some(ToolResult::success(tool.to_string(), output.to_string(), Duration::ZERO)),
+            error: None,
+        };
+        let replayer = TraceReplayer::from_events(
+            vec![
+                ok("list_dir", "a.rs"),
+                ok("read_file", "fn main() {}"),
+                TraceEvent::ToolResult {
+                    tool: "list_dir".to_string(),
+                    result: None,
+                    error: Some("Path escapes jail".to_string()),
+                },
+            ],
+            true,
+        );
+
+        assert_eq!(replayer.take_tool_result("read_file").unwrap().output, "fn main() {}");
+        assert_eq!(replayer.take_tool_result("list_dir").unwrap().output, "a.rs");
+        let err = replayer.take_tool_result("list_dir").unwrap_err();
+        assert!(err.to_string().contains("Path escapes jail"));
+        assert!(replayer.take_tool_result("list_dir").is_err());
+    }
+
+    #[test]
+    fn test_run_started_without_delegate_flag() {
+        let line = r#"{"event": "run_started", "task": "t", "model": "m", "max_iterations": 5, "working_dir": "/tmp", "memory": [], "timestamp": 0}"#;
+        let event: TraceEvent = serde_json::from_str(line).unwrap();
+        assert!(matches!(event, TraceEvent::RunStarted { delegate: false, .. }));
+    }
+
+    #[test]
+    fn test_rejects_malformed_lines() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("bad.jsonl");
+        std::fs::write(&path, "{\"event\": \"model_response\", \"iteration\": 1, \"response\": \"ok\"}\nnot json\n").unwrap();
+
+        let err = TraceReplayer::load(&path, true).err().unwrap();
+        assert!(err.to_string().contains("line 2"));
+    }
+}
@@ -3,6 +3,7 @@
 //! This module provides types for representing the results of agent task execution,
 //! enabling consistent behavior across different execution contexts (CLI and REPL modes).
 
+use crate::advisor::ModelEscalation;
 use std::time::Duration;
 
 /// Result of executing a task through the agent
@@ -32,6 +33,9 @@ pub struct TaskExecutionResult {
     
     /// Final validation score (0.0 - 1.0)
     pub validation_score: f64,
+    
+    /// Mid-task switches to a stronger model
+    pub escalations: Vec<ModelEscalation>,
 }
 
 impl TaskExecutionResult {
@@ -51,6 +55,7 @@ impl TaskExecutionResult {
             early_success: false,
             files_touched,
             validation_score,
+            escalations: Vec::new(),
         }
     }
 
@@ -64,6 +69,7 @@ impl TaskExecutionResult {
             early_success: false,
             files_touched: Vec::new(),
             validation_score: 0.0,
+            escalations: Vec::new(),
         }
     }
 
@@ -73,6 +79,12 @@ impl TaskExecutionResult {
         self
     }
 
+    /// Record the model escalations made during the task
+    pub fn with_escalations(mut self, escalations: Vec<ModelEscalation>) -> Self {
+        self.escalations = escalations;
+        self
+    }
+
     /// Get a human-readable summary of the execution
     pub fn summary(&self) -> String {
         let status = if self.success { "Success" } else { "Failed" };
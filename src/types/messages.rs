@@ -22,6 +22,14 @@ pub enum AgentMsg {
         args: HashMap<String, serde_json::Value>,
     },
 
+    /// Model requests several tool executions in one turn
+    ///
+    /// Consecutive read-only calls are dispatched in parallel; write
+    /// calls run in order. All results are fed back together.
+    ToolCalls {
+        calls: Vec<ToolCallRequest>,
+    },
+
     /// Model asks user for clarification
     Ask {
         question: String,
@@ -40,6 +48,14 @@ pub enum AgentMsg {
     },
 }
 
+/// Single tool invocation inside a `tool_calls` message
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ToolCallRequest {
+    pub tool: String,
+    #[serde(default)]
+    pub args: HashMap<String, serde_json::Value>,
+}
+
 /// Memory entry types stored in conversation history
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(tag = "entry_type", rename_all = "snake_case")]
@@ -167,6 +183,25 @@ mod tests {
         assert_eq!(msg, deserialized);
     }
 
+    #[test]
+    fn test_agent_msg_tool_calls() {
+        let json = r#"{"type": "tool_calls", "calls": [
+            {"tool": "read_file", "args": {"path": "a.rs"}},
+            {"tool": "system_info"}
+        ]}"#;
+
+        let msg: AgentMsg = serde_json::from_str(json).unwrap();
+        match msg {
+            AgentMsg::ToolCalls { calls } => {
+                assert_eq!(calls.len(), 2);
+                assert_eq!(calls[0].tool, "read_file");
+                assert_eq!(calls[0].args["path"], "a.rs");
+                assert!(calls[1].args.is_empty());
+            }
+            other => panic!("Expected tool_calls, got {:?}", other),
+        }
+    }
+
     #[test]
     fn test_memory_entry_token_estimation() {
         let entry = MemoryEntry::UserGoal {
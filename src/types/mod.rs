@@ -5,7 +5,7 @@
 pub mod messages;
 
 // Re-export commonly used types
-pub use messages::{AgentMsg, MemoryEntry};
+pub use messages::{AgentMsg, MemoryEntry, ToolCallRequest};
 
 // Task execution result types
 pub mod execution;
@@ -2,6 +2,7 @@
 //! Provides multi-stage validation pipeline with weighted scoring
 
 use crate::validation::types::{ValidationCheck, ValidationResult, ValidationState};
+use crate::tools::implementations::testing::TestReport;
 use crate::tools::types::ToolResult;
 use std::path::Path;
 use std::time::Instant;
@@ -27,6 +28,9 @@ pub struct ValidatorConfig {
     /// Enable regression testing
     pub check_regression: bool,
     
+    /// Enable test result check (only applies when run_tests was used)
+    pub check_test_results: bool,
+    
     /// Timeout for validation (milliseconds)
     pub timeout_ms: u64,
 }
@@ -40,6 +44,7 @@ impl Default for ValidatorConfig {
             check_content_quality: true,
             check_side_effects: true,
             check_regression: true,
+            check_test_results: true,
             timeout_ms: 50,
         }
     }
@@ -91,6 +96,13 @@ impl TaskValidator {
             checks.push(self.check_regression(tool_results));
         }
         
+        // Stage 6: Test Results (weight: 0.30, only when run_tests was used)
+        if self.config.check_test_results {
+            if let Some(check) = self.check_test_results(tool_results) {
+                checks.push(check);
+            }
+        }
+        
         let elapsed_ms = start.elapsed().as_millis() as u64;
         
         // Check timeout
@@ -255,6 +267,36 @@ impl TaskValidator {
         check
     }
     
+    /// Stage 6: Check the latest run_tests report passed
+    ///
+    /// Returns None when no run_tests report is available, so tasks that
+    /// never ran tests are scored on the other stages only.
+    fn check_test_results(&self, tool_results: &[ToolResult]) -> Option<ValidationCheck> {
+        let start = Instant::now();
+        
+        let report = tool_results
+            .iter()
+            .rev()
+            .filter(|r| r.tool == "run_tests")
+            .find_map(|r| TestReport::from_output(&r.output))?;
+        
+        let mut check = ValidationCheck::new("test_results".to_string(), 0.30);
+        
+        if report.all_passed() {
+            check.pass();
+        } else {
+            let names: Vec<&str> = report.failures.iter().map(|f| f.name.as_str()).collect();
+            check.fail(format!(
+                "{} test(s) failing: {}",
+                report.failed.max(report.failures.len()),
+                names.join(", ")
+            ));
+        }
+        
+        check.execution_time_ms = start.elapsed().as_millis() as u64;
+        Some(check)
+    }
+    
     // Helper methods
     
     /// Check if file exists
@@ -508,6 +550,7 @@ mod tests {
             check_content_quality: false,
             check_side_effects: false,
             check_regression: false,
+            check_test_results: false,
             timeout_ms: 100,
         };
         
@@ -571,6 +614,42 @@ mod tests {
         assert!(validation.timestamp.elapsed().is_ok(), "Should have valid timestamp");
     }
     
+    #[test]
+    fn test_test_results_check() {
+        use crate::tools::implementations::testing::{TestFailure, TestFramework};
+        
+        let validator = TaskValidator::new();
+        let mut report = TestReport::new(TestFramework::Cargo);
+        report.passed = 3;
+        let passing = serde_json::to_string(&report).unwrap();
+        
+        report.failed = 1;
+        report.exit_code = 101;
+        report.failures.push(TestFailure {
+            name: "parser::tests::bad".to_string(),
+            message: "assertion failed".to_string(),
+        });
+        let failing = serde_json::to_string(&report).unwrap();
+        
+        // No run_tests result: stage is skipped
+        let validation = validator.validate(&[create_test_result("read_file", "content here", true)], &[], 1);
+        assert!(validation.checks.iter().all(|c| c.name != "test_results"));
+        
+        // Latest report wins
+        let results = vec![
+            create_test_result("run_tests", &failing, false),
+            create_test_result("run_tests", &passing, true),
+        ];
+        let validation = validator.validate(&results, &[], 1);
+        assert!(validation.checks.iter().find(|c| c.name == "test_results").unwrap().passed);
+        
+        let results = vec![create_test_result("run_tests", &failing, false)];
+        let validation = validator.validate(&results, &[], 1);
+        let check = validation.checks.iter().find(|c| c.name == "test_results").unwrap();
+        assert!(!check.passed);
+        assert!(check.failure_reason.as_ref().unwrap().contains("parser::tests::bad"));
+    }
+    
     #[test]
     fn test_check_weights_sum() {
         let validator = TaskValidator::new();